@@ -0,0 +1,60 @@
+// Crawls a site from another program: `cargo run --example embedded -- https://example.com`.
+// Needs Redis as configured for the `spider` binary (spider.toml, env vars or defaults).
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use spider::Spider;
+use spider::config::{ConfigArgs, SpiderConfig};
+use spider::crawler::events::CrawlEvent;
+use spider::crawler::seeds::SeedSource;
+use spider::logging::{init_logging, LogFormat};
+
+const MAX_PAGES: usize = 25;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    init_logging(LogFormat::Compact, None).map_err(anyhow::Error::msg)?;
+
+    let url = std::env::args().nth(1).unwrap_or_else(|| "https://example.com".to_string());
+    let mut config = SpiderConfig::load(&ConfigArgs::default()).map_err(|errors| anyhow::anyhow!(errors.join("; ")))?;
+    config.scope.starting_url = None;
+    config.report.output_dir = String::new();
+
+    let fetched = Arc::new(AtomicUsize::new(0));
+    let counter = fetched.clone();
+
+    let spider = Spider::builder()
+        .config(config)
+        .seed(SeedSource::Url(url))
+        .job("embedded-example")
+        .on_event(move |event| {
+            if let CrawlEvent::Fetched { .. } = event {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+        .build()?;
+
+    // Watch the events from another task and stop once enough pages were fetched.
+    let mut events = spider.events();
+    let handle = spider.clone();
+    tokio::spawn(async move {
+        while let Some(event) = events.next().await {
+            match &event {
+                CrawlEvent::Fetched { url, status, duration_ms, .. } => println!("{} {} ({} ms)", status, url, duration_ms),
+                CrawlEvent::Failed { url, error_kind, .. } => println!("failed {} ({})", url, error_kind),
+                CrawlEvent::Finished { reason } => {
+                    println!("finished: {}", reason);
+                    break;
+                }
+                _ => {}
+            }
+
+            if fetched.load(Ordering::Relaxed) >= MAX_PAGES && !handle.is_stopped() {
+                handle.stop();
+            }
+        }
+    });
+
+    let outcome = spider.run().await?;
+    println!("Crawl ended ({}), clean: {}", outcome.reason, outcome.clean);
+    Ok(())
+}
@@ -0,0 +1,315 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use axum::extract::{Query, Request, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::{HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use tracing::{info, error};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::Mutex;
+use crate::commands::{self, Inspection};
+use crate::config::SpiderConfig;
+use crate::crawler::backpressure::Backpressure;
+use crate::crawler::concurrency::Concurrency;
+use crate::crawler::crawler::CrawlerConfig;
+use crate::crawler::events::CrawlEvents;
+use crate::crawler::scope::{ScopeAction, ScopeRule, ScopeRules};
+use crate::crawler::seeds::{seed_queue, RejectedSeed, SeedSource};
+use crate::crawler::shutdown::{InFlight, Shutdown};
+use crate::database::Database;
+use crate::utils::is_valid_url;
+
+// Handles on the running crawl shared with the admin API, cloned from the crawler config.
+#[derive(Clone)]
+pub struct AdminState {
+    pub db: Arc<Mutex<Database>>,
+    pub config: Arc<SpiderConfig>,
+    pub backpressure: Backpressure,
+    pub shutdown: Shutdown,
+    pub in_flight: InFlight,
+    pub scope: ScopeRules,
+    pub concurrency: Concurrency,
+    pub events: CrawlEvents,
+}
+
+impl AdminState {
+    pub fn new(db: Arc<Mutex<Database>>, config: &SpiderConfig, crawler: &CrawlerConfig) -> Self {
+        Self {
+            db,
+            config: Arc::new(config.clone()),
+            backpressure: crawler.backpressure.clone(),
+            shutdown: crawler.shutdown.clone(),
+            in_flight: crawler.in_flight.clone(),
+            scope: crawler.scope.clone(),
+            concurrency: crawler.concurrency.clone(),
+            events: crawler.events.clone(),
+        }
+    }
+
+    // "draining", "paused" (by request), "backpressure" or "running".
+    pub fn crawl_state(&self) -> &'static str {
+        if self.shutdown.is_triggered() {
+            "draining"
+        } else if self.backpressure.is_held() {
+            "paused"
+        } else if self.backpressure.is_paused() {
+            "backpressure"
+        } else {
+            "running"
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        ApiError(StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", err))
+    }
+}
+
+fn bad_request(message: impl Into<String>) -> ApiError {
+    ApiError(StatusCode::BAD_REQUEST, message.into())
+}
+
+// Whether `headers` carry `Authorization: Bearer <token>`. Compares in constant time.
+pub fn authorized(headers: &HeaderMap, token: &str) -> bool {
+    let Some(given) = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    else {
+        return false;
+    };
+
+    !token.is_empty()
+        && given.len() == token.len()
+        && given.bytes().zip(token.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+async fn require_token(State(state): State<AdminState>, request: Request, next: Next) -> Response {
+    if authorized(request.headers(), &state.config.admin.token) {
+        next.run(request).await
+    } else {
+        ApiError(StatusCode::UNAUTHORIZED, "Missing or invalid bearer token".to_string()).into_response()
+    }
+}
+
+pub fn router(state: AdminState) -> Router {
+    Router::new()
+        .route("/status", get(status))
+        .route("/config", get(config))
+        .route("/seeds", post(seed))
+        .route("/pause", post(pause))
+        .route("/resume", post(resume))
+        .route("/concurrency", put(set_concurrency))
+        .route("/scope", get(list_scope).post(add_scope).delete(remove_scope))
+        .route("/drain", post(drain))
+        .route("/urls", get(inspect_url))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state)
+}
+
+// Serves the admin API on `addr` until the task is dropped.
+pub async fn serve(addr: SocketAddr, state: AdminState) {
+    let listener = match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Error binding admin API to {}: {}", addr, e);
+            return;
+        }
+    };
+
+    info!("Serving admin API on http://{}", addr);
+    if let Err(e) = axum::serve(listener, router(state)).await {
+        error!("Admin API failed: {}", e);
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusResponse {
+    pub state: &'static str,
+    pub max_concurrency: usize,
+    pub in_flight: usize,
+    pub queued_urls: u64,
+    pub ready_hosts: u64,
+    pub indexer_queue: u64,
+    pub pending_signals: u64,
+    pub visited: u64,
+    pub pages_stored: u64,
+    pub failed: u64,
+    pub pages_fetched: u64,
+    pub bytes_fetched: u64,
+    pub elapsed_secs: u64,
+    pub stop_reason: Option<String>,
+}
+
+async fn status(State(state): State<AdminState>) -> Result<Json<StatusResponse>, ApiError> {
+    let status = commands::status(&state.db).await?;
+
+    Ok(Json(StatusResponse {
+        state: state.crawl_state(),
+        max_concurrency: state.concurrency.limit(),
+        in_flight: state.in_flight.len(),
+        queued_urls: status.queued_urls,
+        ready_hosts: status.ready_hosts,
+        indexer_queue: status.indexer_queue,
+        pending_signals: status.pending_signals,
+        visited: status.visited,
+        pages_stored: status.pages_stored,
+        failed: status.failed,
+        pages_fetched: status.usage.pages,
+        bytes_fetched: status.usage.bytes,
+        elapsed_secs: status.usage.elapsed.as_secs(),
+        stop_reason: status.stop_reason,
+    }))
+}
+
+// The configuration the crawler started with, secrets masked. Runtime changes are in `/status`
+// and `/scope`.
+async fn config(State(state): State<AdminState>) -> Json<SpiderConfig> {
+    Json(state.config.masked())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SeedRequest {
+    pub sources: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SeedResponse {
+    pub accepted: usize,
+    pub rejected: Vec<RejectedSeed>,
+}
+
+// Takes URLs and `redis:<key>` lists. Files and stdin are refused: they would be read on the
+// crawler's host.
+async fn seed(State(state): State<AdminState>, Json(request): Json<SeedRequest>) -> Result<Json<SeedResponse>, ApiError> {
+    let mut sources = Vec::with_capacity(request.sources.len());
+    for source in &request.sources {
+        match source.parse::<SeedSource>().map_err(bad_request)? {
+            source @ (SeedSource::Url(_) | SeedSource::RedisList(_)) => sources.push(source),
+            _ => return Err(bad_request(format!("Unsupported seed source '{}': use URLs or redis:<key>", source))),
+        }
+    }
+
+    let report = seed_queue(&state.db, &sources, &state.scope).await;
+    report.log_summary();
+
+    Ok(Json(SeedResponse {
+        accepted: report.accepted.len(),
+        rejected: report.rejected,
+    }))
+}
+
+async fn pause(State(state): State<AdminState>) -> Json<serde_json::Value> {
+    state.backpressure.request_pause(&state.events);
+    Json(json!({ "state": state.crawl_state() }))
+}
+
+async fn resume(State(state): State<AdminState>) -> Json<serde_json::Value> {
+    state.backpressure.request_resume(&state.events);
+    Json(json!({ "state": state.crawl_state() }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConcurrencyRequest {
+    pub max_concurrency: usize,
+}
+
+// Running workers follow the new limit from their next URL; the next batch spawns that many.
+async fn set_concurrency(
+    State(state): State<AdminState>,
+    Json(request): Json<ConcurrencyRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if request.max_concurrency == 0 {
+        return Err(bad_request("max_concurrency must be greater than zero"));
+    }
+
+    let previous = state.concurrency.set_limit(request.max_concurrency);
+    info!("Concurrency changed from {} to {}", previous, request.max_concurrency);
+
+    Ok(Json(json!({ "max_concurrency": request.max_concurrency })))
+}
+
+async fn list_scope(State(state): State<AdminState>) -> Json<Vec<ScopeRule>> {
+    Json(state.scope.list())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScopeRequest {
+    pub pattern: String,
+    pub action: String,
+}
+
+async fn add_scope(
+    State(state): State<AdminState>,
+    Json(request): Json<ScopeRequest>,
+) -> Result<Json<Vec<ScopeRule>>, ApiError> {
+    let action: ScopeAction = request.action.parse().map_err(bad_request)?;
+    let rule = ScopeRule::new(&request.pattern, action).map_err(bad_request)?;
+
+    info!("Scope rule added: {} {}", action, request.pattern);
+    state.scope.add(rule);
+    Ok(Json(state.scope.list()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PatternQuery {
+    pub pattern: String,
+}
+
+async fn remove_scope(
+    State(state): State<AdminState>,
+    Query(query): Query<PatternQuery>,
+) -> Result<Json<Vec<ScopeRule>>, ApiError> {
+    if !state.scope.remove(&query.pattern) {
+        return Err(ApiError(StatusCode::NOT_FOUND, format!("No scope rule '{}'", query.pattern)));
+    }
+
+    info!("Scope rule removed: {}", query.pattern);
+    Ok(Json(state.scope.list()))
+}
+
+// Same as SIGTERM: in-flight pages finish, results are flushed and the process exits.
+async fn drain(State(state): State<AdminState>) -> Json<serde_json::Value> {
+    info!("Drain requested through the admin API");
+    state.shutdown.trigger();
+    Json(json!({ "state": state.crawl_state() }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UrlQuery {
+    pub url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UrlState {
+    pub in_flight: bool,
+    #[serde(flatten)]
+    pub inspection: Inspection,
+}
+
+async fn inspect_url(State(state): State<AdminState>, Query(query): Query<UrlQuery>) -> Result<Json<UrlState>, ApiError> {
+    if !is_valid_url(&query.url) {
+        return Err(bad_request(format!("Invalid URL '{}'", query.url)));
+    }
+
+    let inspection = commands::inspect(&state.db, &query.url, false).await?;
+
+    Ok(Json(UrlState {
+        in_flight: state.in_flight.contains(&inspection.normalized_url),
+        inspection,
+    }))
+}
@@ -0,0 +1,142 @@
+use std::io::Write;
+use std::str::FromStr;
+use std::sync::Arc;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use tokio::sync::Mutex;
+use crate::config::SpiderConfig;
+use crate::controllers::image_controller::ImageController;
+use crate::controllers::page_controller::PageController;
+use crate::controllers::page_node_controller::LinksController;
+use crate::crawler::scope::ScopeRules;
+use crate::crawler::seeds::{seed_queue, SeedReport, SeedSource};
+use crate::database::{CrawlStatus, Database};
+use crate::pages::{FailedUrl, ImageReference, Page, PageMetadata};
+use crate::utils::{normalize_url, strip_url};
+
+// Everything stored about one URL, for `spider inspect`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Inspection {
+    pub normalized_url: String,
+    pub visited: bool,
+    // Score in the queue, if the URL is still waiting to be crawled.
+    pub queued: Option<f64>,
+    pub failure: Option<FailedUrl>,
+    pub page: Option<Page>,
+    pub metadata: Option<PageMetadata>,
+    pub outlinks: Vec<String>,
+    pub backlinks: Vec<String>,
+    pub images: Vec<InspectedImage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InspectedImage {
+    pub source_url: String,
+    #[serde(flatten)]
+    pub reference: ImageReference,
+}
+
+pub async fn connect(config: &SpiderConfig) -> Result<Arc<Mutex<Database>>> {
+    let storage = &config.storage;
+    let db = Database::connect(
+        &storage.redis_host,
+        &storage.redis_port.to_string(),
+        &storage.redis_password,
+        storage.redis_db,
+    )
+    .await?;
+    Ok(Arc::new(Mutex::new(db)))
+}
+
+// Parses each argument as a seed source (URL, file, `stdin` or `redis:<key>`) and queues it.
+// Scope rules only exist in a running crawler, so every valid seed is in scope here.
+pub async fn seed(db: &Arc<Mutex<Database>>, sources: &[String]) -> Result<SeedReport> {
+    let sources = sources
+        .iter()
+        .map(|source| SeedSource::from_str(source))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| anyhow!(e))?;
+
+    let report = seed_queue(db, &sources, &ScopeRules::new()).await;
+    report.log_summary();
+    Ok(report)
+}
+
+pub async fn status(db: &Arc<Mutex<Database>>) -> Result<CrawlStatus> {
+    db.lock().await.get_status().await
+}
+
+// Writes every stored page as one JSON object per line. Returns the number of pages written.
+pub async fn export(db: &Arc<Mutex<Database>>, out: &mut dyn Write) -> Result<usize> {
+    let pages = PageController::new(db.clone())
+        .get_all_pages()
+        .await
+        .ok_or_else(|| anyhow!("Error reading pages from Redis"))?;
+
+    let mut pages: Vec<Page> = pages.into_values().collect();
+    pages.sort_by(|a, b| a.normalized_url.cmp(&b.normalized_url));
+
+    for page in &pages {
+        serde_json::to_writer(&mut *out, page)?;
+        writeln!(out)?;
+    }
+    out.flush()?;
+
+    Ok(pages.len())
+}
+
+pub async fn requeue_failed(db: &Arc<Mutex<Database>>) -> Result<usize> {
+    db.lock().await.requeue_failed().await
+}
+
+// Collects the stored page, links and images of `url`. The raw HTML is left out unless
+// `include_html` is set.
+pub async fn inspect(db: &Arc<Mutex<Database>>, url: &str, include_html: bool) -> Result<Inspection> {
+    let raw = strip_url(url).map_err(|e| anyhow!("Invalid URL {}: {}", url, e))?;
+    let normalized_url = normalize_url(&raw).map_err(|e| anyhow!("Invalid URL {}: {}", url, e))?;
+
+    let (visited, queued, failure) = {
+        let mut db = db.lock().await;
+        (
+            db.has_url_been_visited(&normalized_url).await?,
+            db.exists_in_queue(&raw).await?,
+            db.get_failure(&normalized_url).await?,
+        )
+    };
+
+    let (page, metadata) = match PageController::new(db.clone()).get_page(&normalized_url).await? {
+        Some((mut page, metadata)) => {
+            if !include_html {
+                page.html.clear();
+                page.content_html.clear();
+            }
+            (Some(page), metadata)
+        }
+        None => (None, None),
+    };
+
+    let (outlinks, backlinks) = LinksController::new(db.clone()).get_links(&normalized_url).await?;
+
+    let images = ImageController::new(db.clone())
+        .get_page_images(&normalized_url)
+        .await?
+        .into_iter()
+        .map(|(source_url, reference)| InspectedImage { source_url, reference })
+        .collect();
+
+    Ok(Inspection {
+        normalized_url,
+        visited,
+        queued,
+        failure,
+        page,
+        metadata,
+        outlinks,
+        backlinks,
+        images,
+    })
+}
+
+pub async fn purge(db: &Arc<Mutex<Database>>, include_data: bool) -> Result<u64> {
+    db.lock().await.purge(include_data).await
+}
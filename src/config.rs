@@ -0,0 +1,766 @@
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use clap::Args;
+use regex::Regex;
+use reqwest::header::HeaderValue;
+use serde::{Deserialize, Serialize};
+use crate::controllers::image_controller::ImageRetention;
+use crate::crawler::backpressure::Watermarks;
+use crate::crawler::budget::CrawlBudget;
+use crate::crawler::canonical::CanonicalPolicy;
+use crate::crawler::fetch::FetchSettings;
+use crate::crawler::priority::{PriorityPolicyKind, UrlBoost};
+use crate::crawler::robots_directives::RobotsPolicy;
+use crate::crawler::seeds::SeedSource;
+use crate::database::frontier::{parse_host_values, FrontierPolicy};
+use crate::logging::LogFormat;
+use crate::utils::{is_valid_url, MAX_BODY_SIZE, MAX_INDEXER_QUEUE_SIZE, MIN_INDEXER_QUEUE_SIZE};
+
+pub const DEFAULT_CONFIG_PATH: &str = "spider.toml";
+pub const DEFAULT_STARTING_URL: &str = "https://starkbak.net";
+pub const DEFAULT_ADMIN_ADDR: &str = "127.0.0.1:9101";
+pub const DEFAULT_REPORT_DIR: &str = "reports";
+
+// Every crawler setting. Values come from, in increasing precedence: the defaults below, a
+// TOML file, environment variables and command-line flags (see `ConfigArgs`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpiderConfig {
+    pub scope: ScopeConfig,
+    pub frontier: FrontierConfig,
+    pub politeness: PolitenessConfig,
+    pub fetcher: FetcherConfig,
+    pub crawl: CrawlConfig,
+    pub storage: StorageConfig,
+    pub budgets: BudgetConfig,
+    pub extraction: ExtractionConfig,
+    pub metrics: MetricsConfig,
+    pub admin: AdminConfig,
+    pub logging: LoggingConfig,
+    pub report: ReportConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScopeConfig {
+    // Crawled in addition to `seeds` when set; used on its own when there are no seeds.
+    pub starting_url: Option<String>,
+    #[serde(with = "display_seq")]
+    pub seeds: Vec<SeedSource>,
+    pub use_sitemaps: bool,
+    pub feed_poll_interval_secs: u64,
+    #[serde(with = "display")]
+    pub canonical_dedupe: CanonicalPolicy,
+}
+
+impl Default for ScopeConfig {
+    fn default() -> Self {
+        Self {
+            starting_url: None,
+            seeds: Vec::new(),
+            use_sitemaps: false,
+            feed_poll_interval_secs: 300,
+            canonical_dedupe: CanonicalPolicy::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FrontierConfig {
+    #[serde(with = "display")]
+    pub priority_policy: PriorityPolicyKind,
+    // URL regex => boost.
+    pub priority_boosts: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PolitenessConfig {
+    pub host_delay_ms: u64,
+    pub host_weights: BTreeMap<String, f64>,
+    pub respect_noindex: bool,
+    pub respect_nofollow: bool,
+    pub respect_rel_nofollow: bool,
+}
+
+impl Default for PolitenessConfig {
+    fn default() -> Self {
+        Self {
+            host_delay_ms: 1000,
+            host_weights: BTreeMap::new(),
+            respect_noindex: true,
+            respect_nofollow: true,
+            respect_rel_nofollow: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FetcherConfig {
+    pub timeout_secs: u64,
+    pub connect_timeout_secs: u64,
+    pub max_body_bytes: usize,
+    // Sent as the User-Agent header when not empty.
+    pub user_agent: String,
+}
+
+impl Default for FetcherConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 30,
+            connect_timeout_secs: 10,
+            max_body_bytes: MAX_BODY_SIZE,
+            user_agent: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CrawlConfig {
+    pub max_concurrency: usize,
+    pub batch_size: usize,
+    pub shutdown_timeout_secs: u64,
+}
+
+impl Default for CrawlConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 10,
+            batch_size: 100,
+            shutdown_timeout_secs: 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    pub redis_host: String,
+    pub redis_port: u16,
+    pub redis_password: String,
+    pub redis_db: i64,
+    #[serde(with = "display")]
+    pub image_retention: ImageRetention,
+    pub indexer_high_watermark: usize,
+    pub indexer_low_watermark: usize,
+    pub backpressure_poll_ms: u64,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            redis_host: "localhost".to_string(),
+            redis_port: 6379,
+            redis_password: String::new(),
+            redis_db: 0,
+            image_retention: ImageRetention::default(),
+            indexer_high_watermark: MAX_INDEXER_QUEUE_SIZE,
+            indexer_low_watermark: MIN_INDEXER_QUEUE_SIZE,
+            backpressure_poll_ms: 500,
+        }
+    }
+}
+
+// Per-host and per-domain maps take `*` as the default for unlisted hosts. `0` means unlimited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BudgetConfig {
+    pub max_pages: u64,
+    pub max_bytes: u64,
+    pub max_duration_secs: u64,
+    pub host_page_budgets: BTreeMap<String, usize>,
+    pub host_byte_budgets: BTreeMap<String, u64>,
+    pub domain_page_budgets: BTreeMap<String, u64>,
+    pub domain_byte_budgets: BTreeMap<String, u64>,
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        Self {
+            max_pages: 100,
+            max_bytes: 0,
+            max_duration_secs: 0,
+            host_page_budgets: BTreeMap::new(),
+            host_byte_budgets: BTreeMap::new(),
+            domain_page_budgets: BTreeMap::new(),
+            domain_byte_budgets: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExtractionConfig {
+    pub simplified_html: bool,
+    pub fetch_images: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsConfig {
+    // `host:port` to serve `/metrics` on; empty disables the endpoint.
+    pub listen_addr: String,
+    pub refresh_secs: u64,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            listen_addr: String::new(),
+            refresh_secs: 15,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminConfig {
+    pub listen_addr: String,
+    // Bearer token for the admin API; the API is off while it is empty.
+    pub token: String,
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self {
+            listen_addr: DEFAULT_ADMIN_ADDR.to_string(),
+            token: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    #[serde(with = "display")]
+    pub format: LogFormat,
+    // JSON lines file recording the outcome of every fetch; empty disables it.
+    pub audit_log: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReportConfig {
+    // Directory the summary report is written to when a crawl ends; empty disables it.
+    pub output_dir: String,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self { output_dir: DEFAULT_REPORT_DIR.to_string() }
+    }
+}
+
+// Environment variables and flags layered over the config file. Empty text values are
+// ignored, so a blank entry in an env file does not clear a setting from the TOML file. Host
+// maps and boosts use the `key=value;key=value` syntax, seeds are comma-separated.
+#[derive(Debug, Clone, Default, Args)]
+pub struct ConfigArgs {
+    #[arg(long, env = "SPIDER_CONFIG", global = true, help = "TOML config file [default: spider.toml if present]")]
+    pub config: Option<PathBuf>,
+
+    #[arg(long, env = "STARTING_URL", global = true, help_heading = "Scope")]
+    pub starting_url: Option<String>,
+    #[arg(long, env = "SEEDS", global = true, help_heading = "Scope")]
+    pub seeds: Option<String>,
+    #[arg(long, env = "USE_SITEMAPS", global = true, help_heading = "Scope")]
+    pub use_sitemaps: Option<bool>,
+    #[arg(long, env = "FEED_POLL_INTERVAL", global = true, help_heading = "Scope")]
+    pub feed_poll_interval: Option<u64>,
+    #[arg(long, env = "CANONICAL_DEDUPE", global = true, help_heading = "Scope")]
+    pub canonical_dedupe: Option<CanonicalPolicy>,
+
+    #[arg(long, env = "PRIORITY_POLICY", global = true, help_heading = "Frontier")]
+    pub priority_policy: Option<PriorityPolicyKind>,
+    #[arg(long, env = "PRIORITY_BOOSTS", global = true, help_heading = "Frontier")]
+    pub priority_boosts: Option<String>,
+
+    #[arg(long, env = "HOST_DELAY_MS", global = true, help_heading = "Politeness")]
+    pub host_delay_ms: Option<u64>,
+    #[arg(long, env = "HOST_WEIGHTS", global = true, help_heading = "Politeness")]
+    pub host_weights: Option<String>,
+    #[arg(long, env = "RESPECT_NOINDEX", global = true, help_heading = "Politeness")]
+    pub respect_noindex: Option<bool>,
+    #[arg(long, env = "RESPECT_NOFOLLOW", global = true, help_heading = "Politeness")]
+    pub respect_nofollow: Option<bool>,
+    #[arg(long, env = "RESPECT_REL_NOFOLLOW", global = true, help_heading = "Politeness")]
+    pub respect_rel_nofollow: Option<bool>,
+
+    #[arg(long, env = "FETCH_TIMEOUT_SECS", global = true, help_heading = "Fetcher")]
+    pub fetch_timeout_secs: Option<u64>,
+    #[arg(long, env = "CONNECT_TIMEOUT_SECS", global = true, help_heading = "Fetcher")]
+    pub connect_timeout_secs: Option<u64>,
+    #[arg(long, env = "MAX_BODY_BYTES", global = true, help_heading = "Fetcher")]
+    pub max_body_bytes: Option<usize>,
+    #[arg(long, env = "USER_AGENT", global = true, help_heading = "Fetcher")]
+    pub user_agent: Option<String>,
+
+    #[arg(long, env = "MAX_CONCURRENCY", global = true, help_heading = "Crawl")]
+    pub max_concurrency: Option<usize>,
+    #[arg(long, env = "BATCH_SIZE", global = true, help_heading = "Crawl")]
+    pub batch_size: Option<usize>,
+    #[arg(long, env = "SHUTDOWN_TIMEOUT_SECS", global = true, help_heading = "Crawl")]
+    pub shutdown_timeout_secs: Option<u64>,
+
+    #[arg(long, env = "REDIS_HOST", global = true, help_heading = "Storage")]
+    pub redis_host: Option<String>,
+    #[arg(long, env = "REDIS_PORT", global = true, help_heading = "Storage")]
+    pub redis_port: Option<u16>,
+    #[arg(long, env = "REDIS_PASSWORD", global = true, hide_env_values = true, help_heading = "Storage")]
+    pub redis_password: Option<String>,
+    #[arg(long, env = "REDIS_DB", global = true, help_heading = "Storage")]
+    pub redis_db: Option<i64>,
+    #[arg(long, env = "IMAGE_RETENTION", global = true, help_heading = "Storage")]
+    pub image_retention: Option<ImageRetention>,
+    #[arg(long, env = "INDEXER_HIGH_WATERMARK", global = true, help_heading = "Storage")]
+    pub indexer_high_watermark: Option<usize>,
+    #[arg(long, env = "INDEXER_LOW_WATERMARK", global = true, help_heading = "Storage")]
+    pub indexer_low_watermark: Option<usize>,
+    #[arg(long, env = "BACKPRESSURE_POLL_MS", global = true, help_heading = "Storage")]
+    pub backpressure_poll_ms: Option<u64>,
+
+    #[arg(long, env = "MAX_PAGES", global = true, help_heading = "Budgets")]
+    pub max_pages: Option<u64>,
+    #[arg(long, env = "MAX_BYTES", global = true, help_heading = "Budgets")]
+    pub max_bytes: Option<u64>,
+    #[arg(long, env = "MAX_DURATION_SECS", global = true, help_heading = "Budgets")]
+    pub max_duration_secs: Option<u64>,
+    #[arg(long, env = "HOST_PAGE_BUDGETS", global = true, help_heading = "Budgets")]
+    pub host_page_budgets: Option<String>,
+    #[arg(long, env = "HOST_BYTE_BUDGETS", global = true, help_heading = "Budgets")]
+    pub host_byte_budgets: Option<String>,
+    #[arg(long, env = "DOMAIN_PAGE_BUDGETS", global = true, help_heading = "Budgets")]
+    pub domain_page_budgets: Option<String>,
+    #[arg(long, env = "DOMAIN_BYTE_BUDGETS", global = true, help_heading = "Budgets")]
+    pub domain_byte_budgets: Option<String>,
+
+    #[arg(long, env = "SIMPLIFIED_HTML", global = true, help_heading = "Extraction")]
+    pub simplified_html: Option<bool>,
+    #[arg(long, env = "FETCH_IMAGES", global = true, help_heading = "Extraction")]
+    pub fetch_images: Option<bool>,
+
+    #[arg(long, env = "METRICS_ADDR", global = true, help_heading = "Metrics")]
+    pub metrics_addr: Option<String>,
+    #[arg(long, env = "METRICS_REFRESH_SECS", global = true, help_heading = "Metrics")]
+    pub metrics_refresh_secs: Option<u64>,
+
+    #[arg(long, env = "ADMIN_ADDR", global = true, help_heading = "Admin")]
+    pub admin_addr: Option<String>,
+    #[arg(long, env = "ADMIN_TOKEN", global = true, hide_env_values = true, help_heading = "Admin")]
+    pub admin_token: Option<String>,
+
+    #[arg(long, env = "LOG_FORMAT", global = true, help_heading = "Logging")]
+    pub log_format: Option<LogFormat>,
+    #[arg(long, env = "AUDIT_LOG", global = true, help_heading = "Logging")]
+    pub audit_log: Option<String>,
+
+    #[arg(long, env = "REPORT_DIR", global = true, help_heading = "Report")]
+    pub report_dir: Option<String>,
+}
+
+impl SpiderConfig {
+    pub fn from_toml(content: &str) -> Result<Self, String> {
+        toml::from_str(content).map_err(|e| e.to_string())
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("Cannot read config file {}: {}", path.display(), e))?;
+        Self::from_toml(&content).map_err(|e| format!("Invalid config file {}: {}", path.display(), e))
+    }
+
+    // Reads the config file (an explicit `--config` must exist, the default one is optional),
+    // applies the environment and flags, then validates the result.
+    pub fn load(args: &ConfigArgs) -> Result<Self, Vec<String>> {
+        let mut config = match &args.config {
+            Some(path) => Self::from_file(path).map_err(|e| vec![e])?,
+            None if Path::new(DEFAULT_CONFIG_PATH).exists() => {
+                Self::from_file(Path::new(DEFAULT_CONFIG_PATH)).map_err(|e| vec![e])?
+            }
+            None => Self::default(),
+        };
+
+        config.apply(args).map_err(|e| vec![e])?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn apply(&mut self, args: &ConfigArgs) -> Result<(), String> {
+        fn set<T: Clone>(target: &mut T, value: &Option<T>) {
+            if let Some(value) = value {
+                *target = value.clone();
+            }
+        }
+
+        fn non_empty(value: &Option<String>) -> Option<&str> {
+            value.as_deref().map(str::trim).filter(|value| !value.is_empty())
+        }
+
+        if let Some(url) = non_empty(&args.starting_url) {
+            self.scope.starting_url = Some(url.to_string());
+        }
+        if let Some(seeds) = non_empty(&args.seeds) {
+            self.scope.seeds = parse_seed_sources(seeds).map_err(|e| format!("SEEDS: {}", e))?;
+        }
+        set(&mut self.scope.use_sitemaps, &args.use_sitemaps);
+        set(&mut self.scope.feed_poll_interval_secs, &args.feed_poll_interval);
+        set(&mut self.scope.canonical_dedupe, &args.canonical_dedupe);
+
+        set(&mut self.frontier.priority_policy, &args.priority_policy);
+        if let Some(boosts) = non_empty(&args.priority_boosts) {
+            self.frontier.priority_boosts = parse_boost_map(boosts).map_err(|e| format!("PRIORITY_BOOSTS: {}", e))?;
+        }
+
+        set(&mut self.politeness.host_delay_ms, &args.host_delay_ms);
+        if let Some(weights) = non_empty(&args.host_weights) {
+            self.politeness.host_weights = parse_host_map(weights).map_err(|e| format!("HOST_WEIGHTS: {}", e))?;
+        }
+        set(&mut self.politeness.respect_noindex, &args.respect_noindex);
+        set(&mut self.politeness.respect_nofollow, &args.respect_nofollow);
+        set(&mut self.politeness.respect_rel_nofollow, &args.respect_rel_nofollow);
+
+        set(&mut self.fetcher.timeout_secs, &args.fetch_timeout_secs);
+        set(&mut self.fetcher.connect_timeout_secs, &args.connect_timeout_secs);
+        set(&mut self.fetcher.max_body_bytes, &args.max_body_bytes);
+        if let Some(user_agent) = non_empty(&args.user_agent) {
+            self.fetcher.user_agent = user_agent.to_string();
+        }
+
+        set(&mut self.crawl.max_concurrency, &args.max_concurrency);
+        set(&mut self.crawl.batch_size, &args.batch_size);
+        set(&mut self.crawl.shutdown_timeout_secs, &args.shutdown_timeout_secs);
+
+        if let Some(host) = non_empty(&args.redis_host) {
+            self.storage.redis_host = host.to_string();
+        }
+        set(&mut self.storage.redis_port, &args.redis_port);
+        if let Some(password) = non_empty(&args.redis_password) {
+            self.storage.redis_password = password.to_string();
+        }
+        set(&mut self.storage.redis_db, &args.redis_db);
+        set(&mut self.storage.image_retention, &args.image_retention);
+        set(&mut self.storage.indexer_high_watermark, &args.indexer_high_watermark);
+        set(&mut self.storage.indexer_low_watermark, &args.indexer_low_watermark);
+        set(&mut self.storage.backpressure_poll_ms, &args.backpressure_poll_ms);
+
+        set(&mut self.budgets.max_pages, &args.max_pages);
+        set(&mut self.budgets.max_bytes, &args.max_bytes);
+        set(&mut self.budgets.max_duration_secs, &args.max_duration_secs);
+        for (key, value, target) in [
+            ("HOST_BYTE_BUDGETS", &args.host_byte_budgets, &mut self.budgets.host_byte_budgets),
+            ("DOMAIN_PAGE_BUDGETS", &args.domain_page_budgets, &mut self.budgets.domain_page_budgets),
+            ("DOMAIN_BYTE_BUDGETS", &args.domain_byte_budgets, &mut self.budgets.domain_byte_budgets),
+        ] {
+            if let Some(value) = non_empty(value) {
+                *target = parse_host_map(value).map_err(|e| format!("{}: {}", key, e))?;
+            }
+        }
+        if let Some(budgets) = non_empty(&args.host_page_budgets) {
+            self.budgets.host_page_budgets = parse_host_map(budgets).map_err(|e| format!("HOST_PAGE_BUDGETS: {}", e))?;
+        }
+
+        set(&mut self.extraction.simplified_html, &args.simplified_html);
+        set(&mut self.extraction.fetch_images, &args.fetch_images);
+
+        if let Some(addr) = non_empty(&args.metrics_addr) {
+            self.metrics.listen_addr = addr.to_string();
+        }
+        set(&mut self.metrics.refresh_secs, &args.metrics_refresh_secs);
+
+        if let Some(addr) = non_empty(&args.admin_addr) {
+            self.admin.listen_addr = addr.to_string();
+        }
+        if let Some(token) = non_empty(&args.admin_token) {
+            self.admin.token = token.to_string();
+        }
+
+        set(&mut self.logging.format, &args.log_format);
+        if let Some(path) = non_empty(&args.audit_log) {
+            self.logging.audit_log = path.to_string();
+        }
+
+        if let Some(dir) = non_empty(&args.report_dir) {
+            self.report.output_dir = dir.to_string();
+        }
+
+        Ok(())
+    }
+
+    // Every problem with the merged settings, so they can all be fixed in one go.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if let Some(url) = &self.scope.starting_url
+            && !is_valid_url(url)
+        {
+            errors.push(format!("scope.starting_url: invalid URL '{}'", url));
+        }
+
+        for pattern in self.frontier.priority_boosts.keys() {
+            if let Err(e) = Regex::new(pattern) {
+                errors.push(format!("frontier.priority_boosts: invalid pattern '{}': {}", pattern, e));
+            }
+        }
+
+        for (host, weight) in &self.politeness.host_weights {
+            if host == "*" {
+                errors.push("politeness.host_weights: a `*` default is not supported, unlisted hosts have weight 1".to_string());
+            } else if *weight <= 0.0 {
+                errors.push(format!("politeness.host_weights: weight for '{}' must be greater than zero", host));
+            }
+        }
+
+        if self.fetcher.timeout_secs == 0 {
+            errors.push("fetcher.timeout_secs must be greater than zero".to_string());
+        }
+        if self.fetcher.connect_timeout_secs == 0 {
+            errors.push("fetcher.connect_timeout_secs must be greater than zero".to_string());
+        }
+        if self.fetcher.max_body_bytes == 0 {
+            errors.push("fetcher.max_body_bytes must be greater than zero".to_string());
+        }
+        if HeaderValue::from_str(&self.fetcher.user_agent).is_err() {
+            errors.push(format!("fetcher.user_agent: invalid header value '{}'", self.fetcher.user_agent));
+        }
+
+        if self.crawl.max_concurrency == 0 {
+            errors.push("crawl.max_concurrency must be greater than zero".to_string());
+        }
+        if self.crawl.batch_size == 0 {
+            errors.push("crawl.batch_size must be greater than zero".to_string());
+        }
+
+        if self.storage.redis_host.trim().is_empty() {
+            errors.push("storage.redis_host must not be empty".to_string());
+        }
+        if let Err(e) = self.watermarks() {
+            errors.push(format!("storage: {}", e));
+        }
+        if self.storage.backpressure_poll_ms == 0 {
+            errors.push("storage.backpressure_poll_ms must be greater than zero".to_string());
+        }
+
+        if let Err(e) = self.metrics_addr() {
+            errors.push(format!("metrics.listen_addr: {}", e));
+        }
+        if self.metrics.refresh_secs == 0 {
+            errors.push("metrics.refresh_secs must be greater than zero".to_string());
+        }
+
+        if let Err(e) = self.admin_addr() {
+            errors.push(format!("admin.listen_addr: {}", e));
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    // The effective config as TOML, with secrets masked.
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string_pretty(&self.masked()).map_err(|e| e.to_string())
+    }
+
+    // A copy with the Redis password and admin token replaced, safe to print or serve.
+    pub fn masked(&self) -> Self {
+        let mut masked = self.clone();
+        for secret in [&mut masked.storage.redis_password, &mut masked.admin.token] {
+            if !secret.is_empty() {
+                *secret = "********".to_string();
+            }
+        }
+        masked
+    }
+
+    // Where to serve `/metrics`, if enabled.
+    pub fn metrics_addr(&self) -> Result<Option<SocketAddr>, String> {
+        match self.metrics.listen_addr.trim() {
+            "" => Ok(None),
+            addr => addr
+                .parse()
+                .map(Some)
+                .map_err(|e| format!("invalid address '{}': {}", addr, e)),
+        }
+    }
+
+    // Where to serve the admin API, if a token is set.
+    pub fn admin_addr(&self) -> Result<Option<SocketAddr>, String> {
+        let addr = self.admin.listen_addr.trim();
+        let addr: SocketAddr = addr.parse().map_err(|e| format!("invalid address '{}': {}", addr, e))?;
+        Ok(if self.admin.token.is_empty() { None } else { Some(addr) })
+    }
+
+    pub fn audit_log(&self) -> Option<&Path> {
+        match self.logging.audit_log.trim() {
+            "" => None,
+            path => Some(Path::new(path)),
+        }
+    }
+
+    pub fn report_dir(&self) -> Option<&Path> {
+        match self.report.output_dir.trim() {
+            "" => None,
+            dir => Some(Path::new(dir)),
+        }
+    }
+
+    pub fn starting_url(&self) -> &str {
+        self.scope.starting_url.as_deref().unwrap_or(DEFAULT_STARTING_URL)
+    }
+
+    // The configured seeds, plus the starting URL when it was set or nothing else was given.
+    pub fn seed_sources(&self) -> Vec<SeedSource> {
+        let mut sources = self.scope.seeds.clone();
+        if sources.is_empty() || self.scope.starting_url.is_some() {
+            sources.insert(0, SeedSource::Url(self.starting_url().to_string()));
+        }
+        sources
+    }
+
+    pub fn url_boosts(&self) -> Vec<UrlBoost> {
+        self.frontier
+            .priority_boosts
+            .iter()
+            .filter_map(|(pattern, boost)| Regex::new(pattern).ok().map(|pattern| UrlBoost { pattern, boost: *boost }))
+            .collect()
+    }
+
+    pub fn robots_policy(&self) -> RobotsPolicy {
+        RobotsPolicy {
+            respect_noindex: self.politeness.respect_noindex,
+            respect_nofollow: self.politeness.respect_nofollow,
+            respect_rel_nofollow: self.politeness.respect_rel_nofollow,
+        }
+    }
+
+    pub fn frontier_policy(&self) -> FrontierPolicy {
+        let (_, host_weights) = split_host_map(&self.politeness.host_weights);
+        let (host_max_pages, host_page_budgets) = split_host_map(&self.budgets.host_page_budgets);
+
+        FrontierPolicy {
+            host_delay: Duration::from_millis(self.politeness.host_delay_ms),
+            host_weights,
+            host_max_pages: host_max_pages.unwrap_or(0),
+            host_page_budgets,
+        }
+    }
+
+    pub fn budget(&self) -> CrawlBudget {
+        let (host_max_bytes, host_byte_budgets) = split_host_map(&self.budgets.host_byte_budgets);
+        let (domain_max_pages, domain_page_budgets) = split_host_map(&self.budgets.domain_page_budgets);
+        let (domain_max_bytes, domain_byte_budgets) = split_host_map(&self.budgets.domain_byte_budgets);
+
+        CrawlBudget {
+            max_pages: self.budgets.max_pages,
+            max_bytes: self.budgets.max_bytes,
+            max_duration: match self.budgets.max_duration_secs {
+                0 => None,
+                secs => Some(Duration::from_secs(secs)),
+            },
+            host_max_bytes: host_max_bytes.unwrap_or(0),
+            host_byte_budgets,
+            domain_max_pages: domain_max_pages.unwrap_or(0),
+            domain_page_budgets,
+            domain_max_bytes: domain_max_bytes.unwrap_or(0),
+            domain_byte_budgets,
+        }
+    }
+
+    pub fn watermarks(&self) -> Result<Watermarks, String> {
+        Watermarks::new(self.storage.indexer_high_watermark, self.storage.indexer_low_watermark)
+    }
+
+    pub fn fetch_settings(&self) -> FetchSettings {
+        FetchSettings {
+            timeout: Duration::from_secs(self.fetcher.timeout_secs),
+            connect_timeout: Duration::from_secs(self.fetcher.connect_timeout_secs),
+            max_body_size: self.fetcher.max_body_bytes,
+            user_agent: self.fetcher.user_agent.clone(),
+        }
+    }
+}
+
+pub fn parse_seed_sources(value: &str) -> Result<Vec<SeedSource>, String> {
+    value
+        .split(',')
+        .filter(|source| !source.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+// `host=value;...` into a map that keeps `*` as its own key.
+pub fn parse_host_map<T: std::str::FromStr>(value: &str) -> Result<BTreeMap<String, T>, String> {
+    let (default, values) = parse_host_values::<T>(value)?;
+    let mut map: BTreeMap<String, T> = values.into_iter().collect();
+    if let Some(default) = default {
+        map.insert("*".to_string(), default);
+    }
+    Ok(map)
+}
+
+pub fn parse_boost_map(value: &str) -> Result<BTreeMap<String, f64>, String> {
+    Ok(crate::crawler::priority::parse_url_boosts(value)?
+        .into_iter()
+        .map(|boost| (boost.pattern.as_str().to_string(), boost.boost))
+        .collect())
+}
+
+// Splits off the `*` default and normalizes hosts the same way `parse_host_values` does, so
+// TOML tables and env strings behave alike.
+fn split_host_map<T: Copy>(map: &BTreeMap<String, T>) -> (Option<T>, HashMap<String, T>) {
+    let mut default = None;
+    let mut values = HashMap::new();
+
+    for (host, value) in map {
+        let host = host.trim().to_ascii_lowercase();
+        if host == "*" {
+            default = Some(*value);
+        } else {
+            values.insert(host.trim_start_matches("www.").to_string(), *value);
+        }
+    }
+
+    (default, values)
+}
+
+// Serde adapters for the setting enums, which already round-trip through FromStr/Display.
+mod display {
+    use std::fmt::Display;
+    use std::str::FromStr;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T: Display, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr<Err = String>,
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
+
+mod display_seq {
+    use std::fmt::Display;
+    use std::str::FromStr;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T: Display, S: Serializer>(values: &[T], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(|value| value.to_string()))
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        T: FromStr<Err = String>,
+        D: Deserializer<'de>,
+    {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|value| value.parse().map_err(de::Error::custom))
+            .collect()
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use redis::AsyncCommands;
+use tracing::{info, error};
+use crate::pages::Feed;
+
+pub struct FeedController {
+    db: std::sync::Arc<tokio::sync::Mutex<crate::database::Database>>,
+}
+
+impl FeedController {
+    pub fn new(db: std::sync::Arc<tokio::sync::Mutex<crate::database::Database>>) -> Self {
+        Self { db }
+    }
+
+    async fn connection(&self) -> redis::RedisResult<redis::aio::Connection> {
+        let db_guard = self.db.lock().await;
+        db_guard.client.get_async_connection().await
+    }
+
+    // Registers newly discovered feeds, due for polling straight away. Feeds already in the
+    // registry keep their validators and schedule.
+    pub async fn save_feeds(&self, crawcfg: &crate::crawler::crawler::CrawlerConfig) {
+        use crate::utils::{FEED_PREFIX, FEED_SCHEDULE_KEY};
+
+        let mut conn = match self.connection().await {
+            Ok(c) => c,
+            Err(e) => {
+                error!("Failed to get Redis connection: {:?}", e);
+                return;
+            }
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        let mut pipe = redis::pipe();
+
+        let feeds_guard = crawcfg.feeds.lock().await;
+        for (normalized_url, feed) in feeds_guard.iter() {
+            let feed_key = format!("{}:{}", FEED_PREFIX, normalized_url);
+
+            for (field, value) in &feed.to_hash() {
+                pipe.hset_nx(&feed_key, field, value);
+            }
+            pipe.cmd("ZADD").arg(FEED_SCHEDULE_KEY).arg("NX").arg(now).arg(normalized_url);
+        }
+        let count = feeds_guard.len();
+        drop(feeds_guard);
+
+        if count == 0 {
+            return;
+        }
+
+        match pipe.query_async::<_, ()>(&mut conn).await {
+            Ok(_) => info!("Registered {} feeds", count),
+            Err(e) => error!("Error saving feeds: {:?}", e),
+        }
+    }
+
+    pub async fn due_feeds(&self, now: i64) -> redis::RedisResult<Vec<Feed>> {
+        use crate::utils::{FEED_PREFIX, FEED_SCHEDULE_KEY};
+
+        let mut conn = self.connection().await?;
+        let due: Vec<String> = conn.zrangebyscore(FEED_SCHEDULE_KEY, "-inf", now).await?;
+
+        let mut feeds = Vec::with_capacity(due.len());
+        for normalized_url in due {
+            let data: HashMap<String, String> = conn.hgetall(format!("{}:{}", FEED_PREFIX, normalized_url)).await?;
+            match Feed::from_hash(&data) {
+                Ok(feed) => feeds.push(feed),
+                Err(e) => error!("Invalid feed record {}: {}", normalized_url, e),
+            }
+        }
+
+        Ok(feeds)
+    }
+
+    // Stores the validators from a poll and schedules the next one.
+    pub async fn save_poll(&self, feed: &Feed, next_poll: i64) -> redis::RedisResult<()> {
+        use crate::utils::{FEED_PREFIX, FEED_SCHEDULE_KEY};
+
+        let mut conn = self.connection().await?;
+        let feed_key = format!("{}:{}", FEED_PREFIX, feed.normalized_url);
+
+        let mut pipe = redis::pipe();
+        for (field, value) in &feed.to_hash() {
+            pipe.hset(&feed_key, field, value);
+        }
+        pipe.zadd(FEED_SCHEDULE_KEY, &feed.normalized_url, next_poll);
+
+        pipe.query_async(&mut conn).await
+    }
+
+    // Records item URLs as seen for `feed`, returning the ones not seen before.
+    pub async fn add_items(&self, feed: &Feed, items: &[String]) -> redis::RedisResult<Vec<String>> {
+        use crate::utils::FEED_ITEMS_PREFIX;
+
+        if items.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.connection().await?;
+        let items_key = format!("{}:{}", FEED_ITEMS_PREFIX, feed.normalized_url);
+
+        let mut pipe = redis::pipe();
+        for item in items {
+            pipe.sadd(&items_key, item);
+        }
+        let added: Vec<i64> = pipe.query_async(&mut conn).await?;
+
+        Ok(items
+            .iter()
+            .zip(added)
+            .filter(|(_, added)| *added == 1)
+            .map(|(item, _)| item.clone())
+            .collect())
+    }
+}
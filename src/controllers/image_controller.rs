@@ -1,48 +1,209 @@
+use redis::AsyncCommands;
+use std::fmt;
+use std::str::FromStr;
 use tracing::{info, error};
+use crate::pages::ImageReference;
+
+// How long image records are kept. `Ttl` evicts images not seen for a fixed time; `Lru`
+// keeps the most recently seen `n` images and evicts the rest. Both track when each image
+// was last seen in `image_lru` and sweep after every save, so the `page_images:` sets that
+// point at an evicted image are cleaned up with it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImageRetention {
+    #[default]
+    None,
+    Ttl(u64),
+    Lru(usize),
+}
+
+impl FromStr for ImageRetention {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_ascii_lowercase();
+        let (kind, arg) = value.split_once(':').unwrap_or((value.as_str(), ""));
+
+        match kind {
+            "none" | "" => Ok(Self::None),
+            "ttl" => arg
+                .parse::<u64>()
+                .ok()
+                .filter(|secs| *secs > 0)
+                .map(Self::Ttl)
+                .ok_or_else(|| format!("Invalid image TTL '{}': expected ttl:<seconds>", arg)),
+            "lru" => arg
+                .parse::<usize>()
+                .ok()
+                .filter(|max| *max > 0)
+                .map(Self::Lru)
+                .ok_or_else(|| format!("Invalid image LRU size '{}': expected lru:<max images>", arg)),
+            other => Err(format!("Invalid image retention: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for ImageRetention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Ttl(secs) => write!(f, "ttl:{}", secs),
+            Self::Lru(max) => write!(f, "lru:{}", max),
+        }
+    }
+}
+
+// Which images a sweep removes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Eviction {
+    Nothing,
+    // Images last seen before this timestamp.
+    SeenBefore(i64),
+    // This many of the least recently seen images.
+    Oldest(usize),
+}
+
+impl ImageRetention {
+    // The sweep to run when `tracked` images are in `image_lru` at time `now`.
+    pub fn eviction(&self, tracked: usize, now: i64) -> Eviction {
+        match *self {
+            Self::None => Eviction::Nothing,
+            Self::Ttl(secs) => Eviction::SeenBefore(now - secs as i64),
+            Self::Lru(max_images) if tracked > max_images => Eviction::Oldest(tracked - max_images),
+            Self::Lru(_) => Eviction::Nothing,
+        }
+    }
+}
 
 pub struct ImageController {
     db: std::sync::Arc<tokio::sync::Mutex<crate::database::Database>>,
+    retention: ImageRetention,
 }
 
 impl ImageController {
     pub fn new(db: std::sync::Arc<tokio::sync::Mutex<crate::database::Database>>) -> Self {
-        Self { db }
+        Self { db, retention: ImageRetention::default() }
+    }
+
+    pub fn with_retention(mut self, retention: ImageRetention) -> Self {
+        self.retention = retention;
+        self
     }
 
     pub async fn save_images(&self, crawcfg: &crate::crawler::crawler::CrawlerConfig) -> redis::RedisResult<()> {
+        use crate::utils::{IMAGE_PREFIX, IMAGE_PAGES_PREFIX, IMAGE_REFS_PREFIX, IMAGE_LRU_KEY, PAGE_IMAGES_PREFIX};
+
         let mut pipe = redis::pipe();
+        let mut seen = std::collections::HashSet::new();
+        let now = chrono::Utc::now().timestamp();
 
         info!("Saving images...");
 
         let mut count = 0;
-        
+
         let images_guard = crawcfg.images.lock().await;
-        for (normalized_url, image_data) in images_guard.iter() {
-            for image in image_data {
-                let image_key = format!("{}:{}", crate::utils::IMAGE_PREFIX, image.normalized_source_url);
+        for (normalized_url, images) in images_guard.iter() {
+            for image in images {
+                let source = &image.normalized_source_url;
 
-                pipe.hset(&image_key, "page_url", &image.normalized_page_url)
-                    .hset(&image_key, "alt", &image.alt)
-                    .expire(&image_key, 3600usize);
+                pipe.hset(format!("{}:{}", IMAGE_PREFIX, source), "last_seen", now)
+                    .hset(format!("{}:{}", IMAGE_REFS_PREFIX, source), normalized_url, image.reference().to_json())
+                    .sadd(format!("{}:{}", IMAGE_PAGES_PREFIX, source), normalized_url)
+                    .sadd(format!("{}:{}", PAGE_IMAGES_PREFIX, normalized_url), source);
 
+                seen.insert(source.clone());
                 count += 1;
+            }
+        }
+        drop(images_guard);
+
+        let image_data_guard = crawcfg.image_data.lock().await;
+        for (normalized_source_url, image_data) in image_data_guard.iter() {
+            let image_key = format!("{}:{}", IMAGE_PREFIX, normalized_source_url);
 
-                let page_images_key = format!("{}:{}", crate::utils::PAGE_IMAGES_PREFIX, normalized_url);
-                pipe.sadd(&page_images_key, &image.normalized_source_url);
+            for (field, value) in &image_data.to_hash() {
+                pipe.hset(&image_key, field, value);
+            }
+            seen.insert(normalized_source_url.clone());
+        }
+        drop(image_data_guard);
+
+        if self.retention != ImageRetention::None {
+            for source in &seen {
+                pipe.zadd(IMAGE_LRU_KEY, source, now);
             }
         }
 
         let db_guard = self.db.lock().await;
         let mut conn = db_guard.client.get_async_connection().await?;
-        drop(db_guard); 
+        drop(db_guard);
 
         let result = pipe.query_async::<_, ()>(&mut conn).await;
 
         match &result {
             Ok(_) => info!("Successfully written {} entries to the db!", count),
-            Err(e) => tracing::error!("Error saving images: {:?}", e),
+            Err(e) => error!("Error saving images: {:?}", e),
+        }
+        result?;
+
+        if self.retention != ImageRetention::None {
+            self.evict_images(&mut conn, now).await?;
+        }
+
+        Ok(())
+    }
+
+    // Images stored for `normalized_url`, with how that page references each one.
+    pub async fn get_page_images(&self, normalized_url: &str) -> redis::RedisResult<Vec<(String, ImageReference)>> {
+        use crate::utils::{IMAGE_REFS_PREFIX, PAGE_IMAGES_PREFIX};
+
+        let db_guard = self.db.lock().await;
+        let mut conn = db_guard.client.get_async_connection().await?;
+        drop(db_guard);
+
+        let mut sources: Vec<String> = conn.smembers(format!("{}:{}", PAGE_IMAGES_PREFIX, normalized_url)).await?;
+        sources.sort();
+
+        let mut images = Vec::with_capacity(sources.len());
+        for source in sources {
+            let reference: Option<String> = conn.hget(format!("{}:{}", IMAGE_REFS_PREFIX, source), normalized_url).await?;
+            let reference = reference
+                .and_then(|json| ImageReference::from_json(&json).ok())
+                .unwrap_or_default();
+            images.push((source, reference));
+        }
+
+        Ok(images)
+    }
+
+    async fn evict_images(&self, conn: &mut redis::aio::Connection, now: i64) -> redis::RedisResult<()> {
+        use crate::utils::{IMAGE_PREFIX, IMAGE_PAGES_PREFIX, IMAGE_REFS_PREFIX, IMAGE_LRU_KEY, PAGE_IMAGES_PREFIX};
+
+        let tracked: usize = conn.zcard(IMAGE_LRU_KEY).await?;
+        let evicted: Vec<String> = match self.retention.eviction(tracked, now) {
+            Eviction::Nothing => return Ok(()),
+            Eviction::SeenBefore(cutoff) => conn.zrangebyscore(IMAGE_LRU_KEY, "-inf", format!("({}", cutoff)).await?,
+            Eviction::Oldest(count) => conn.zrange(IMAGE_LRU_KEY, 0, count as isize - 1).await?,
+        };
+        if evicted.is_empty() {
+            return Ok(());
+        }
+
+        let mut pipe = redis::pipe();
+
+        for source in &evicted {
+            let pages: Vec<String> = conn.smembers(format!("{}:{}", IMAGE_PAGES_PREFIX, source)).await?;
+            for page in pages {
+                pipe.srem(format!("{}:{}", PAGE_IMAGES_PREFIX, page), source);
+            }
+
+            pipe.del(format!("{}:{}", IMAGE_PREFIX, source))
+                .del(format!("{}:{}", IMAGE_REFS_PREFIX, source))
+                .del(format!("{}:{}", IMAGE_PAGES_PREFIX, source))
+                .zrem(IMAGE_LRU_KEY, source);
         }
 
-        result
+        pipe.query_async::<_, ()>(conn).await?;
+        info!("Evicted {} images ({})", evicted.len(), self.retention);
+        Ok(())
     }
 }
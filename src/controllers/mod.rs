@@ -1,3 +1,5 @@
+pub mod feed_controller;
 pub mod image_controller;
 pub mod page_controller;
 pub mod page_node_controller;
+pub mod sitemap_controller;
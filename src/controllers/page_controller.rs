@@ -1,6 +1,18 @@
 use redis::AsyncCommands;
 use tracing::{info, error};
 
+// Stores a page hash, counting it in `page_count` the first time it is stored.
+//
+// KEYS: page, page count
+// ARGV: field, value, ...
+const STORE_PAGE_SCRIPT: &str = r#"
+if redis.call('EXISTS', KEYS[1]) == 0 then
+    redis.call('INCR', KEYS[2])
+end
+redis.call('HSET', KEYS[1], unpack(ARGV))
+return 0
+"#;
+
 pub struct PageController {
     db: std::sync::Arc<tokio::sync::Mutex<crate::database::Database>>,
 }
@@ -72,6 +84,8 @@ impl PageController {
 
     pub async fn save_pages(&self, crawcfg: &crate::crawler::crawler::CrawlerConfig) {
         let data = crawcfg.pages.lock().await;
+        let metadata = crawcfg.metadata.lock().await;
+        let structured_data = crawcfg.structured_data.lock().await;
         info!("Writing {} entries to the db...", data.len());
 
         let db_guard = self.db.lock().await;
@@ -84,17 +98,39 @@ impl PageController {
         };
         drop(db_guard); 
 
+        // The script counts each page in `page_count` as it is first written, so the count
+        // and the page can't drift apart.
+        let store_page = redis::Script::new(STORE_PAGE_SCRIPT);
         let mut pipe = redis::pipe();
+        pipe.cmd("SCRIPT").arg("LOAD").arg(STORE_PAGE_SCRIPT).ignore();
 
         for (_, page) in data.iter() {
             let page_hash = crate::pages::hash_page(page);
             let page_key = format!("{}:{}", crate::utils::PAGE_PREFIX, page.normalized_url);
 
-            for (field, value) in &page_hash {
-                pipe.hset(&page_key, field, value);
+            pipe.cmd("EVALSHA")
+                .arg(store_page.get_hash())
+                .arg(2)
+                .arg(&page_key)
+                .arg(crate::utils::PAGE_COUNT_KEY)
+                .arg(page_hash.iter().flat_map(|(field, value)| [field, value]).collect::<Vec<_>>())
+                .ignore();
+
+            if let Some(page_metadata) = metadata.get(&page.normalized_url) {
+                let metadata_key = format!("{}:{}", crate::utils::PAGE_METADATA_PREFIX, page.normalized_url);
+                for (field, value) in &page_metadata.to_hash() {
+                    pipe.hset(&metadata_key, field, value).ignore();
+                }
+            }
+
+            if let Some(page_structured_data) = structured_data.get(&page.normalized_url) {
+                let structured_data_key = format!("{}:{}", crate::utils::STRUCTURED_DATA_PREFIX, page.normalized_url);
+                for (field, value) in &page_structured_data.to_hash() {
+                    pipe.hset(&structured_data_key, field, value).ignore();
+                }
             }
 
-            let _: Result<(), _> = conn.lpush(crate::utils::INDEXER_QUEUE_KEY, &page_key).await;
+            pipe.lpush(crate::utils::INDEXER_QUEUE_KEY, &page_key).ignore();
         }
 
         if let Err(e) = pipe.query_async::<_, ()>(&mut conn).await {
@@ -103,4 +139,39 @@ impl PageController {
             info!("Successfully written {} entries to the db!", data.len());
         }
     }
+
+    // The stored page and its metadata, if `normalized_url` has been crawled.
+    pub async fn get_page(
+        &self,
+        normalized_url: &str,
+    ) -> redis::RedisResult<Option<(crate::pages::Page, Option<crate::pages::PageMetadata>)>> {
+        let db_guard = self.db.lock().await;
+        let mut conn = db_guard.client.get_async_connection().await?;
+        drop(db_guard);
+
+        let page_key = format!("{}:{}", crate::utils::PAGE_PREFIX, normalized_url);
+        let metadata_key = format!("{}:{}", crate::utils::PAGE_METADATA_PREFIX, normalized_url);
+
+        let page_hash: std::collections::HashMap<String, String> = conn.hgetall(&page_key).await?;
+        if page_hash.is_empty() {
+            return Ok(None);
+        }
+
+        let page = match crate::pages::dehash_page(&page_hash) {
+            Ok(page) => page,
+            Err(e) => {
+                error!("Error dehashing page from Redis: {:?}", e);
+                return Ok(None);
+            }
+        };
+
+        let metadata_hash: std::collections::HashMap<String, String> = conn.hgetall(&metadata_key).await?;
+        let metadata = if metadata_hash.is_empty() {
+            None
+        } else {
+            crate::pages::PageMetadata::from_hash(&metadata_hash).ok()
+        };
+
+        Ok(Some((page, metadata)))
+    }
 }
@@ -28,7 +28,11 @@ impl LinksController {
         let backlinks_guard = crawcfg.backlinks.lock().await;
         for (key, backlinks) in backlinks_guard.iter() {
             let redis_key = format!("{}:{}", crate::utils::BACKLINKS_PREFIX, key);
+            let edges_key = format!("{}:{}", crate::utils::BACKLINK_EDGES_PREFIX, key);
             for link in backlinks.get_links() {
+                if let Some(edge) = backlinks.get_edge(&link) {
+                    pipe.cmd("HSET").arg(&edges_key).arg(&link).arg(edge.to_json());
+                }
                 pipe.cmd("SADD").arg(&redis_key).arg(link);
             }
             count += backlinks.get_links().len();
@@ -40,7 +44,11 @@ impl LinksController {
         let outlinks_guard = crawcfg.outlinks.lock().await;
         for (key, outlinks) in outlinks_guard.iter() {
             let redis_key = format!("{}:{}", crate::utils::OUTLINKS_PREFIX, key);
+            let edges_key = format!("{}:{}", crate::utils::OUTLINK_EDGES_PREFIX, key);
             for link in outlinks.get_links() {
+                if let Some(edge) = outlinks.get_edge(&link) {
+                    pipe.cmd("HSET").arg(&edges_key).arg(&link).arg(edge.to_json());
+                }
                 pipe.cmd("SADD").arg(&redis_key).arg(link);
             }
             count += outlinks.get_links().len();
@@ -52,4 +60,21 @@ impl LinksController {
             Err(e) => error!("Error executing pipeline: {:?}", e),
         }
     }
+
+    // Stored outlinks and backlinks of `normalized_url`, sorted.
+    pub async fn get_links(&self, normalized_url: &str) -> redis::RedisResult<(Vec<String>, Vec<String>)> {
+        let db_guard = self.db.lock().await;
+        let mut conn = db_guard.client.get_async_connection().await?;
+        drop(db_guard);
+
+        let (mut outlinks, mut backlinks): (Vec<String>, Vec<String>) = redis::pipe()
+            .smembers(format!("{}:{}", crate::utils::OUTLINKS_PREFIX, normalized_url))
+            .smembers(format!("{}:{}", crate::utils::BACKLINKS_PREFIX, normalized_url))
+            .query_async(&mut conn)
+            .await?;
+
+        outlinks.sort();
+        backlinks.sort();
+        Ok((outlinks, backlinks))
+    }
 }
@@ -0,0 +1,91 @@
+use redis::AsyncCommands;
+use tracing::{info, error};
+use crate::crawler::sitemap::SitemapEntry;
+use crate::utils::{MIN_SCORE, MAX_SCORE};
+
+pub struct SitemapController {
+    db: std::sync::Arc<tokio::sync::Mutex<crate::database::Database>>,
+}
+
+impl SitemapController {
+    pub fn new(db: std::sync::Arc<tokio::sync::Mutex<crate::database::Database>>) -> Self {
+        Self { db }
+    }
+
+    // Enqueues sitemap URLs and records them for orphan detection. A URL already queued
+    // keeps whichever score is lower.
+    pub async fn save_sitemap_urls(&self, entries: &[SitemapEntry]) {
+        use crate::utils::SITEMAP_URLS_KEY;
+
+        let now = chrono::Utc::now();
+        let urls: Vec<(String, f64)> = entries
+            .iter()
+            .map(|entry| (entry.url.clone(), entry.score(now).clamp(MIN_SCORE as f64, MAX_SCORE as f64)))
+            .collect();
+
+        let mut db_guard = self.db.lock().await;
+        // Sitemap entries count as one link away from the seed that listed the sitemap.
+        let normalized_urls = match db_guard.push_urls(&urls, 1.0).await {
+            Ok(normalized_urls) => normalized_urls,
+            Err(e) => {
+                error!("Error pushing sitemap URLs: {:?}", e);
+                return;
+            }
+        };
+
+        if normalized_urls.is_empty() {
+            return;
+        }
+
+        let mut conn = match db_guard.client.get_async_connection().await {
+            Ok(c) => c,
+            Err(e) => {
+                error!("Failed to get Redis connection: {:?}", e);
+                return;
+            }
+        };
+        drop(db_guard);
+
+        match conn.sadd::<_, _, ()>(SITEMAP_URLS_KEY, &normalized_urls).await {
+            Ok(_) => info!("Seeded {} URLs from sitemaps", normalized_urls.len()),
+            Err(e) => error!("Error saving sitemap URLs: {:?}", e),
+        }
+    }
+
+    // Rebuilds `sitemap_orphans`: sitemap URLs that no crawled page links to.
+    pub async fn update_orphans(&self) -> redis::RedisResult<usize> {
+        use crate::utils::{BACKLINKS_PREFIX, SITEMAP_ORPHANS_KEY, SITEMAP_URLS_KEY};
+
+        let db_guard = self.db.lock().await;
+        let mut conn = db_guard.client.get_async_connection().await?;
+        drop(db_guard);
+
+        let sitemap_urls: Vec<String> = conn.smembers(SITEMAP_URLS_KEY).await?;
+        if sitemap_urls.is_empty() {
+            return Ok(0);
+        }
+
+        let mut pipe = redis::pipe();
+        for url in &sitemap_urls {
+            pipe.exists(format!("{}:{}", BACKLINKS_PREFIX, url));
+        }
+        let linked: Vec<bool> = pipe.query_async(&mut conn).await?;
+
+        let orphans: Vec<&String> = sitemap_urls
+            .iter()
+            .zip(linked)
+            .filter(|(_, linked)| !linked)
+            .map(|(url, _)| url)
+            .collect();
+
+        let mut pipe = redis::pipe();
+        pipe.atomic().del(SITEMAP_ORPHANS_KEY);
+        if !orphans.is_empty() {
+            pipe.sadd(SITEMAP_ORPHANS_KEY, &orphans);
+        }
+        pipe.query_async::<_, ()>(&mut conn).await?;
+
+        info!("{} of {} sitemap URLs are not linked from any crawled page", orphans.len(), sitemap_urls.len());
+        Ok(orphans.len())
+    }
+}
@@ -0,0 +1,192 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+use tracing::{info, error};
+use tokio::sync::{Mutex, Notify};
+use tokio::time::sleep;
+use crate::database::Database;
+use crate::metrics::metrics;
+use super::events::{CrawlEvent, CrawlEvents};
+use crate::utils::{MAX_INDEXER_QUEUE_SIZE, MIN_INDEXER_QUEUE_SIZE, RESUME_CRAWL};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackpressureState {
+    #[default]
+    Running,
+    // The indexer queue reached the high watermark; workers wait.
+    Paused,
+    // Resumed by an explicit RESUME_CRAWL while still above the low watermark. Pausing is
+    // re-armed once the queue drains to the low watermark.
+    Overridden,
+}
+
+impl BackpressureState {
+    pub fn is_paused(self) -> bool {
+        self == BackpressureState::Paused
+    }
+}
+
+// Pause once `pages_queue` holds `high` entries, resume once it is back down to `low`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Watermarks {
+    pub high: usize,
+    pub low: usize,
+}
+
+impl Default for Watermarks {
+    fn default() -> Self {
+        Self { high: MAX_INDEXER_QUEUE_SIZE, low: MIN_INDEXER_QUEUE_SIZE }
+    }
+}
+
+impl Watermarks {
+    pub fn new(high: usize, low: usize) -> Result<Self, String> {
+        if high == 0 {
+            return Err("High watermark must be greater than zero".into());
+        }
+        if low >= high {
+            return Err(format!("Low watermark {} must be below the high watermark {}", low, high));
+        }
+        Ok(Self { high, low })
+    }
+
+    pub fn next_state(&self, state: BackpressureState, queue_len: usize, resume_signal: bool) -> BackpressureState {
+        match state {
+            BackpressureState::Running if queue_len >= self.high => BackpressureState::Paused,
+            BackpressureState::Paused | BackpressureState::Overridden if queue_len <= self.low => BackpressureState::Running,
+            BackpressureState::Paused if resume_signal => BackpressureState::Overridden,
+            state => state,
+        }
+    }
+}
+
+// Shared pause switch for the workers, driven by `monitor` and by `pause`/`resume` from the
+// admin API.
+#[derive(Debug, Clone, Default)]
+pub struct Backpressure {
+    pub watermarks: Watermarks,
+    state: Arc<StdMutex<BackpressureState>>,
+    // Paused by hand; independent of the watermarks.
+    held: Arc<AtomicBool>,
+    resumed: Arc<Notify>,
+}
+
+impl Backpressure {
+    pub fn new(watermarks: Watermarks) -> Self {
+        Self { watermarks, ..Default::default() }
+    }
+
+    pub fn state(&self) -> BackpressureState {
+        self.state.lock().map(|state| *state).unwrap_or_default()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_held() || self.state().is_paused()
+    }
+
+    pub fn is_held(&self) -> bool {
+        self.held.load(Ordering::SeqCst)
+    }
+
+    // Pauses the crawl until `resume`, whatever the indexer queue does.
+    pub fn pause(&self) {
+        if !self.held.swap(true, Ordering::SeqCst) {
+            info!("Crawl paused by request");
+        }
+    }
+
+    // `pause` on behalf of `Spider::pause` or the admin API, telling event subscribers.
+    pub fn request_pause(&self, events: &CrawlEvents) {
+        self.pause();
+        events.emit(CrawlEvent::Paused);
+    }
+
+    pub fn request_resume(&self, events: &CrawlEvents) {
+        self.resume();
+        events.emit(CrawlEvent::Resumed);
+    }
+
+    // Lifts a manual pause and overrides a watermark pause, like RESUME_CRAWL.
+    pub fn resume(&self) {
+        let was_held = self.held.swap(false, Ordering::SeqCst);
+
+        if let Ok(mut state) = self.state.lock()
+            && state.is_paused()
+        {
+            *state = BackpressureState::Overridden;
+            metrics().backpressure_paused.set(0);
+        }
+
+        if was_held {
+            info!("Crawl resumed by request");
+        }
+        self.resumed.notify_waiters();
+    }
+
+    // Applies one observation of the indexer queue and returns the new state.
+    pub fn update(&self, queue_len: usize, resume_signal: bool) -> BackpressureState {
+        let Ok(mut state) = self.state.lock() else {
+            return BackpressureState::default();
+        };
+
+        let previous = *state;
+        *state = self.watermarks.next_state(previous, queue_len, resume_signal);
+
+        match (previous.is_paused(), state.is_paused()) {
+            (false, true) => {
+                info!(
+                    "Indexer queue at {} (high watermark {}). Pausing crawl...",
+                    queue_len, self.watermarks.high
+                );
+                metrics().backpressure_pauses.inc();
+                metrics().backpressure_paused.set(1);
+            }
+            (true, false) => {
+                metrics().backpressure_paused.set(0);
+                if *state == BackpressureState::Overridden {
+                    info!("Resume crawl! ({} signal with {} pages queued)", RESUME_CRAWL, queue_len);
+                } else {
+                    info!("Indexer queue down to {}. Resuming crawl...", queue_len);
+                }
+                self.resumed.notify_waiters();
+            }
+            _ => {}
+        }
+
+        *state
+    }
+
+    // Resolves once the crawl is no longer paused.
+    pub async fn wait_resumed(&self) {
+        let resumed = self.resumed.notified();
+        if !self.is_paused() {
+            return;
+        }
+        resumed.await;
+    }
+
+    // Watches the indexer queue length and the signal queue every `interval`. Runs until the
+    // task is dropped.
+    pub async fn monitor(self, db: Arc<Mutex<Database>>, interval: Duration) {
+        loop {
+            let observation = {
+                let mut db = db.lock().await;
+                match db.get_indexer_queue_size().await {
+                    Ok(size) => db.pop_signals().await.map(|signals| (size, signals)),
+                    Err(e) => Err(e),
+                }
+            };
+
+            match observation {
+                Ok((size, signals)) => {
+                    metrics().pages_queue.set(size);
+                    let resume_signal = signals.iter().any(|signal| signal == RESUME_CRAWL);
+                    self.update(size.max(0) as usize, resume_signal);
+                }
+                Err(e) => error!("Error checking indexer queue: {:?}", e),
+            }
+
+            sleep(interval).await;
+        }
+    }
+}
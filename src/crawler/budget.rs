@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+// Second-level suffixes under which registrations happen one level deeper
+// ("example.co.uk", not "co.uk"). Not a full public suffix list.
+const MULTI_LABEL_SUFFIXES: [&str; 12] = [
+    "co.uk", "org.uk", "ac.uk", "gov.uk", "com.au", "net.au", "org.au", "co.nz", "co.jp", "com.br", "com.cn", "co.in",
+];
+
+// Limits for a whole crawl, persisted in Redis so they hold across batches and restarts.
+// Zero means unlimited. Per-host page caps live in the frontier policy, since they are
+// enforced when URLs are dispatched.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CrawlBudget {
+    pub max_pages: u64,
+    pub max_bytes: u64,
+    pub max_duration: Option<Duration>,
+    pub host_max_bytes: u64,
+    pub host_byte_budgets: HashMap<String, u64>,
+    pub domain_max_pages: u64,
+    pub domain_page_budgets: HashMap<String, u64>,
+    pub domain_max_bytes: u64,
+    pub domain_byte_budgets: HashMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BudgetUsage {
+    pub pages: u64,
+    pub bytes: u64,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HostUsage {
+    pub host_bytes: u64,
+    pub domain_pages: u64,
+    pub domain_bytes: u64,
+}
+
+fn over(used: u64, limit: u64) -> bool {
+    limit > 0 && used >= limit
+}
+
+impl CrawlBudget {
+    // Why the crawl as a whole must stop, if it must.
+    pub fn exceeded(&self, usage: &BudgetUsage) -> Option<String> {
+        if over(usage.pages, self.max_pages) {
+            return Some(format!("page budget of {} reached", self.max_pages));
+        }
+
+        if over(usage.bytes, self.max_bytes) {
+            return Some(format!("byte budget of {} reached", self.max_bytes));
+        }
+
+        match self.max_duration {
+            Some(max_duration) if usage.elapsed >= max_duration => {
+                Some(format!("time budget of {}s reached", max_duration.as_secs()))
+            }
+            _ => None,
+        }
+    }
+
+    // Why URLs on `host` must not be fetched any more, if they must not.
+    pub fn host_exceeded(&self, host: &str, usage: &HostUsage) -> Option<String> {
+        let domain = registrable_domain(host);
+
+        let host_max_bytes = self.host_byte_budgets.get(host).copied().unwrap_or(self.host_max_bytes);
+        if over(usage.host_bytes, host_max_bytes) {
+            return Some(format!("byte budget of {} for host {} reached", host_max_bytes, host));
+        }
+
+        let domain_max_pages = self.domain_page_budgets.get(domain).copied().unwrap_or(self.domain_max_pages);
+        if over(usage.domain_pages, domain_max_pages) {
+            return Some(format!("page budget of {} for domain {} reached", domain_max_pages, domain));
+        }
+
+        let domain_max_bytes = self.domain_byte_budgets.get(domain).copied().unwrap_or(self.domain_max_bytes);
+        if over(usage.domain_bytes, domain_max_bytes) {
+            return Some(format!("byte budget of {} for domain {} reached", domain_max_bytes, domain));
+        }
+
+        None
+    }
+}
+
+// "blog.news.example.co.uk" -> "example.co.uk", "www.example.com" -> "example.com"
+pub fn registrable_domain(host: &str) -> &str {
+    let host = host.split(':').next().unwrap_or(host).trim_end_matches('.');
+
+    if host.parse::<std::net::Ipv4Addr>().is_ok() {
+        return host;
+    }
+
+    let suffix_labels = if MULTI_LABEL_SUFFIXES.iter().any(|suffix| host.ends_with(&format!(".{}", suffix))) { 3 } else { 2 };
+
+    match host.rmatch_indices('.').nth(suffix_labels - 1) {
+        Some((index, _)) => &host[index + 1..],
+        None => host,
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::database::frontier::host_of;
+use crate::utils::{is_valid_url, normalize_url};
+
+// Which `<link rel="canonical">` targets may replace a page's own URL as its dedupe key.
+// Cross-host canonicals are ignored by default, so one site cannot claim (or hide)
+// another site's pages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CanonicalPolicy {
+    Ignore,
+    #[default]
+    SameHost,
+    AnyHost,
+}
+
+impl FromStr for CanonicalPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "off" | "ignore" | "false" => Ok(Self::Ignore),
+            "same-host" | "same_host" | "true" => Ok(Self::SameHost),
+            "any-host" | "any_host" | "any" => Ok(Self::AnyHost),
+            other => Err(format!("Invalid canonical policy: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for CanonicalPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ignore => write!(f, "off"),
+            Self::SameHost => write!(f, "same-host"),
+            Self::AnyHost => write!(f, "any-host"),
+        }
+    }
+}
+
+impl CanonicalPolicy {
+    pub fn dedupe_key(&self, normalized_url: &str, canonical: &str) -> String {
+        if *self == Self::Ignore || canonical.is_empty() || !is_valid_url(canonical) {
+            return normalized_url.to_string();
+        }
+
+        let normalized_canonical = match normalize_url(canonical) {
+            Ok(url) => url,
+            Err(_) => return normalized_url.to_string(),
+        };
+
+        if *self == Self::SameHost && host_of(&normalized_canonical) != host_of(normalized_url) {
+            return normalized_url.to_string();
+        }
+
+        normalized_canonical
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+// How many URLs and images are fetched at once. Workers hold a permit while they crawl a URL,
+// so a new limit applies to running workers as soon as they finish their current URL; each
+// batch spawns `limit()` workers.
+#[derive(Debug, Clone)]
+pub struct Concurrency {
+    limit: Arc<AtomicUsize>,
+    permits: Arc<Semaphore>,
+}
+
+impl Concurrency {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit: Arc::new(AtomicUsize::new(limit)),
+            permits: Arc::new(Semaphore::new(limit)),
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.load(Ordering::SeqCst)
+    }
+
+    // Returns the previous limit. Lowering it takes effect as busy permits are released.
+    pub fn set_limit(&self, limit: usize) -> usize {
+        let previous = self.limit.swap(limit, Ordering::SeqCst);
+
+        if limit > previous {
+            self.permits.add_permits(limit - previous);
+        } else if limit < previous {
+            let excess = previous - limit;
+            let remaining = excess - self.permits.forget_permits(excess);
+            if remaining > 0 {
+                let permits = self.permits.clone();
+                tokio::spawn(async move {
+                    if let Ok(permit) = permits.acquire_many_owned(remaining as u32).await {
+                        permit.forget();
+                    }
+                });
+            }
+        }
+
+        previous
+    }
+
+    pub async fn acquire(&self) -> Option<SemaphorePermit<'_>> {
+        self.permits.acquire().await.ok()
+    }
+
+    pub fn semaphore(&self) -> Arc<Semaphore> {
+        self.permits.clone()
+    }
+
+    // Permits not held by a worker right now.
+    pub fn available(&self) -> usize {
+        self.permits.available_permits()
+    }
+}
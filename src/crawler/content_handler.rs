@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use scraper::Html;
+use tracing::{error, warn};
+use crate::pages::{create_page, Page, PageMetadata, StructuredData};
+use crate::utils::html_text::collapse_whitespace;
+use crate::utils::FEED_MIME_TYPES;
+use super::get_feed_content::get_feed_content;
+use super::get_main_content::get_main_content_from_document;
+use super::get_page_data::{mime_type, PageData};
+use super::get_page_metadata::get_page_metadata_from_document;
+use super::get_pdf_content::get_pdf_content;
+use super::get_structured_data::get_structured_data_from_document;
+use super::get_urls_from_html::{
+    get_feed_links_from_document, get_links_from_document, get_meta_refresh_from_document, ImageMap, LinkEdges, MetaRefresh,
+};
+use super::robots_directives::{get_robots_directives_from_document, RobotsDirectives};
+
+// What a handler extracted from one response: the page to store, any URLs to add to the
+// frontier and, when the format carries it, page-level metadata. The remaining fields are
+// filled by HTML-like formats; the crawler treats every handler's output the same way.
+#[derive(Debug, Clone)]
+pub struct HandledContent {
+    pub page: Page,
+    pub links: Vec<String>,
+    pub metadata: Option<PageMetadata>,
+    // Recorded in the link graph; targets are enqueued unless their rel says nofollow.
+    pub link_edges: LinkEdges,
+    pub images: ImageMap,
+    pub feeds: Vec<String>,
+    pub structured_data: Option<StructuredData>,
+    // Directives from the document itself, merged with the X-Robots-Tag header.
+    pub robots: RobotsDirectives,
+    pub refresh: Option<MetaRefresh>,
+}
+
+impl HandledContent {
+    pub fn new(page: Page) -> Self {
+        Self {
+            page,
+            links: Vec::new(),
+            metadata: None,
+            link_edges: LinkEdges::new(),
+            images: ImageMap::new(),
+            feeds: Vec::new(),
+            structured_data: None,
+            robots: RobotsDirectives::default(),
+            refresh: None,
+        }
+    }
+}
+
+// Handlers run on tokio's blocking pool, so they may parse synchronously.
+pub trait ContentHandler: Send + Sync {
+    fn handle(&self, raw_url: &str, normalized_url: &str, data: &PageData) -> Result<HandledContent, String>;
+}
+
+// Handlers keyed by MIME type. A `type/*` entry matches any subtype without its own handler.
+#[derive(Clone)]
+pub struct ContentHandlers {
+    handlers: HashMap<String, Arc<dyn ContentHandler>>,
+}
+
+impl Default for ContentHandlers {
+    fn default() -> Self {
+        let mut handlers = Self::empty();
+
+        let html: Arc<dyn ContentHandler> = Arc::new(HtmlHandler);
+        let feed: Arc<dyn ContentHandler> = Arc::new(FeedHandler);
+
+        handlers.register("text/html", html.clone());
+        handlers.register("application/xhtml+xml", html);
+        handlers.register("text/plain", Arc::new(PlainTextHandler));
+        handlers.register("application/pdf", Arc::new(PdfHandler));
+
+        for mime in FEED_MIME_TYPES.iter().chain(&["application/xml", "text/xml"]) {
+            handlers.register(mime, feed.clone());
+        }
+
+        handlers
+    }
+}
+
+impl fmt::Debug for ContentHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut mime_types: Vec<_> = self.handlers.keys().collect();
+        mime_types.sort();
+        f.debug_struct("ContentHandlers").field("mime_types", &mime_types).finish()
+    }
+}
+
+impl ContentHandlers {
+    pub fn empty() -> Self {
+        Self { handlers: HashMap::new() }
+    }
+
+    // Registers `handler` for `mime`, replacing any existing handler for it.
+    pub fn register(&mut self, mime: &str, handler: Arc<dyn ContentHandler>) {
+        self.handlers.insert(mime_type(mime), handler);
+    }
+
+    pub fn resolve(&self, content_type: &str) -> Option<Arc<dyn ContentHandler>> {
+        let mime = mime_type(content_type);
+
+        if let Some(handler) = self.handlers.get(&mime) {
+            return Some(handler.clone());
+        }
+
+        let (major, _) = mime.split_once('/')?;
+        self.handlers.get(&format!("{}/*", major)).cloned()
+    }
+
+    pub fn accepts(&self, content_type: &str) -> bool {
+        self.resolve(content_type).is_some()
+    }
+}
+
+// Links with their anchors, images, feeds, meta robots and refresh, metadata, structured
+// data and the main content. The page keeps the simplified content HTML; the crawler drops it
+// unless `simplified_html` is on.
+pub struct HtmlHandler;
+
+impl ContentHandler for HtmlHandler {
+    fn handle(&self, raw_url: &str, normalized_url: &str, data: &PageData) -> Result<HandledContent, String> {
+        let document = Html::parse_document(&data.body);
+
+        let (link_edges, images) = get_links_from_document(&document, raw_url).map_err(|e| e.to_string())?;
+        let main_content = get_main_content_from_document(&document);
+        let page = create_page(normalized_url.to_string(), data.body.clone(), data.content_type.clone(), data.status_code as i32)
+            .with_content(main_content.text, main_content.html);
+
+        let metadata = match get_page_metadata_from_document(&document, raw_url) {
+            Ok(mut metadata) => {
+                metadata.normalized_url = normalized_url.to_string();
+                Some(metadata)
+            }
+            Err(err) => {
+                error!("Error extracting page metadata: {}", err);
+                None
+            }
+        };
+
+        let structured_data = match get_structured_data_from_document(&document, raw_url) {
+            Ok(mut structured_data) => {
+                for warning in &structured_data.warnings {
+                    warn!("{}: {}", normalized_url, warning);
+                }
+                structured_data.normalized_url = normalized_url.to_string();
+                Some(structured_data)
+            }
+            Err(err) => {
+                error!("Error extracting structured data: {}", err);
+                None
+            }
+        };
+
+        Ok(HandledContent {
+            link_edges,
+            images,
+            feeds: get_feed_links_from_document(&document, raw_url),
+            structured_data,
+            robots: get_robots_directives_from_document(&document),
+            refresh: get_meta_refresh_from_document(&document, raw_url),
+            metadata,
+            ..HandledContent::new(page)
+        })
+    }
+}
+
+pub struct PlainTextHandler;
+
+impl ContentHandler for PlainTextHandler {
+    fn handle(&self, _raw_url: &str, normalized_url: &str, data: &PageData) -> Result<HandledContent, String> {
+        let page = create_page(normalized_url.to_string(), data.body.clone(), data.content_type.clone(), data.status_code as i32)
+            .with_content(collapse_whitespace(&data.body), String::new());
+
+        Ok(HandledContent::new(page))
+    }
+}
+
+pub struct PdfHandler;
+
+impl ContentHandler for PdfHandler {
+    fn handle(&self, _raw_url: &str, normalized_url: &str, data: &PageData) -> Result<HandledContent, String> {
+        let pdf = get_pdf_content(&data.bytes)?;
+
+        let mut metadata = PageMetadata::new(normalized_url.to_string());
+        metadata.title = pdf.title.clone();
+        metadata.description = pdf.subject.clone();
+        metadata.keywords = pdf.keywords.clone();
+        metadata.word_count = pdf.text.split_whitespace().count();
+
+        // The raw PDF bytes are not stored; the page body is the extracted text.
+        let page = create_page(normalized_url.to_string(), pdf.text.clone(), data.content_type.clone(), data.status_code as i32)
+            .with_content(pdf.text, String::new());
+
+        Ok(HandledContent {
+            metadata: Some(metadata),
+            ..HandledContent::new(page)
+        })
+    }
+}
+
+pub struct FeedHandler;
+
+impl ContentHandler for FeedHandler {
+    fn handle(&self, raw_url: &str, normalized_url: &str, data: &PageData) -> Result<HandledContent, String> {
+        let feed = get_feed_content(&data.body, raw_url)?;
+
+        let mut metadata = PageMetadata::new(normalized_url.to_string());
+        metadata.title = feed.title;
+        metadata.word_count = feed.text.split_whitespace().count();
+
+        let page = create_page(normalized_url.to_string(), data.body.clone(), data.content_type.clone(), data.status_code as i32)
+            .with_content(feed.text, String::new());
+
+        Ok(HandledContent {
+            links: feed.links,
+            metadata: Some(metadata),
+            ..HandledContent::new(page)
+        })
+    }
+}
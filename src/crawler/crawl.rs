@@ -1,99 +1,477 @@
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
-use log::{info, error};
-use crate::database::Database;
-use crate::pages::create_page;
-use crate::utils::{is_valid_url, MIN_SCORE, MAX_SCORE};
+use tokio::time::sleep;
+use tracing::{debug, info, info_span, error, field, Instrument, Span};
+use crate::database::{Database, FetchStat};
+use crate::database::frontier::{host_of, PoppedUrl};
+use crate::logging::AUDIT_TARGET;
+use crate::metrics::metrics;
+use crate::pages::FailedUrl;
+use crate::utils::{is_valid_url, normalize_url, MIN_SCORE, MAX_SCORE};
+use super::priority::{bounded_score, LinkCandidate};
+use super::content_handler::ContentHandler;
 use super::crawler::CrawlerConfig;
-use super::get_page_data::get_page_data;
-use super::get_urls_from_html::get_urls_from_html;
+use super::events::CrawlEvent;
+use super::get_page_data::PageData;
 
 impl CrawlerConfig {
+    // One worker: pops and crawls URLs until the queue is empty, the budget runs out or the
+    // crawl stops. Any number of workers can share the crawler; each URL takes a permit.
     pub async fn crawl(&self, db: &Arc<Mutex<Database>>) {
-        let _guard = self.wg.lock().await;
-
         loop {
-            info!("Crawling...");
+            if self.shutdown.is_triggered() {
+                info!("Shutdown requested, worker stopping");
+                return;
+            }
+
+            if self.backpressure.is_paused() {
+                info!("Crawl paused by backpressure, waiting for the indexer...");
+                tokio::select! {
+                    _ = self.backpressure.wait_resumed() => {}
+                    _ = self.shutdown.wait() => {}
+                }
+                continue;
+            }
+
+            let permit = tokio::select! {
+                permit = self.concurrency.acquire() => permit,
+                _ = self.shutdown.wait() => None,
+            };
+            let Some(permit) = permit else {
+                return;
+            };
 
             if self.max_pages_reached().await {
                 info!("Maximum number of pages reached");
                 return;
             }
 
-            info!("Waiting for message queue...");
-
-            let (raw_url, depth, normalized_url) = match db.lock().await.pop_url().await {
-                Ok(tuple) => tuple,
+            let popped = db.lock().await.pop_url().await;
+            let url = match popped {
+                Ok(PoppedUrl::Ready { raw_url, score, depth, normalized_url }) => QueuedUrl { raw_url, normalized_url, score, depth },
+                Ok(PoppedUrl::Wait(delay)) => {
+                    drop(permit);
+                    sleep(delay.clamp(Duration::from_millis(10), Duration::from_secs(1))).await;
+                    continue;
+                }
+                Ok(PoppedUrl::Empty) => {
+                    info!("No more URLs in the queue");
+                    return;
+                }
                 Err(err) => {
-                    error!("No more URLs in the queue: {}", err);
+                    error!("Error popping URL: {}", err);
                     return;
                 }
             };
 
-            info!("Popped URL: {} | Depth Level: {} | Normalized URL: {}", raw_url, depth, normalized_url);
+            let host = host_of(&url.normalized_url);
+            let usage = db.lock().await.get_usage(host).await;
+            match usage {
+                Ok((usage, host_usage)) => {
+                    if let Some(reason) = self.budget.exceeded(&usage) {
+                        info!("Crawl budget exhausted: {}", reason);
+                        if let Err(err) = db.lock().await.push_url(&url.raw_url, url.score).await {
+                            error!("Error requeueing {}: {}", url.raw_url, err);
+                        }
+                        return;
+                    }
 
-            match db.lock().await.has_url_been_visited(&normalized_url).await {
-                Ok(true) => {
-                    info!("Skipping {} - already visited", normalized_url);
-                    continue;
+                    if let Some(reason) = self.budget.host_exceeded(host, &host_usage) {
+                        let dropped = db.lock().await.drop_host(host).await;
+                        match dropped {
+                            Ok(dropped) => info!("Skipping {} - {}; dropped {} more queued URLs", url.normalized_url, reason, dropped),
+                            Err(err) => error!("Error dropping host {}: {}", host, err),
+                        }
+                        metrics().rejections.inc("host_budget");
+                        self.events.emit(CrawlEvent::Skipped { url: url.raw_url, reason: "host_budget".to_string() });
+                        continue;
+                    }
                 }
-                Ok(false) => {}
-                Err(err) => {
-                    error!("Error checking visit status: {}", err);
-                    continue;
+                Err(err) => error!("Error reading crawl budget: {}", err),
+            }
+
+            self.priority_policy.dispatched(&url.normalized_url);
+            let _in_flight = self.in_flight.track(&url.normalized_url, &url.raw_url, url.score);
+
+            let span = info_span!(
+                "url",
+                url = %url.raw_url,
+                depth = url.depth,
+                host = host_of(&url.normalized_url),
+                attempt = field::Empty,
+            );
+            self.crawl_url(db, url).instrument(span).await;
+        }
+    }
+
+    // Fetches one popped URL, stores what it yields and enqueues its links.
+    async fn crawl_url(&self, db: &Arc<Mutex<Database>>, url: QueuedUrl) {
+        debug!("Popped {}", url.normalized_url);
+
+        match db.lock().await.has_url_been_visited(&url.normalized_url).await {
+            Ok(true) => {
+                info!("Skipping {} - already visited", url.normalized_url);
+                return;
+            }
+            Ok(false) => {}
+            Err(err) => {
+                error!("Error checking visit status: {}", err);
+                return;
+            }
+        }
+
+        if !self.scope.allows(&url.raw_url) {
+            info!("Skipping {} - out of scope", url.normalized_url);
+            metrics().rejections.inc("scope");
+            self.events.emit(CrawlEvent::Skipped { url: url.raw_url, reason: "scope".to_string() });
+            return;
+        }
+
+        let host = host_of(&url.normalized_url);
+        let attempt = match db.lock().await.record_attempt(&url.normalized_url).await {
+            Ok(attempt) => attempt,
+            Err(err) => {
+                error!("Error recording fetch attempt: {}", err);
+                1
+            }
+        };
+        Span::current().record("attempt", attempt);
+
+        info!("Fetching");
+
+        let started = Instant::now();
+        let accept = |content_type: &str| self.content_handlers.accepts(content_type);
+        let fetched = self.fetcher.fetch(&url.raw_url, &accept).await;
+        let audit = FetchAudit {
+            url: &url.raw_url,
+            normalized_url: &url.normalized_url,
+            host,
+            depth: url.depth,
+            attempt,
+            duration_ms: started.elapsed().as_millis() as u64,
+        };
+        let page_data = match fetched {
+            Ok(data) => {
+                audit.fetched(&data);
+                record_fetch_stats(db, audit.stat(Some(data.status_code), &data.content_type, data.redirected, None)).await;
+                self.events.emit(CrawlEvent::Fetched {
+                    url: url.raw_url.clone(),
+                    normalized_url: url.normalized_url.clone(),
+                    depth: url.depth,
+                    status: data.status_code,
+                    content_type: data.content_type.clone(),
+                    bytes: data.bytes.len(),
+                    duration_ms: audit.duration_ms,
+                });
+                data
+            }
+            Err(failure) => {
+                audit.failed(&failure.kind, failure.status, &failure.message);
+                record_fetch_stats(db, audit.stat(failure.status, "", false, Some(&failure.kind))).await;
+                error!("Error fetching page data: {}", failure);
+                self.events.emit(CrawlEvent::Failed {
+                    url: url.raw_url.clone(),
+                    normalized_url: url.normalized_url.clone(),
+                    depth: url.depth,
+                    error_kind: failure.kind.clone(),
+                    error: failure.message.clone(),
+                });
+                let failure = FailedUrl::new(url.normalized_url.clone(), url.raw_url.clone(), url.score, failure.message);
+                if let Err(err) = db.lock().await.record_failure(&failure).await {
+                    error!("Error recording failure: {}", err);
                 }
+                return;
             }
+        };
 
-            info!("Crawling from {} ({})...", normalized_url, raw_url);
+        if let Err(err) = db.lock().await.record_fetch(host, page_data.bytes.len() as u64).await {
+            error!("Error recording fetch: {}", err);
+        }
 
-            let (html, status_code, content_type) = match get_page_data(&raw_url).await {
-                Ok(data) => data,
-                Err(err) => {
-                    error!("Error fetching page data: {}", err);
-                    continue;
+        let handler = match self.content_handlers.resolve(&page_data.content_type) {
+            Some(handler) => handler,
+            None => {
+                error!("No content handler for {}", page_data.content_type);
+                return;
+            }
+        };
+
+        self.crawl_content(db, handler, &url, page_data).await;
+        self.priority_policy.crawled(&url.normalized_url);
+    }
+
+    // Stores a response through its content handler and enqueues the links it found. The page
+    // is marked visited last, so a crawl interrupted before its links were queued fetches it again.
+    async fn crawl_content(
+        &self,
+        db: &Arc<Mutex<Database>>,
+        handler: Arc<dyn ContentHandler>,
+        url: &QueuedUrl,
+        page_data: PageData,
+    ) {
+        let (raw_url, normalized_url) = (url.raw_url.as_str(), url.normalized_url.as_str());
+
+        // Parsing HTML or extracting PDF text is CPU-bound; keep it off the async workers.
+        let (owned_raw_url, owned_normalized_url) = (raw_url.to_string(), normalized_url.to_string());
+        let handled = tokio::task::spawn_blocking(move || {
+            let content = handler.handle(&owned_raw_url, &owned_normalized_url, &page_data);
+            (content, page_data)
+        })
+        .await;
+        let (content, page_data) = match handled {
+            Ok(handled) => handled,
+            Err(err) => {
+                error!("Content handler task failed: {:?}", err);
+                return;
+            }
+        };
+        let content = match content {
+            Ok(content) => content,
+            Err(err) => {
+                error!("Error handling {} content: {}", page_data.content_type, err);
+                return;
+            }
+        };
+
+        metrics().links_discovered.add((content.links.len() + content.link_edges.len()) as u64);
+
+        if let Some(refresh) = content.refresh.filter(|refresh| refresh.redirects_from(normalized_url)) {
+            let target = refresh.url;
+            info!("Meta refresh from {} to {}", normalized_url, target);
+
+            if is_valid_url(&target) {
+                let bounded_score = url.score.clamp(MIN_SCORE as f64, MAX_SCORE as f64);
+                let pushed = db.lock().await.push_urls(&[(target.clone(), bounded_score)], url.depth).await;
+                if let Err(err) = pushed {
+                    error!("Error pushing refresh target {}: {}", target, err);
+                    return;
                 }
-            };
+            }
+
+            if let Err(err) = db.lock().await.visit_page(normalized_url).await {
+                error!("Error marking page visited: {}", err);
+            }
+            return;
+        }
 
-            let (links, images_map) = match get_urls_from_html(&html, &raw_url) {
-                Ok(data) => data,
+        let canonical = content.metadata.as_ref().map(|m| m.canonical.as_str()).unwrap_or("");
+        let page_key = self.canonical_policy.dedupe_key(normalized_url, canonical);
+
+        if page_key != normalized_url {
+            match db.lock().await.has_url_been_visited(&page_key).await {
+                Ok(true) => {
+                    info!("Skipping {} - duplicate of canonical {}", normalized_url, page_key);
+                    let _ = db.lock().await.visit_page(normalized_url).await;
+                    return;
+                }
+                Ok(false) => {}
                 Err(err) => {
-                    error!("Error extracting URLs from HTML: {}", err);
-                    continue;
+                    error!("Error checking visit status: {}", err);
+                    return;
                 }
-            };
+            }
+        }
 
-            self.add_images(&normalized_url, &images_map).await;
-            self.update_links(&normalized_url, &links).await;
+        let robots = page_data.robots.merge(content.robots);
 
-            let page = create_page(normalized_url.clone(), html, content_type, status_code as i32);
+        self.update_links(&page_key, &content.link_edges).await;
+        self.add_feeds(&content.feeds).await;
+
+        if self.robots_policy.should_index(&robots) {
+            let mut page = content.page;
+            page.normalized_url = page_key.clone();
+            if !self.simplified_html {
+                page.content_html.clear();
+            }
 
             if let Err(err) = self.add_page(page).await {
                 error!("Error adding page: {}", err);
-                continue;
+                // Other workers filled the batch while this page was in flight; crawl it again
+                // after the flush.
+                if self.max_pages_reached().await
+                    && let Err(err) = db.lock().await.push_url(raw_url, url.score).await
+                {
+                    error!("Error requeueing {}: {}", raw_url, err);
+                }
+                return;
             }
 
-            if let Err(err) = db.lock().await.visit_page(&normalized_url).await {
-                error!("Error marking page visited: {}", err);
-                continue;
+            if let Some(mut metadata) = content.metadata {
+                metadata.normalized_url = page_key.clone();
+                self.add_metadata(&page_key, metadata).await;
             }
 
-            info!("Adding links from {}...", normalized_url);
+            if let Some(mut structured_data) = content.structured_data {
+                structured_data.normalized_url = page_key.clone();
+                self.add_structured_data(&page_key, structured_data).await;
+            }
 
-            for raw_link in links {
-                if !is_valid_url(&raw_link) {
-                    continue;
+            self.add_images(&page_key, &content.images).await;
+        } else {
+            info!("Not indexing {} - noindex", normalized_url);
+            metrics().rejections.inc("noindex");
+        }
+
+        if self.robots_policy.should_follow(&robots) {
+            let follow_edges = content.link_edges.into_iter().filter(|(_, edge)| {
+                let follow = self.robots_policy.should_follow_link(&edge.rel);
+                if !follow {
+                    metrics().rejections.inc("rel_nofollow");
                 }
+                follow
+            });
+            let links: Vec<String> = content.links.into_iter().chain(follow_edges.map(|(raw_link, _)| raw_link)).collect();
 
-                let score = match db.lock().await.exists_in_queue(&raw_link).await {
-                    Ok(Some(existing_score)) => existing_score,
-                    Ok(None) => depth + 1.0,
-                    Err(_) => depth + 1.0,
+            info!("Adding {} links from {}...", links.len(), normalized_url);
+            if let Err(err) = self.enqueue_links(db, url, links).await {
+                // Left unvisited and recorded as failed, so `requeue-failed` crawls it again.
+                error!("Error enqueueing links from {}: {}", normalized_url, err);
+                let failure = FailedUrl::new(normalized_url.to_string(), raw_url.to_string(), url.score, err);
+                if let Err(err) = db.lock().await.record_failure(&failure).await {
+                    error!("Error recording failure: {}", err);
+                }
+                return;
+            }
+        } else {
+            info!("Not following links from {} - nofollow", normalized_url);
+            metrics().rejections.inc("nofollow");
+        }
+
+        if let Err(err) = db.lock().await.visit_page(normalized_url).await {
+            error!("Error marking page visited: {}", err);
+            return;
+        }
+
+        if page_key != normalized_url {
+            let _ = db.lock().await.visit_page(&page_key).await;
+        }
+    }
+
+    // Scores and pushes the links of `source` one level deeper, skipping visited pages and
+    // links back to the source itself.
+    async fn enqueue_links(
+        &self,
+        db: &Arc<Mutex<Database>>,
+        source: &QueuedUrl,
+        links: impl IntoIterator<Item = String>,
+    ) -> Result<(), String> {
+        let source_url = source.normalized_url.as_str();
+        let links: Vec<String> = links
+            .into_iter()
+            .filter(|link| {
+                if !is_valid_url(link) {
+                    metrics().rejections.inc("invalid_url");
+                    return false;
+                }
+                if normalize_url(link).is_ok_and(|normalized| normalized == source_url) {
+                    return false;
+                }
+                if !self.scope.allows(link) {
+                    metrics().rejections.inc("scope");
+                    return false;
+                }
+                true
+            })
+            .collect();
+
+        if links.is_empty() {
+            return Ok(());
+        }
+
+        let states = db.lock().await.get_link_states(&links).await.map_err(|e| e.to_string())?;
+
+        let scored: Vec<(String, f64)> = links
+            .iter()
+            .zip(states)
+            .filter(|(_, (visited, _))| !visited)
+            .map(|(raw_link, (_, existing_score))| {
+                let candidate = LinkCandidate {
+                    source_url,
+                    target_url: raw_link,
+                    parent_score: source.score,
+                    outlink_count: links.len(),
+                    queued: existing_score.is_some(),
                 };
+                (raw_link.clone(), bounded_score(self.priority_policy.as_ref(), &candidate, existing_score))
+            })
+            .collect();
 
-                let bounded_score = score.clamp(MIN_SCORE as f64, MAX_SCORE as f64);
+        let pushed = db.lock().await.push_urls(&scored, source.depth + 1.0).await.map_err(|e| e.to_string())?;
+        metrics().links_admitted.add(pushed.len() as u64);
+        Ok(())
+    }
+}
 
-                let _ = db.lock().await.push_url(&raw_link, bounded_score).await;
-            }
+// A URL taken from the queue: `score` orders the queue, `depth` counts the links followed
+// from a seed.
+struct QueuedUrl {
+    raw_url: String,
+    normalized_url: String,
+    score: f64,
+    depth: f64,
+}
+
+async fn record_fetch_stats(db: &Arc<Mutex<Database>>, stat: FetchStat) {
+    if let Err(err) = db.lock().await.record_fetch_stats(&stat).await {
+        error!("Error recording fetch stats: {}", err);
+    }
+}
+
+// One line of the audit log per fetch, emitted under `AUDIT_TARGET`.
+struct FetchAudit<'a> {
+    url: &'a str,
+    normalized_url: &'a str,
+    host: &'a str,
+    depth: f64,
+    attempt: u64,
+    duration_ms: u64,
+}
+
+impl FetchAudit<'_> {
+    fn stat(&self, status: Option<u16>, content_type: &str, redirected: bool, error: Option<&str>) -> FetchStat {
+        FetchStat {
+            host: self.host.to_string(),
+            depth: self.depth,
+            duration_ms: self.duration_ms,
+            status,
+            content_type: content_type.to_string(),
+            redirected,
+            error: error.map(str::to_string),
         }
     }
+
+    fn fetched(&self, page_data: &PageData) {
+        info!(
+            target: AUDIT_TARGET,
+            url = self.url,
+            normalized_url = self.normalized_url,
+            host = self.host,
+            depth = self.depth,
+            attempt = self.attempt,
+            duration_ms = self.duration_ms,
+            outcome = "fetched",
+            status = page_data.status_code,
+            content_type = %page_data.content_type,
+            bytes = page_data.bytes.len(),
+            "fetch"
+        );
+    }
+
+    fn failed(&self, kind: &str, status: Option<u16>, error: &str) {
+        info!(
+            target: AUDIT_TARGET,
+            url = self.url,
+            normalized_url = self.normalized_url,
+            host = self.host,
+            depth = self.depth,
+            attempt = self.attempt,
+            duration_ms = self.duration_ms,
+            outcome = "failed",
+            status,
+            error_kind = kind,
+            error,
+            "fetch"
+        );
+    }
 }
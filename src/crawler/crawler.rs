@@ -1,8 +1,22 @@
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::{Mutex, Semaphore};
-use crate::pages::{Page, PageNode, Image};
+use tokio::sync::Mutex;
+use tracing::error;
+use crate::database::Database;
+use crate::pages::{Feed, Page, PageMetadata, PageNode, Image, ImageData, LinkEdge, StructuredData};
 use crate::utils::{is_valid_url, normalize_url};
+use super::backpressure::Backpressure;
+use super::budget::CrawlBudget;
+use super::canonical::CanonicalPolicy;
+use super::concurrency::Concurrency;
+use super::content_handler::ContentHandlers;
+use super::events::CrawlEvents;
+use super::fetcher::{Fetcher, HttpFetcher};
+use super::priority::{Bfs, PriorityPolicy};
+use super::get_urls_from_html::{ImageMap, LinkEdges};
+use super::robots_directives::RobotsPolicy;
+use super::scope::ScopeRules;
+use super::shutdown::{InFlight, Shutdown};
 
 #[derive(Clone)]
 pub struct CrawlerConfig {
@@ -10,9 +24,26 @@ pub struct CrawlerConfig {
     pub outlinks: Arc<Mutex<HashMap<String, PageNode>>>,
     pub backlinks: Arc<Mutex<HashMap<String, PageNode>>>,
     pub images: Arc<Mutex<HashMap<String, Vec<Image>>>>,
+    pub image_data: Arc<Mutex<HashMap<String, ImageData>>>,
+    pub metadata: Arc<Mutex<HashMap<String, PageMetadata>>>,
+    pub structured_data: Arc<Mutex<HashMap<String, StructuredData>>>,
+    pub feeds: Arc<Mutex<HashMap<String, Feed>>>,
     pub max_pages: usize,
-    pub concurrency_limit: Arc<Semaphore>,
-    pub wg: Arc<Mutex<()>>,
+    pub budget: CrawlBudget,
+    pub simplified_html: bool,
+    pub fetch_images: bool,
+    pub robots_policy: RobotsPolicy,
+    pub canonical_policy: CanonicalPolicy,
+    pub content_handlers: ContentHandlers,
+    pub fetcher: Arc<dyn Fetcher>,
+    pub events: CrawlEvents,
+    pub priority_policy: Arc<dyn PriorityPolicy>,
+    pub backpressure: Backpressure,
+    pub shutdown: Shutdown,
+    pub in_flight: InFlight,
+    pub scope: ScopeRules,
+    // Can be changed while the crawl runs.
+    pub concurrency: Concurrency,
 }
 
 impl CrawlerConfig {
@@ -22,9 +53,25 @@ impl CrawlerConfig {
             outlinks: Arc::new(Mutex::new(HashMap::new())),
             backlinks: Arc::new(Mutex::new(HashMap::new())),
             images: Arc::new(Mutex::new(HashMap::new())),
+            image_data: Arc::new(Mutex::new(HashMap::new())),
+            metadata: Arc::new(Mutex::new(HashMap::new())),
+            structured_data: Arc::new(Mutex::new(HashMap::new())),
+            feeds: Arc::new(Mutex::new(HashMap::new())),
             max_pages,
-            concurrency_limit: Arc::new(Semaphore::new(max_concurrency)),
-            wg: Arc::new(Mutex::new(())),
+            budget: CrawlBudget::default(),
+            simplified_html: false,
+            fetch_images: false,
+            robots_policy: RobotsPolicy::default(),
+            canonical_policy: CanonicalPolicy::default(),
+            content_handlers: ContentHandlers::default(),
+            fetcher: Arc::new(HttpFetcher),
+            events: CrawlEvents::default(),
+            priority_policy: Arc::new(Bfs),
+            backpressure: Backpressure::default(),
+            shutdown: Shutdown::new(),
+            in_flight: InFlight::new(),
+            scope: ScopeRules::new(),
+            concurrency: Concurrency::new(max_concurrency),
         }
     }
 
@@ -36,6 +83,17 @@ impl CrawlerConfig {
         self.pages.lock().await.len() >= self.max_pages
     }
 
+    // Why the crawl must stop, if its persisted budget has run out.
+    pub async fn budget_exhausted(&self, db: &Arc<Mutex<Database>>) -> Option<String> {
+        match db.lock().await.get_budget_usage().await {
+            Ok(usage) => self.budget.exceeded(&usage),
+            Err(err) => {
+                error!("Error reading crawl budget: {}", err);
+                None
+            }
+        }
+    }
+
     pub async fn add_page(&self, page: Page) -> Result<(), String> {
         let mut pages_guard = self.pages.lock().await;
         let normalized_url = page.normalized_url.clone();
@@ -52,13 +110,13 @@ impl CrawlerConfig {
         Ok(())
     }
 
-    pub async fn update_links(&self, current_url: &str, outgoing_links: &[String]) {
+    pub async fn update_links(&self, current_url: &str, outgoing_links: &LinkEdges) {
         let mut backlinks = self.backlinks.lock().await;
         let mut outlinks = self.outlinks.lock().await;
 
         let mut current_node = PageNode::new(current_url.to_string());
 
-        for link in outgoing_links {
+        for (link, extracted_edge) in outgoing_links {
             if !is_valid_url(link) {
                 continue;
             }
@@ -72,29 +130,64 @@ impl CrawlerConfig {
                 continue;
             }
 
+            let edge = LinkEdge {
+                source_url: current_url.to_string(),
+                target_url: normalized_link.clone(),
+                ..extracted_edge.clone()
+            };
+
             backlinks
                 .entry(normalized_link.clone())
                 .or_insert_with(|| PageNode::new(normalized_link.clone()))
-                .append_link(current_url.to_string());
+                .append_edge(current_url.to_string(), edge.clone());
 
-            current_node.append_link(normalized_link);
+            current_node.append_edge(normalized_link, edge);
         }
 
         outlinks.insert(current_url.to_string(), current_node);
     }
 
-    pub async fn add_images(&self, page_url: &str, image_map: &HashMap<String, HashMap<String, String>>) {
+    pub async fn add_images(&self, page_url: &str, image_map: &ImageMap) {
         let mut images = self.images.lock().await;
 
         for (img_url, attrs) in image_map {
-            let alt = attrs.get("alt").cloned().unwrap_or_default();
+            let attr = |name: &str| attrs.get(name).cloned().unwrap_or_default();
             let image = Image {
                 normalized_page_url: page_url.to_string(),
                 normalized_source_url: img_url.to_string(),
-                alt,
+                source_url: attr("url"),
+                alt: attr("alt"),
+                width: attr("width"),
+                height: attr("height"),
+                loading: attr("loading"),
+                descriptor: attr("descriptor"),
             };
 
             images.entry(page_url.to_string()).or_default().push(image);
         }
     }
+
+    pub async fn add_metadata(&self, page_url: &str, metadata: PageMetadata) {
+        self.metadata.lock().await.insert(page_url.to_string(), metadata);
+    }
+
+    pub async fn add_structured_data(&self, page_url: &str, structured_data: StructuredData) {
+        if structured_data.is_empty() {
+            return;
+        }
+
+        self.structured_data.lock().await.insert(page_url.to_string(), structured_data);
+    }
+
+    pub async fn add_feeds(&self, feed_urls: &[String]) {
+        let mut feeds = self.feeds.lock().await;
+
+        for feed_url in feed_urls {
+            if let Ok(normalized) = normalize_url(feed_url) {
+                feeds
+                    .entry(normalized.clone())
+                    .or_insert_with(|| Feed::new(normalized, feed_url.clone()));
+            }
+        }
+    }
 }
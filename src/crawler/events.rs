@@ -0,0 +1,98 @@
+use std::sync::Arc;
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+
+// Events buffered per subscriber before the slowest one starts missing them.
+pub const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum CrawlEvent {
+    Started {
+        job: String,
+        seeds: usize,
+    },
+    Fetched {
+        url: String,
+        normalized_url: String,
+        depth: f64,
+        status: u16,
+        content_type: String,
+        bytes: usize,
+        duration_ms: u64,
+    },
+    Failed {
+        url: String,
+        normalized_url: String,
+        depth: f64,
+        error_kind: String,
+        error: String,
+    },
+    // Popped but not fetched: `scope` or `host_budget`.
+    Skipped {
+        url: String,
+        reason: String,
+    },
+    // A batch of pages was written to Redis.
+    Flushed {
+        pages: usize,
+    },
+    Paused,
+    Resumed,
+    // Always the last event of a run.
+    Finished {
+        reason: String,
+    },
+}
+
+pub type EventCallback = Arc<dyn Fn(&CrawlEvent) + Send + Sync>;
+
+// Delivers crawl events to the callbacks, in the emitting task, and to every `EventStream`.
+#[derive(Clone)]
+pub struct CrawlEvents {
+    sender: broadcast::Sender<CrawlEvent>,
+    callbacks: Arc<Vec<EventCallback>>,
+}
+
+impl Default for CrawlEvents {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl CrawlEvents {
+    pub fn new(callbacks: Vec<EventCallback>) -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self { sender, callbacks: Arc::new(callbacks) }
+    }
+
+    pub fn emit(&self, event: CrawlEvent) {
+        for callback in self.callbacks.iter() {
+            callback(&event);
+        }
+        // Fails only when nobody is subscribed.
+        let _ = self.sender.send(event);
+    }
+
+    // Events emitted from now on.
+    pub fn subscribe(&self) -> EventStream {
+        EventStream(self.sender.subscribe())
+    }
+}
+
+pub struct EventStream(broadcast::Receiver<CrawlEvent>);
+
+impl EventStream {
+    // The next event, or None once the spider is dropped. Events missed by a subscriber that
+    // fell more than `EVENT_CHANNEL_CAPACITY` behind are skipped.
+    pub async fn next(&mut self) -> Option<CrawlEvent> {
+        loop {
+            match self.0.recv().await {
+                Ok(event) => return Some(event),
+                Err(RecvError::Lagged(missed)) => warn!("Crawl event subscriber missed {} events", missed),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
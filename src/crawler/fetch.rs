@@ -0,0 +1,230 @@
+use std::error::Error;
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, CONTENT_LENGTH, CONTENT_TYPE};
+use crate::metrics::{metrics, status_class};
+use crate::utils::MAX_BODY_SIZE;
+
+// Client settings shared by every fetch in the process. Set once at startup with
+// `configure_fetcher`; until then the defaults apply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchSettings {
+    pub timeout: Duration,
+    pub connect_timeout: Duration,
+    pub max_body_size: usize,
+    pub user_agent: String,
+}
+
+impl Default for FetchSettings {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            connect_timeout: Duration::from_secs(10),
+            max_body_size: MAX_BODY_SIZE,
+            user_agent: String::new(),
+        }
+    }
+}
+
+static FETCH_SETTINGS: OnceLock<FetchSettings> = OnceLock::new();
+
+// Returns false if the settings were already fixed by an earlier call or fetch.
+pub fn configure_fetcher(settings: FetchSettings) -> bool {
+    FETCH_SETTINGS.set(settings).is_ok()
+}
+
+pub fn fetch_settings() -> &'static FetchSettings {
+    FETCH_SETTINGS.get_or_init(FetchSettings::default)
+}
+
+// Responses `fetch` refuses. Transport failures come back as `reqwest::Error`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FetchError {
+    Status(u16),
+    TooLarge { length: Option<usize>, limit: usize },
+    ContentType(String),
+}
+
+impl FetchError {
+    // Label for `spider_fetch_errors_total`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FetchError::Status(_) => "http_status",
+            FetchError::TooLarge { .. } => "too_large",
+            FetchError::ContentType(_) => "content_type",
+        }
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Status(code) => {
+                let reason = StatusCode::from_u16(*code).ok().and_then(|status| status.canonical_reason());
+                write!(f, "HTTP error: {} {}", code, reason.unwrap_or("Unknown"))
+            }
+            FetchError::TooLarge { length: Some(length), limit } => {
+                write!(f, "Response too large: {} bytes (limit {})", length, limit)
+            }
+            FetchError::TooLarge { length: None, limit } => write!(f, "Response too large: more than {} bytes", limit),
+            FetchError::ContentType(content_type) => write!(f, "Invalid content type: {}", content_type),
+        }
+    }
+}
+
+impl Error for FetchError {}
+
+// Label for `spider_fetch_errors_total`.
+pub fn error_kind(err: &(dyn Error + 'static)) -> &'static str {
+    if let Some(err) = err.downcast_ref::<FetchError>() {
+        return err.kind();
+    }
+    match err.downcast_ref::<reqwest::Error>() {
+        Some(err) if err.is_timeout() => "timeout",
+        Some(err) if err.is_connect() => "connect",
+        Some(err) if err.is_body() || err.is_decode() => "body",
+        Some(err) if err.is_builder() => "invalid_request",
+        _ => "other",
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FetchedResponse {
+    pub bytes: Vec<u8>,
+    pub status_code: u16,
+    // Whether redirects led away from the requested URL.
+    pub redirected: bool,
+    pub content_type: String,
+    pub headers: HeaderMap,
+}
+
+impl FetchedResponse {
+    // Decodes the body using the charset from the Content-Type header, falling back to UTF-8.
+    pub fn text(&self) -> String {
+        let encoding = self
+            .content_type
+            .split(';')
+            .filter_map(|part| part.trim().strip_prefix("charset="))
+            .find_map(|label| encoding_rs::Encoding::for_label(label.trim_matches('"').as_bytes()))
+            .unwrap_or(encoding_rs::UTF_8);
+
+        let (text, _, _) = encoding.decode(&self.bytes);
+        text.into_owned()
+    }
+}
+
+// Downloads `url`, rejecting responses whose Content-Type `accept` refuses before
+// reading the body, and bodies larger than `max_bytes`.
+pub async fn fetch(
+    url: &str,
+    max_bytes: usize,
+    accept: impl Fn(&str) -> bool,
+) -> Result<FetchedResponse, Box<dyn Error>> {
+    fetch_with_headers(url, max_bytes, accept, HeaderMap::new()).await
+}
+
+// Like `fetch`, sending extra request headers. A 304 Not Modified answer to a conditional
+// request is returned as-is with an empty body.
+pub async fn fetch_with_headers(
+    url: &str,
+    max_bytes: usize,
+    accept: impl Fn(&str) -> bool,
+    request_headers: HeaderMap,
+) -> Result<FetchedResponse, Box<dyn Error>> {
+    let started = Instant::now();
+    let result = send(url, max_bytes, accept, request_headers).await;
+
+    let metrics = metrics();
+    metrics.fetch_duration.observe(started.elapsed().as_secs_f64());
+    match &result {
+        Ok(response) => {
+            metrics.fetches.inc(&status_class(response.status_code));
+            metrics.fetched_bytes.add(response.bytes.len() as u64);
+        }
+        Err(err) => {
+            if let Some(FetchError::Status(code)) = err.downcast_ref::<FetchError>() {
+                metrics.fetches.inc(&status_class(*code));
+            }
+            metrics.fetch_errors.inc(error_kind(err.as_ref()));
+        }
+    }
+
+    result
+}
+
+async fn send(
+    url: &str,
+    max_bytes: usize,
+    accept: impl Fn(&str) -> bool,
+    request_headers: HeaderMap,
+) -> Result<FetchedResponse, Box<dyn Error>> {
+    let settings = fetch_settings();
+    let mut builder = reqwest::Client::builder()
+        .timeout(settings.timeout)
+        .connect_timeout(settings.connect_timeout);
+    if !settings.user_agent.is_empty() {
+        builder = builder.user_agent(settings.user_agent.as_str());
+    }
+    let client = builder.build()?;
+
+    let mut response = client.get(url).headers(request_headers).send().await?;
+
+    let status = response.status();
+    let status_code = status.as_u16();
+    let redirected = reqwest::Url::parse(url).ok().as_ref() != Some(response.url());
+
+    if status.is_client_error() || status.is_server_error() {
+        return Err(FetchError::Status(status_code).into());
+    }
+
+    if status == StatusCode::NOT_MODIFIED {
+        return Ok(FetchedResponse {
+            bytes: Vec::new(),
+            status_code,
+            redirected,
+            content_type: String::new(),
+            headers: response.headers().clone(),
+        });
+    }
+
+    let content_length = response
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|val| val.to_str().ok())
+        .and_then(|val| val.parse::<usize>().ok());
+
+    if content_length.is_some_and(|length| length > max_bytes) {
+        return Err(FetchError::TooLarge { length: content_length, limit: max_bytes }.into());
+    }
+
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|val| val.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+
+    if !accept(&content_type) {
+        return Err(FetchError::ContentType(content_type).into());
+    }
+
+    let headers = response.headers().clone();
+
+    let mut bytes = Vec::with_capacity(content_length.unwrap_or(0));
+    while let Some(chunk) = response.chunk().await? {
+        if bytes.len() + chunk.len() > max_bytes {
+            return Err(FetchError::TooLarge { length: None, limit: max_bytes }.into());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(FetchedResponse {
+        bytes,
+        status_code,
+        redirected,
+        content_type,
+        headers,
+    })
+}
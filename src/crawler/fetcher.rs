@@ -0,0 +1,57 @@
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use super::fetch::{error_kind, FetchError};
+use super::get_page_data::{get_page_data_with, PageData};
+
+pub type FetchFuture<'a> = Pin<Box<dyn Future<Output = Result<PageData, FetchFailure>> + Send + 'a>>;
+
+// Why a page could not be fetched. `kind` is reported in metrics, the audit log and the crawl
+// report, so keep it to a short fixed set of values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchFailure {
+    pub kind: String,
+    // HTTP status of an error response.
+    pub status: Option<u16>,
+    pub message: String,
+}
+
+impl FetchFailure {
+    pub fn new(kind: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { kind: kind.into(), status: None, message: message.into() }
+    }
+
+    pub fn from_error(err: &(dyn Error + 'static)) -> Self {
+        let status = match err.downcast_ref::<FetchError>() {
+            Some(FetchError::Status(code)) => Some(*code),
+            _ => None,
+        };
+        Self { kind: error_kind(err).to_string(), status, message: err.to_string() }
+    }
+}
+
+impl fmt::Display for FetchFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for FetchFailure {}
+
+// Fetches the pages the crawler pops. `accept` tells whether a bare MIME type has a content
+// handler; responses it rejects should fail with kind `content_type`.
+pub trait Fetcher: Send + Sync {
+    fn fetch<'a>(&'a self, url: &'a str, accept: &'a (dyn Fn(&str) -> bool + Sync)) -> FetchFuture<'a>;
+}
+
+// The default fetcher: HTTP through the shared client, using the settings passed to
+// `configure_fetcher`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HttpFetcher;
+
+impl Fetcher for HttpFetcher {
+    fn fetch<'a>(&'a self, url: &'a str, accept: &'a (dyn Fn(&str) -> bool + Sync)) -> FetchFuture<'a> {
+        Box::pin(async move { get_page_data_with(url, accept).await.map_err(|e| FetchFailure::from_error(e.as_ref())) })
+    }
+}
@@ -0,0 +1,89 @@
+use roxmltree::{Document, Node};
+use url::Url;
+use crate::utils::html_text::collapse_whitespace;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedContent {
+    pub title: String,
+    pub text: String,
+    pub links: Vec<String>,
+}
+
+// Extracts item links from RSS 0.9x/1.0/2.0 and Atom feeds. Other XML documents yield
+// their text content and no links.
+pub fn get_feed_content(xml: &str, raw_url: &str) -> Result<FeedContent, String> {
+    let base_url = Url::parse(raw_url).map_err(|e| format!("Invalid URL {}: {}", raw_url, e))?;
+    let document = Document::parse(xml.trim_start_matches('\u{feff}')).map_err(|e| format!("Invalid XML: {}", e))?;
+    let root = document.root_element();
+
+    let mut content = FeedContent::default();
+
+    match root.tag_name().name() {
+        "rss" | "RDF" => {
+            if let Some(channel) = child(root, "channel") {
+                content.title = child_text(channel, "title");
+            }
+
+            for item in root.descendants().filter(|n| n.tag_name().name() == "item") {
+                let link = child(item, "link")
+                    .map(|link| node_text(link))
+                    .filter(|link| !link.is_empty())
+                    .or_else(|| {
+                        child(item, "guid")
+                            .filter(|guid| guid.attribute("isPermaLink") != Some("false"))
+                            .map(|guid| node_text(guid))
+                    });
+
+                if let Some(link) = link {
+                    push_link(&mut content.links, &base_url, &link);
+                }
+            }
+        }
+        "feed" => {
+            content.title = child_text(root, "title");
+
+            for entry in root.children().filter(|n| n.tag_name().name() == "entry") {
+                let alternate = entry
+                    .children()
+                    .filter(|n| n.tag_name().name() == "link")
+                    .find(|link| matches!(link.attribute("rel"), None | Some("alternate")));
+
+                if let Some(href) = alternate.and_then(|link| link.attribute("href")) {
+                    push_link(&mut content.links, &base_url, href);
+                }
+            }
+        }
+        _ => {}
+    }
+
+    content.text = collapse_whitespace(&node_text(root));
+
+    Ok(content)
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.tag_name().name() == name)
+}
+
+fn child_text(node: Node, name: &str) -> String {
+    child(node, name).map(|n| collapse_whitespace(&node_text(n))).unwrap_or_default()
+}
+
+fn node_text(node: Node) -> String {
+    node.descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim()
+        .to_string()
+}
+
+fn push_link(links: &mut Vec<String>, base_url: &Url, href: &str) {
+    if let Ok(link) = base_url.join(href.trim()) {
+        let link = link.to_string();
+        if !links.contains(&link) {
+            links.push(link);
+        }
+    }
+}
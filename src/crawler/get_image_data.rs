@@ -0,0 +1,101 @@
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageReader, Limits};
+use std::error::Error;
+use std::io::Cursor;
+use crate::pages::ImageData;
+use crate::utils::normalize_url::normalize_url;
+use super::fetcher::{Fetcher, HttpFetcher};
+
+// Larger images keep their header dimensions but are not decoded or hashed.
+pub const MAX_DECODE_DIMENSION: u32 = 8192;
+pub const MAX_DECODE_ALLOC: u64 = 128 * 1024 * 1024;
+
+pub async fn get_image_data(url: &str) -> Result<ImageData, Box<dyn Error>> {
+    get_image_data_with(&HttpFetcher, url).await
+}
+
+// Like `get_image_data`, but downloads through `fetcher`.
+pub async fn get_image_data_with(fetcher: &dyn Fetcher, url: &str) -> Result<ImageData, Box<dyn Error>> {
+    let accept = |content_type: &str| {
+        content_type.is_empty()
+            || content_type.starts_with("image/")
+            || content_type.starts_with("application/octet-stream")
+    };
+    let response = fetcher.fetch(url, &accept).await?;
+
+    // Decoding is CPU-bound; keep it off the async workers.
+    let (bytes, content_type) = (response.bytes, response.content_type);
+    let mut image_data = tokio::task::spawn_blocking(move || analyze_image(&bytes, &content_type)).await?;
+    image_data.normalized_source_url = normalize_url(url)?;
+    Ok(image_data)
+}
+
+// Records what can be learned from the raw bytes. Formats that cannot be decoded
+// (SVG, AVIF, ...) still get their MIME type and size, just no dimensions or hash.
+pub fn analyze_image(bytes: &[u8], content_type: &str) -> ImageData {
+    let header_mime = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+
+    let mut image_data = ImageData {
+        mime_type: header_mime,
+        byte_size: bytes.len(),
+        ..Default::default()
+    };
+
+    let reader = match ImageReader::new(Cursor::new(bytes)).with_guessed_format() {
+        Ok(reader) => reader,
+        Err(_) => return image_data,
+    };
+
+    if let Some(format) = reader.format() {
+        image_data.mime_type = format.to_mime_type().to_string();
+    }
+
+    // Only the header is read here, so oversized images still get their dimensions.
+    if let Ok((width, height)) = reader.into_dimensions() {
+        image_data.width = Some(width);
+        image_data.height = Some(height);
+    }
+
+    let mut reader = match ImageReader::new(Cursor::new(bytes)).with_guessed_format() {
+        Ok(reader) => reader,
+        Err(_) => return image_data,
+    };
+    reader.limits(decode_limits());
+
+    if let Ok(decoded) = reader.decode() {
+        image_data.width = Some(decoded.width());
+        image_data.height = Some(decoded.height());
+        image_data.dhash = Some(dhash(&decoded));
+    }
+
+    image_data
+}
+
+fn decode_limits() -> Limits {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DECODE_DIMENSION);
+    limits.max_image_height = Some(MAX_DECODE_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_ALLOC);
+    limits
+}
+
+// Difference hash: shrink to 9x8 grayscale and record whether each pixel is brighter
+// than its right-hand neighbour. Near-duplicate images differ in only a few bits.
+pub fn dhash(image: &DynamicImage) -> u64 {
+    let small = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = small.get_pixel(x, y)[0];
+            let right = small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(left > right);
+        }
+    }
+
+    hash
+}
+
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
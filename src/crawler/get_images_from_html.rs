@@ -0,0 +1,144 @@
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
+use url::Url;
+use crate::utils::normalize_url::normalize_url;
+
+pub type ImageMap = HashMap<String, HashMap<String, String>>;
+
+const LAZY_SRC_ATTRS: [&str; 4] = ["data-src", "data-lazy-src", "data-original", "data-lazy"];
+const LAZY_SRCSET_ATTRS: [&str; 2] = ["data-srcset", "data-lazy-srcset"];
+const SIZE_ATTRS: [&str; 3] = ["width", "height", "loading"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrcsetCandidate {
+    pub url: String,
+    pub descriptor: String,
+}
+
+pub fn get_images_from_document(document: &Html, base_url: &Url) -> ImageMap {
+    let img_selector = Selector::parse("img").unwrap();
+    let source_selector = Selector::parse("picture source").unwrap();
+    let style_selector = Selector::parse("[style]").unwrap();
+    let background = Regex::new(r#"(?i)background(?:-image)?\s*:[^;]*?url\(\s*['"]?([^'")]+?)['"]?\s*\)"#).unwrap();
+
+    let mut image_map = HashMap::new();
+
+    for element in document.select(&img_selector) {
+        let lazy_src = LAZY_SRC_ATTRS.iter().find_map(|attr| element.value().attr(attr));
+
+        // Lazy-loading scripts keep a placeholder in `src` and the real image in a data attribute.
+        let src = lazy_src.or_else(|| element.value().attr("src"));
+        if let Some(src) = src {
+            insert_image(&mut image_map, base_url, src, "", element);
+        }
+
+        for attr in std::iter::once("srcset").chain(LAZY_SRCSET_ATTRS) {
+            if let Some(srcset) = element.value().attr(attr) {
+                for candidate in parse_srcset(srcset) {
+                    insert_image(&mut image_map, base_url, &candidate.url, &candidate.descriptor, element);
+                }
+            }
+        }
+    }
+
+    for source in document.select(&source_selector) {
+        let img = source
+            .parent()
+            .and_then(ElementRef::wrap)
+            .and_then(|picture| picture.children().filter_map(ElementRef::wrap).find(|c| c.value().name() == "img"))
+            .unwrap_or(source);
+
+        for attr in std::iter::once("srcset").chain(LAZY_SRCSET_ATTRS) {
+            if let Some(srcset) = source.value().attr(attr) {
+                for candidate in parse_srcset(srcset) {
+                    insert_image(&mut image_map, base_url, &candidate.url, &candidate.descriptor, img);
+                }
+            }
+        }
+    }
+
+    for element in document.select(&style_selector) {
+        let style = element.value().attr("style").unwrap_or("");
+        for capture in background.captures_iter(style) {
+            insert_image(&mut image_map, base_url, &capture[1], "", element);
+        }
+    }
+
+    image_map
+}
+
+pub fn parse_srcset(srcset: &str) -> Vec<SrcsetCandidate> {
+    let mut candidates = Vec::new();
+    let mut rest = srcset;
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        if rest.is_empty() {
+            break;
+        }
+
+        let url_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let mut url = &rest[..url_end];
+        rest = &rest[url_end..];
+
+        let mut descriptor = "";
+        if url.ends_with(',') {
+            url = url.trim_end_matches(',');
+        } else {
+            let descriptor_end = rest.find(',').unwrap_or(rest.len());
+            descriptor = rest[..descriptor_end].trim();
+            rest = &rest[descriptor_end..];
+        }
+
+        if !url.is_empty() {
+            candidates.push(SrcsetCandidate {
+                url: url.to_string(),
+                descriptor: descriptor.to_string(),
+            });
+        }
+    }
+
+    candidates
+}
+
+fn insert_image(image_map: &mut ImageMap, base_url: &Url, src: &str, descriptor: &str, element: ElementRef) {
+    let src = src.trim();
+    if src.is_empty() || src.contains(&['<', '>', '"', ' '][..]) || src.chars().any(|c| !(' '..='~').contains(&c)) {
+        return;
+    }
+
+    let joined = match base_url.join(src) {
+        Ok(joined) => joined,
+        Err(_) => return,
+    };
+
+    let norm_url = match normalize_url(joined.as_ref()) {
+        Ok(norm_url) => norm_url,
+        Err(_) => return,
+    };
+
+    if image_map.contains_key(&norm_url) {
+        return;
+    }
+
+    let mut image_data = HashMap::new();
+    image_data.insert("src".to_string(), norm_url.clone());
+    image_data.insert("url".to_string(), joined.to_string());
+
+    if !descriptor.is_empty() {
+        image_data.insert("descriptor".to_string(), descriptor.to_string());
+    }
+
+    if let Some(alt) = element.value().attr("alt") {
+        image_data.insert("alt".to_string(), alt.to_string());
+    }
+
+    for attr in SIZE_ATTRS {
+        if let Some(value) = element.value().attr(attr) {
+            image_data.insert(attr.to_string(), value.trim().to_string());
+        }
+    }
+
+    image_map.insert(norm_url, image_data);
+}
@@ -0,0 +1,271 @@
+use std::sync::OnceLock;
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use crate::utils::html_text::{collapse_whitespace, visible_text, SKIPPED_TEXT_TAGS};
+
+const BOILERPLATE_TAGS: [&str; 10] = ["nav", "footer", "header", "aside", "form", "iframe", "button", "select", "dialog", "menu"];
+const TEXT_BLOCK_TAGS: [&str; 15] = ["p", "pre", "blockquote", "li", "td", "dt", "dd", "figcaption", "h1", "h2", "h3", "h4", "h5", "h6", "caption"];
+const CONTAINER_TAGS: [&str; 9] = ["div", "article", "section", "main", "ul", "ol", "table", "tbody", "tr"];
+
+const MIN_PARAGRAPH_LENGTH: usize = 25;
+const MAX_BLOCK_LINK_DENSITY: f64 = 0.5;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MainContent {
+    pub text: String,
+    pub html: String,
+}
+
+struct Block {
+    tag: String,
+    text: String,
+}
+
+static HEURISTICS: OnceLock<Heuristics> = OnceLock::new();
+static BODY_SELECTOR: OnceLock<Selector> = OnceLock::new();
+static LINK_SELECTOR: OnceLock<Selector> = OnceLock::new();
+
+struct Heuristics {
+    unlikely: Regex,
+    positive: Regex,
+    negative: Regex,
+}
+
+impl Heuristics {
+    fn new() -> Self {
+        Self {
+            unlikely: Regex::new(r"(?i)\bad-|\bads?\b|advert|banner|breadcrumb|combx|comment|cookie|disqus|footer|menu|modal|nav|pagination|popup|related|share|sidebar|social|sponsor|subscribe").unwrap(),
+            positive: Regex::new(r"(?i)article|body|content|entry|main|post|story|text").unwrap(),
+            negative: Regex::new(r"(?i)hidden|meta|promo|sidebar|widget|footer|shoutbox|sponsor|comment").unwrap(),
+        }
+    }
+
+    fn class_and_id(element: ElementRef) -> String {
+        format!(
+            "{} {}",
+            element.value().attr("class").unwrap_or(""),
+            element.value().attr("id").unwrap_or("")
+        )
+    }
+
+    fn is_boilerplate(&self, element: ElementRef) -> bool {
+        let name = element.value().name();
+        if BOILERPLATE_TAGS.contains(&name) || SKIPPED_TEXT_TAGS.contains(&name) {
+            return true;
+        }
+
+        if name == "body" || name == "article" || name == "main" {
+            return false;
+        }
+
+        let class_and_id = Self::class_and_id(element);
+        self.unlikely.is_match(&class_and_id) && !self.positive.is_match(&class_and_id)
+    }
+
+    fn class_weight(&self, element: ElementRef) -> f64 {
+        let class_and_id = Self::class_and_id(element);
+        let mut weight = 0.0;
+
+        if self.positive.is_match(&class_and_id) {
+            weight += 25.0;
+        }
+
+        if self.negative.is_match(&class_and_id) {
+            weight -= 25.0;
+        }
+
+        weight
+    }
+}
+
+pub fn get_main_content(html_body: &str) -> MainContent {
+    let document = Html::parse_document(html_body);
+    get_main_content_from_document(&document)
+}
+
+pub fn get_main_content_from_document(document: &Html) -> MainContent {
+    let heuristics = HEURISTICS.get_or_init(Heuristics::new);
+    let body_selector = BODY_SELECTOR.get_or_init(|| Selector::parse("body").unwrap());
+
+    let body = match document.select(body_selector).next() {
+        Some(body) => body,
+        None => return MainContent::default(),
+    };
+
+    let mut scores: Vec<(ElementRef, f64)> = Vec::new();
+    score_paragraphs(body, heuristics, &mut scores);
+
+    let top = scores
+        .iter()
+        .map(|(element, score)| (*element, score * (1.0 - link_density(*element))))
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+
+    let selected = match top {
+        Some((top_element, top_score)) => select_siblings(top_element, top_score, &scores),
+        None => vec![body],
+    };
+
+    let mut blocks = Vec::new();
+    for element in selected {
+        collect_blocks(element, heuristics, &mut blocks);
+    }
+
+    MainContent {
+        text: blocks.iter().map(|b| b.text.as_str()).collect::<Vec<_>>().join("\n\n"),
+        html: render_blocks(&blocks),
+    }
+}
+
+fn score_paragraphs<'a>(element: ElementRef<'a>, heuristics: &Heuristics, scores: &mut Vec<(ElementRef<'a>, f64)>) {
+    for child in element.children().filter_map(ElementRef::wrap) {
+        if heuristics.is_boilerplate(child) {
+            continue;
+        }
+
+        if !is_paragraph(child) {
+            score_paragraphs(child, heuristics, scores);
+            continue;
+        }
+
+        let text = collapse_whitespace(&visible_text(child));
+        if text.len() < MIN_PARAGRAPH_LENGTH {
+            continue;
+        }
+
+        let score = 1.0 + text.matches(',').count() as f64 + (text.len() as f64 / 100.0).min(3.0);
+
+        let parent = child.parent().and_then(ElementRef::wrap);
+        let grandparent = parent.and_then(|p| p.parent()).and_then(ElementRef::wrap);
+
+        for (ancestor, share) in [(parent, 1.0), (grandparent, 0.5)] {
+            let Some(ancestor) = ancestor else { continue };
+
+            match scores.iter_mut().find(|(candidate, _)| *candidate == ancestor) {
+                Some((_, candidate_score)) => *candidate_score += score * share,
+                None => scores.push((ancestor, heuristics.class_weight(ancestor) + score * share)),
+            }
+        }
+    }
+}
+
+fn select_siblings<'a>(top: ElementRef<'a>, top_score: f64, scores: &[(ElementRef<'a>, f64)]) -> Vec<ElementRef<'a>> {
+    let threshold = (top_score * 0.2).max(10.0);
+
+    let parent = match top.parent().and_then(ElementRef::wrap) {
+        Some(parent) => parent,
+        None => return vec![top],
+    };
+
+    parent
+        .children()
+        .filter_map(ElementRef::wrap)
+        .filter(|sibling| {
+            if *sibling == top {
+                return true;
+            }
+
+            if scores.iter().any(|(candidate, score)| candidate == sibling && *score >= threshold) {
+                return true;
+            }
+
+            if sibling.value().name() == "p" {
+                let text = collapse_whitespace(&visible_text(*sibling));
+                return text.len() > 80 && link_density(*sibling) < 0.25;
+            }
+
+            false
+        })
+        .collect()
+}
+
+fn collect_blocks(element: ElementRef, heuristics: &Heuristics, blocks: &mut Vec<Block>) {
+    let name = element.value().name();
+
+    if is_paragraph(element) {
+        let text = collapse_whitespace(&visible_text(element));
+        let is_heading = name.len() == 2 && name.starts_with('h');
+
+        if !text.is_empty() && (is_heading || link_density(element) < MAX_BLOCK_LINK_DENSITY) {
+            let tag = if TEXT_BLOCK_TAGS.contains(&name) { name } else { "p" };
+            blocks.push(Block { tag: tag.to_string(), text });
+        }
+        return;
+    }
+
+    for child in element.children().filter_map(ElementRef::wrap) {
+        if !heuristics.is_boilerplate(child) {
+            collect_blocks(child, heuristics, blocks);
+        }
+    }
+}
+
+fn is_paragraph(element: ElementRef) -> bool {
+    let name = element.value().name();
+
+    if TEXT_BLOCK_TAGS.contains(&name) {
+        return true;
+    }
+
+    if name != "div" {
+        return false;
+    }
+
+    let has_block_children = element
+        .children()
+        .filter_map(ElementRef::wrap)
+        .any(|child| {
+            let child_name = child.value().name();
+            TEXT_BLOCK_TAGS.contains(&child_name) || CONTAINER_TAGS.contains(&child_name)
+        });
+
+    !has_block_children
+}
+
+fn link_density(element: ElementRef) -> f64 {
+    let text_length = collapse_whitespace(&visible_text(element)).len();
+    if text_length == 0 {
+        return 0.0;
+    }
+
+    let link_selector = LINK_SELECTOR.get_or_init(|| Selector::parse("a").unwrap());
+    let link_length: usize = element
+        .select(link_selector)
+        .map(|link| collapse_whitespace(&visible_text(link)).len())
+        .sum();
+
+    link_length as f64 / text_length as f64
+}
+
+fn render_blocks(blocks: &[Block]) -> String {
+    if blocks.is_empty() {
+        return String::new();
+    }
+
+    let mut html = String::from("<article>\n");
+    let mut in_list = false;
+
+    for block in blocks {
+        if block.tag == "li" && !in_list {
+            html.push_str("<ul>\n");
+            in_list = true;
+        } else if block.tag != "li" && in_list {
+            html.push_str("</ul>\n");
+            in_list = false;
+        }
+
+        html.push_str(&format!("<{0}>{1}</{0}>\n", block.tag, escape_html(&block.text)));
+    }
+
+    if in_list {
+        html.push_str("</ul>\n");
+    }
+
+    html.push_str("</article>");
+    html
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
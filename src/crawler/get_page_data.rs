@@ -1,33 +1,52 @@
 use std::error::Error;
-use std::time::Duration;
+use super::fetch::{fetch, fetch_settings};
+use super::robots_directives::{parse_x_robots_tag, RobotsDirectives};
 
-pub async fn get_page_data(url: &str) -> Result<(String, u16, String), Box<dyn Error>> {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(30))
-        .connect_timeout(Duration::from_secs(10))
-        .build()?;
-    
-    let response = client.get(url).send().await?;
+#[derive(Debug, Clone)]
+pub struct PageData {
+    pub body: String,
+    pub bytes: Vec<u8>,
+    pub status_code: u16,
+    pub redirected: bool,
+    pub content_type: String,
+    pub robots: RobotsDirectives,
+}
 
-    let status = response.status();
-    let status_code = status.as_u16();
+pub async fn get_page_data(url: &str) -> Result<PageData, Box<dyn Error>> {
+    get_page_data_with(url, |content_type| content_type.starts_with("text/html")).await
+}
 
-    if status.is_client_error() || status.is_server_error() {
-        return Err(format!("HTTP error: {} {}", status_code, status.canonical_reason().unwrap_or("Unknown")).into());
-    }
+// Like `get_page_data`, but accepts any Content-Type `accept` allows. `content_type` is the
+// bare MIME type; `body` is only decoded for textual types, binary content stays in `bytes`.
+pub async fn get_page_data_with(url: &str, accept: impl Fn(&str) -> bool) -> Result<PageData, Box<dyn Error>> {
+    let response = fetch(url, fetch_settings().max_body_size, accept).await?;
 
-    let content_type = response
-        .headers()
-        .get(reqwest::header::CONTENT_TYPE)
-        .and_then(|val| val.to_str().ok())
-        .unwrap_or("")
-        .to_string();
+    let robots = parse_x_robots_tag(
+        response
+            .headers
+            .get_all("x-robots-tag")
+            .iter()
+            .filter_map(|val| val.to_str().ok()),
+    );
 
-    if !content_type.starts_with("text/html") {
-        return Err(format!("Invalid content type: {}", content_type).into());
-    }
+    let content_type = mime_type(&response.content_type);
+    let body = if is_textual(&content_type) { response.text() } else { String::new() };
 
-    let body = response.text().await?;
+    Ok(PageData {
+        body,
+        status_code: response.status_code,
+        redirected: response.redirected,
+        content_type,
+        robots,
+        bytes: response.bytes,
+    })
+}
+
+// "Text/HTML; charset=utf-8" -> "text/html"
+pub fn mime_type(content_type: &str) -> String {
+    content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase()
+}
 
-    Ok((body, status_code, "text/html".to_string()))
+fn is_textual(mime: &str) -> bool {
+    mime.is_empty() || mime.starts_with("text/") || mime.ends_with("xml") || mime.ends_with("json")
 }
@@ -0,0 +1,91 @@
+use scraper::{Html, Selector};
+use crate::pages::{Heading, PageMetadata};
+use crate::utils::html_text::{collapse_whitespace, visible_text};
+use crate::utils::normalize_url::normalize_url;
+use super::get_urls_from_html::get_base_url;
+
+pub fn get_page_metadata(html_body: &str, raw_url: &str) -> Result<PageMetadata, Box<dyn std::error::Error>> {
+    let document = Html::parse_document(html_body);
+    get_page_metadata_from_document(&document, raw_url)
+}
+
+pub fn get_page_metadata_from_document(
+    document: &Html,
+    raw_url: &str,
+) -> Result<PageMetadata, Box<dyn std::error::Error>> {
+    let base_url = get_base_url(document, raw_url)?;
+    let mut metadata = PageMetadata::new(normalize_url(raw_url)?);
+
+    let title_selector = Selector::parse("head title, title").unwrap();
+    let meta_selector = Selector::parse("meta[content]").unwrap();
+    let link_selector = Selector::parse("link[rel][href]").unwrap();
+    let heading_selector = Selector::parse("h1, h2, h3, h4, h5, h6").unwrap();
+    let body_selector = Selector::parse("body").unwrap();
+
+    if let Some(title) = document.select(&title_selector).next() {
+        metadata.title = collapse_whitespace(&title.text().collect::<String>());
+    }
+
+    if let Some(lang) = document.root_element().value().attr("lang") {
+        metadata.lang = lang.trim().to_string();
+    }
+
+    for element in document.select(&meta_selector) {
+        let content = element.value().attr("content").unwrap_or("").trim();
+        let name = element.value().attr("name")
+            .or_else(|| element.value().attr("property"))
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase();
+
+        match name.as_str() {
+            "description" => metadata.description = content.to_string(),
+            "keywords" => {
+                metadata.keywords = content
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|k| !k.is_empty())
+                    .map(String::from)
+                    .collect();
+            }
+            "robots" => metadata.robots = content.to_ascii_lowercase(),
+            _ if name.starts_with("og:") => {
+                metadata.open_graph.insert(name, content.to_string());
+            }
+            _ if name.starts_with("twitter:") => {
+                metadata.twitter.insert(name, content.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    for element in document.select(&link_selector) {
+        let rel = element.value().attr("rel").unwrap_or("");
+        if !rel.split_ascii_whitespace().any(|r| r.eq_ignore_ascii_case("canonical")) {
+            continue;
+        }
+
+        if let Some(href) = element.value().attr("href")
+            && let Ok(canonical) = base_url.join(href.trim())
+        {
+            metadata.canonical = canonical.to_string();
+            break;
+        }
+    }
+
+    for element in document.select(&heading_selector) {
+        let text = collapse_whitespace(&element.text().collect::<String>());
+        if text.is_empty() {
+            continue;
+        }
+
+        let level = element.value().name()[1..].parse::<u8>().unwrap_or(1);
+        metadata.headings.push(Heading { level, text });
+    }
+
+    if let Some(body) = document.select(&body_selector).next() {
+        metadata.word_count = visible_text(body).split_whitespace().count();
+    }
+
+    Ok(metadata)
+}
@@ -0,0 +1,59 @@
+use lopdf::{decode_text_string, Document};
+use crate::utils::html_text::collapse_whitespace;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PdfContent {
+    pub text: String,
+    pub title: String,
+    pub author: String,
+    pub subject: String,
+    pub keywords: Vec<String>,
+    pub page_count: usize,
+}
+
+pub fn get_pdf_content(bytes: &[u8]) -> Result<PdfContent, String> {
+    let document = Document::load_mem(bytes).map_err(|e| format!("Invalid PDF: {}", e))?;
+
+    let page_numbers: Vec<u32> = document.get_pages().keys().copied().collect();
+
+    // Pages whose fonts lopdf can't decode yield no text rather than failing the whole document.
+    let text = page_numbers
+        .iter()
+        .filter_map(|page| document.extract_text(&[*page]).ok())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut content = PdfContent {
+        text: collapse_whitespace(&text),
+        page_count: page_numbers.len(),
+        ..Default::default()
+    };
+
+    let info = document
+        .trailer
+        .get(b"Info")
+        .and_then(|info| document.dereference(info))
+        .and_then(|(_, info)| info.as_dict());
+
+    if let Ok(info) = info {
+        let field = |key: &[u8]| -> String {
+            info.get(key)
+                .and_then(|value| document.dereference(value))
+                .and_then(|(_, value)| decode_text_string(value))
+                .map(|value| collapse_whitespace(&value))
+                .unwrap_or_default()
+        };
+
+        content.title = field(b"Title");
+        content.author = field(b"Author");
+        content.subject = field(b"Subject");
+        content.keywords = field(b"Keywords")
+            .split([',', ';'])
+            .map(str::trim)
+            .filter(|keyword| !keyword.is_empty())
+            .map(str::to_string)
+            .collect();
+    }
+
+    Ok(content)
+}
@@ -0,0 +1,202 @@
+use scraper::{ElementRef, Html, Selector};
+use serde_json::{Map, Value};
+use url::Url;
+use crate::pages::StructuredData;
+use crate::utils::html_text::collapse_whitespace;
+use crate::utils::normalize_url::normalize_url;
+use super::get_urls_from_html::get_base_url;
+
+struct Vocabulary {
+    scope: &'static str,
+    property: &'static str,
+    item_type: &'static str,
+    ids: &'static [&'static str],
+    context: Option<&'static str>,
+}
+
+const MICRODATA: Vocabulary = Vocabulary {
+    scope: "itemscope",
+    property: "itemprop",
+    item_type: "itemtype",
+    ids: &["itemid"],
+    context: None,
+};
+
+const RDFA: Vocabulary = Vocabulary {
+    scope: "typeof",
+    property: "property",
+    item_type: "typeof",
+    ids: &["resource", "about"],
+    context: Some("vocab"),
+};
+
+pub fn get_structured_data(html_body: &str, raw_url: &str) -> Result<StructuredData, Box<dyn std::error::Error>> {
+    let document = Html::parse_document(html_body);
+    get_structured_data_from_document(&document, raw_url)
+}
+
+pub fn get_structured_data_from_document(
+    document: &Html,
+    raw_url: &str,
+) -> Result<StructuredData, Box<dyn std::error::Error>> {
+    let base_url = get_base_url(document, raw_url)?;
+    let mut data = StructuredData::new(normalize_url(raw_url)?);
+
+    let script_selector = Selector::parse("script[type]").unwrap();
+    let microdata_selector = Selector::parse("[itemscope]:not([itemprop])").unwrap();
+    let rdfa_selector = Selector::parse("[typeof]:not([property])").unwrap();
+
+    let json_ld_scripts = document.select(&script_selector).filter(|script| {
+        script.value().attr("type")
+            .map(|t| t.trim().eq_ignore_ascii_case("application/ld+json"))
+            .unwrap_or(false)
+    });
+
+    for (index, script) in json_ld_scripts.enumerate() {
+        let raw = script.text().collect::<String>();
+        let raw = raw.trim();
+
+        if raw.is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<Value>(raw) {
+            Ok(Value::Array(values)) => {
+                for value in values {
+                    push_json_ld(value, &mut data.json_ld);
+                }
+            }
+            Ok(value) => push_json_ld(value, &mut data.json_ld),
+            Err(e) => data.warnings.push(format!("Malformed JSON-LD block {}: {}", index + 1, e)),
+        }
+    }
+
+    for element in document.select(&microdata_selector) {
+        data.microdata.push(extract_item(element, &MICRODATA, &base_url));
+    }
+
+    for element in document.select(&rdfa_selector) {
+        data.rdfa.push(extract_item(element, &RDFA, &base_url));
+    }
+
+    Ok(data)
+}
+
+fn push_json_ld(value: Value, entities: &mut Vec<Value>) {
+    let graph = match value.get("@graph").and_then(Value::as_array) {
+        Some(graph) => graph.clone(),
+        None => {
+            entities.push(value);
+            return;
+        }
+    };
+
+    let context = value.get("@context").cloned();
+
+    for node in graph {
+        match (node, &context) {
+            (Value::Object(mut object), Some(context)) if !object.contains_key("@context") => {
+                object.insert("@context".to_string(), context.clone());
+                entities.push(Value::Object(object));
+            }
+            (node, _) => entities.push(node),
+        }
+    }
+}
+
+fn extract_item(element: ElementRef, vocab: &Vocabulary, base_url: &Url) -> Value {
+    let mut item = Map::new();
+
+    if let Some(context) = vocab.context.and_then(|attr| element.value().attr(attr)) {
+        item.insert("@context".to_string(), Value::String(context.to_string()));
+    }
+
+    if let Some(item_type) = element.value().attr(vocab.item_type) {
+        let types: Vec<Value> = item_type
+            .split_whitespace()
+            .map(|t| Value::String(t.to_string()))
+            .collect();
+
+        match types.len() {
+            0 => {}
+            1 => { item.insert("@type".to_string(), types[0].clone()); }
+            _ => { item.insert("@type".to_string(), Value::Array(types)); }
+        }
+    }
+
+    if let Some(id) = vocab.ids.iter().find_map(|attr| element.value().attr(attr)) {
+        let id = base_url.join(id).map(|u| u.to_string()).unwrap_or_else(|_| id.to_string());
+        item.insert("@id".to_string(), Value::String(id));
+    }
+
+    collect_properties(element, vocab, base_url, &mut item);
+
+    Value::Object(item)
+}
+
+fn collect_properties(element: ElementRef, vocab: &Vocabulary, base_url: &Url, item: &mut Map<String, Value>) {
+    for child in element.children().filter_map(ElementRef::wrap) {
+        let is_scope = child.value().attr(vocab.scope).is_some();
+
+        if let Some(names) = child.value().attr(vocab.property) {
+            let value = if is_scope {
+                extract_item(child, vocab, base_url)
+            } else {
+                property_value(child, base_url)
+            };
+
+            for name in names.split_whitespace() {
+                insert_property(item, name, value.clone());
+            }
+        }
+
+        if !is_scope {
+            collect_properties(child, vocab, base_url, item);
+        }
+    }
+}
+
+fn property_value(element: ElementRef, base_url: &Url) -> Value {
+    let el = element.value();
+
+    if let Some(content) = el.attr("content") {
+        return Value::String(content.trim().to_string());
+    }
+
+    let url_attr = match el.name() {
+        "a" | "area" | "link" => el.attr("href"),
+        "audio" | "embed" | "iframe" | "img" | "source" | "track" | "video" => el.attr("src"),
+        "object" => el.attr("data"),
+        _ => el.attr("resource"),
+    };
+
+    if let Some(url) = url_attr {
+        let resolved = base_url.join(url.trim()).map(|u| u.to_string()).unwrap_or_else(|_| url.to_string());
+        return Value::String(resolved);
+    }
+
+    let literal = match el.name() {
+        "data" | "meter" => el.attr("value"),
+        "time" => el.attr("datetime"),
+        _ => None,
+    };
+
+    if let Some(literal) = literal {
+        return Value::String(literal.trim().to_string());
+    }
+
+    Value::String(collapse_whitespace(&element.text().collect::<String>()))
+}
+
+fn insert_property(item: &mut Map<String, Value>, name: &str, value: Value) {
+    match item.get_mut(name) {
+        Some(Value::Array(values)) => values.push(value),
+        Some(existing) => {
+            let first = existing.take();
+            *existing = Value::Array(vec![first, value]);
+        }
+        None => {
+            item.insert(name.to_string(), value);
+        }
+    }
+}
@@ -1,23 +1,46 @@
 use regex::Regex;
-use scraper::{Html, Selector};
-use std::collections::{HashMap, HashSet};
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
 use url::Url;
+use crate::pages::{LinkEdge, LinkPosition};
+use crate::utils::html_text::{collapse_whitespace, visible_text};
 use crate::utils::normalize_url::normalize_url;
+use crate::utils::FEED_MIME_TYPES;
+use super::robots_directives::parse_rel;
+
+pub use super::get_images_from_html::ImageMap;
+use super::get_images_from_html::get_images_from_document;
+
+pub type LinkEdges = HashMap<String, LinkEdge>;
 
 pub fn get_urls_from_html(
     html_body: &str,
     raw_url: &str,
-) -> Result<(Vec<String>, HashMap<String, HashMap<String, String>>), Box<dyn std::error::Error>> {
-    let base_url = Url::parse(raw_url)?;
-
+) -> Result<(Vec<String>, ImageMap), Box<dyn std::error::Error>> {
     let document = Html::parse_document(html_body);
+    get_urls_from_document(&document, raw_url)
+}
+
+pub fn get_urls_from_document(
+    document: &Html,
+    raw_url: &str,
+) -> Result<(Vec<String>, ImageMap), Box<dyn std::error::Error>> {
+    let (links, image_map) = get_links_from_document(document, raw_url)?;
+    Ok((links.into_keys().collect(), image_map))
+}
+
+pub fn get_links_from_document(
+    document: &Html,
+    raw_url: &str,
+) -> Result<(LinkEdges, ImageMap), Box<dyn std::error::Error>> {
+    let base_url = get_base_url(document, raw_url)?;
+    let source_url = normalize_url(raw_url).unwrap_or_default();
+
     let a_selector = Selector::parse("a[href]").unwrap();
-    let img_selector = Selector::parse("img[src]").unwrap();
 
     let non_ascii = Regex::new(r"[^\x20-\x7E]")?;
 
-    let mut link_set = HashSet::new();
-    let mut image_map = HashMap::new();
+    let mut link_edges: LinkEdges = HashMap::new();
 
     for element in document.select(&a_selector) {
         if let Some(href) = element.value().attr("href") {
@@ -26,33 +49,168 @@ pub fn get_urls_from_html(
             }
 
             if let Ok(parsed) = base_url.join(href) {
-                link_set.insert(parsed.to_string());
+                let edge = get_link_edge(element, &source_url, parsed.as_str());
+
+                match link_edges.get_mut(parsed.as_str()) {
+                    Some(existing) => existing.merge(&edge),
+                    None => {
+                        link_edges.insert(parsed.to_string(), edge);
+                    }
+                }
             }
         }
     }
 
-    for element in document.select(&img_selector) {
-        let mut image_data = HashMap::new();
+    let image_map = get_images_from_document(document, &base_url);
 
-        if let Some(src) = element.value().attr("src") {
-            if src.contains(&['<', '>', '"', ' '][..]) || non_ascii.is_match(src) {
-                continue;
-            }
+    Ok((link_edges, image_map))
+}
 
-            if let Ok(joined) = base_url.join(src) {
-                let normalized = normalize_url(&joined.to_string());
-                if let Ok(norm_url) = normalized {
-                    image_data.insert("src".to_string(), norm_url.clone());
+fn get_link_edge(element: ElementRef, source_url: &str, target_url: &str) -> LinkEdge {
+    let mut edge = LinkEdge::new(source_url.to_string(), target_url.to_string());
 
-                    if let Some(alt) = element.value().attr("alt") {
-                        image_data.insert("alt".to_string(), alt.to_string());
-                    }
+    let mut anchor_text = collapse_whitespace(&visible_text(element));
+    if anchor_text.is_empty() {
+        let img_selector = Selector::parse("img[alt]").unwrap();
+        if let Some(img) = element.select(&img_selector).next() {
+            anchor_text = collapse_whitespace(img.value().attr("alt").unwrap_or(""));
+        }
+    }
 
-                    image_map.insert(norm_url, image_data);
-                }
-            }
+    if !anchor_text.is_empty() {
+        edge.anchor_texts.push(anchor_text);
+    }
+
+    edge.title = collapse_whitespace(element.value().attr("title").unwrap_or(""));
+    edge.rel = parse_rel(element.value().attr("rel").unwrap_or(""));
+    edge.position = get_link_position(element);
+    edge
+}
+
+fn get_link_position(element: ElementRef) -> LinkPosition {
+    for ancestor in element.ancestors().filter_map(ElementRef::wrap) {
+        let role = ancestor.value().attr("role").unwrap_or("");
+
+        match ancestor.value().name() {
+            "nav" => return LinkPosition::Nav,
+            "header" => return LinkPosition::Header,
+            "footer" => return LinkPosition::Footer,
+            "aside" => return LinkPosition::Aside,
+            _ if role.eq_ignore_ascii_case("navigation") => return LinkPosition::Nav,
+            _ if role.eq_ignore_ascii_case("banner") => return LinkPosition::Header,
+            _ if role.eq_ignore_ascii_case("contentinfo") => return LinkPosition::Footer,
+            _ if role.eq_ignore_ascii_case("complementary") => return LinkPosition::Aside,
+            _ => {}
+        }
+    }
+
+    LinkPosition::Body
+}
+
+pub fn get_base_url(document: &Html, raw_url: &str) -> Result<Url, url::ParseError> {
+    let page_url = Url::parse(raw_url)?;
+    let base_selector = Selector::parse("base[href]").unwrap();
+
+    let base_url = document
+        .select(&base_selector)
+        .next()
+        .and_then(|base| base.value().attr("href"))
+        .and_then(|href| page_url.join(href.trim()).ok())
+        .filter(|url| url.scheme() == "http" || url.scheme() == "https");
+
+    Ok(base_url.unwrap_or(page_url))
+}
+
+// Feeds advertised with <link rel="alternate" type="application/rss+xml" href="...">.
+pub fn get_feed_links_from_document(document: &Html, raw_url: &str) -> Vec<String> {
+    let link_selector = Selector::parse("link[rel][type][href]").unwrap();
+    let base_url = match get_base_url(document, raw_url) {
+        Ok(base_url) => base_url,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut feeds = Vec::new();
+
+    for link in document.select(&link_selector) {
+        let attr = |name: &str| link.value().attr(name).unwrap_or("").trim();
+
+        let is_alternate = attr("rel").split_ascii_whitespace().any(|rel| rel.eq_ignore_ascii_case("alternate"));
+        let is_feed = FEED_MIME_TYPES.iter().any(|mime| attr("type").eq_ignore_ascii_case(mime));
+
+        if !is_alternate || !is_feed {
+            continue;
         }
+
+        if let Ok(feed_url) = base_url.join(attr("href"))
+            && (feed_url.scheme() == "http" || feed_url.scheme() == "https")
+            && !feeds.contains(&feed_url.to_string())
+        {
+            feeds.push(feed_url.to_string());
+        }
+    }
+
+    feeds
+}
+
+// Longest refresh delay still treated as a redirect; slower refreshes are page reloads or
+// "you will be redirected" notices, and the page is indexed as usual.
+pub const MAX_REFRESH_REDIRECT_SECS: f64 = 3.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetaRefresh {
+    pub delay_secs: f64,
+    pub url: String,
+}
+
+impl MetaRefresh {
+    // Whether the page at `normalized_url` should be skipped in favour of the refresh target.
+    pub fn redirects_from(&self, normalized_url: &str) -> bool {
+        self.delay_secs <= MAX_REFRESH_REDIRECT_SECS
+            && normalize_url(&self.url).is_ok_and(|target| target != normalized_url)
+    }
+}
+
+pub fn get_meta_refresh_from_document(document: &Html, raw_url: &str) -> Option<MetaRefresh> {
+    let meta_selector = Selector::parse("meta[http-equiv][content]").unwrap();
+    let base_url = get_base_url(document, raw_url).ok()?;
+
+    let content = document
+        .select(&meta_selector)
+        .find(|meta| {
+            meta.value()
+                .attr("http-equiv")
+                .is_some_and(|v| v.trim().eq_ignore_ascii_case("refresh"))
+        })?
+        .value()
+        .attr("content")?;
+
+    let (delay_secs, target) = parse_meta_refresh(content)?;
+    let resolved = base_url.join(&target).ok()?;
+
+    if resolved.scheme() != "http" && resolved.scheme() != "https" {
+        return None;
+    }
+
+    Some(MetaRefresh { delay_secs, url: resolved.to_string() })
+}
+
+fn parse_meta_refresh(content: &str) -> Option<(f64, String)> {
+    let (delay, rest) = content.split_once([';', ','])?;
+    let delay: f64 = delay.trim().parse().ok().filter(|delay: &f64| *delay >= 0.0)?;
+    let rest = rest.trim();
+
+    let target = match rest.get(..3) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("url") => {
+            rest[3..].trim_start().strip_prefix('=').unwrap_or(rest)
+        }
+        _ => rest,
+    };
+
+    let target = target.trim().trim_matches(|c| c == '\'' || c == '"').trim();
+
+    if target.is_empty() {
+        return None;
     }
 
-    Ok((link_set.into_iter().collect(), image_map))
+    Some((delay, target.to_string()))
 }
@@ -1,4 +1,28 @@
+pub mod backpressure;
+pub mod budget;
+pub mod canonical;
+pub mod concurrency;
+pub mod content_handler;
 pub mod crawl;
+#[allow(clippy::module_inception)]
 pub mod crawler;
+pub mod events;
+pub mod fetch;
+pub mod fetcher;
+pub mod get_feed_content;
+pub mod get_image_data;
+pub mod get_images_from_html;
+pub mod get_main_content;
 pub mod get_page_data;
+pub mod get_page_metadata;
+pub mod get_pdf_content;
+pub mod get_structured_data;
 pub mod get_urls_from_html;
+pub mod poll_feeds;
+pub mod priority;
+pub mod process_images;
+pub mod robots_directives;
+pub mod scope;
+pub mod seeds;
+pub mod shutdown;
+pub mod sitemap;
@@ -0,0 +1,123 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{info, warn, error};
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use crate::controllers::feed_controller::FeedController;
+use crate::database::Database;
+use crate::pages::Feed;
+use crate::utils::{normalize_url, MIN_SCORE};
+use super::fetch::{fetch_settings, fetch_with_headers, FetchedResponse};
+use super::get_feed_content::get_feed_content;
+
+// Polls every feed that is due, pushing unseen item URLs to the front of the queue, and
+// schedules each feed's next poll `interval` from now. Returns the number of new items.
+pub async fn poll_feeds(feeds: &FeedController, db: &Arc<Mutex<Database>>, interval: Duration) -> usize {
+    let now = chrono::Utc::now();
+
+    let due = match feeds.due_feeds(now.timestamp()).await {
+        Ok(due) => due,
+        Err(e) => {
+            error!("Error loading due feeds: {:?}", e);
+            return 0;
+        }
+    };
+
+    let mut new_items = 0;
+
+    for mut feed in due {
+        let response = fetch_with_headers(&feed.url, fetch_settings().max_body_size, |_| true, conditional_headers(&feed))
+            .await
+            .map_err(|e| e.to_string());
+
+        match response {
+            Ok(response) if response.status_code == StatusCode::NOT_MODIFIED.as_u16() => {
+                info!("Feed {} not modified", feed.url);
+            }
+            Ok(response) => {
+                new_items += enqueue_new_items(feeds, db, &feed, &response).await;
+                update_validators(&mut feed, &response);
+            }
+            Err(err) => warn!("Error polling feed {}: {}", feed.url, err),
+        }
+
+        feed.last_polled = Some(now);
+        let next_poll = now.timestamp() + interval.as_secs() as i64;
+        if let Err(e) = feeds.save_poll(&feed, next_poll).await {
+            error!("Error saving feed {}: {:?}", feed.url, e);
+        }
+    }
+
+    new_items
+}
+
+pub fn conditional_headers(feed: &Feed) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+
+    if !feed.etag.is_empty()
+        && let Ok(etag) = HeaderValue::from_str(&feed.etag)
+    {
+        headers.insert(IF_NONE_MATCH, etag);
+    }
+
+    if !feed.last_modified.is_empty()
+        && let Ok(last_modified) = HeaderValue::from_str(&feed.last_modified)
+    {
+        headers.insert(IF_MODIFIED_SINCE, last_modified);
+    }
+
+    headers
+}
+
+fn update_validators(feed: &mut Feed, response: &FetchedResponse) {
+    let header = |name| {
+        response
+            .headers
+            .get(name)
+            .and_then(|val| val.to_str().ok())
+            .unwrap_or("")
+            .to_string()
+    };
+
+    feed.etag = header(ETAG);
+    feed.last_modified = header(LAST_MODIFIED);
+}
+
+async fn enqueue_new_items(feeds: &FeedController, db: &Arc<Mutex<Database>>, feed: &Feed, response: &FetchedResponse) -> usize {
+    let content = match get_feed_content(&response.text(), &feed.url) {
+        Ok(content) => content,
+        Err(err) => {
+            warn!("Error parsing feed {}: {}", feed.url, err);
+            return 0;
+        }
+    };
+
+    let items: Vec<String> = content.links.into_iter().filter(|link| normalize_url(link).is_ok()).collect();
+
+    let new_items = match feeds.add_items(feed, &items).await {
+        Ok(new_items) => new_items,
+        Err(e) => {
+            error!("Error recording items of feed {}: {:?}", feed.url, e);
+            return 0;
+        }
+    };
+
+    let mut pushed = 0;
+    for item in &new_items {
+        let normalized = normalize_url(item).unwrap_or_default();
+        let mut db = db.lock().await;
+
+        if let Ok(true) = db.has_url_been_visited(&normalized).await {
+            continue;
+        }
+
+        let queued = db.push_urls(&[(item.clone(), MIN_SCORE as f64)], 0.0).await;
+        if queued.is_ok_and(|queued| !queued.is_empty()) {
+            pushed += 1;
+        }
+    }
+
+    info!("Feed {}: {} new items", feed.url, pushed);
+    pushed
+}
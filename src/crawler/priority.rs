@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use regex::Regex;
+use url::Url;
+use crate::database::frontier::host_of;
+use crate::utils::{normalize_url, MAX_SCORE, MIN_SCORE};
+
+// At most this many pages hold OPIC cash at once.
+pub const MAX_OPIC_PAGES: usize = 1_000_000;
+
+// A link about to be pushed to the frontier. `parent_score` is the score the source page
+// was popped with; `outlink_count` is how many followable links the source page has.
+// `queued` is set when the target is already waiting in the frontier. Links to visited
+// pages are not scored.
+#[derive(Debug, Clone)]
+pub struct LinkCandidate<'a> {
+    pub source_url: &'a str,
+    pub target_url: &'a str,
+    pub parent_score: f64,
+    pub outlink_count: usize,
+    pub queued: bool,
+}
+
+// Decides where newly found links land in `spider_queue`; lower scores are crawled first.
+// When a link is already queued the crawler keeps the lower of the two scores, so a URL
+// moves up as better backlinks to it are discovered.
+pub trait PriorityPolicy: Send + Sync {
+    fn score(&self, link: &LinkCandidate) -> f64;
+
+    // Called with the normalized URL of a crawled page once its links were scored.
+    fn crawled(&self, _normalized_url: &str) {}
+
+    // Called with the normalized URL of each URL the frontier hands to a worker.
+    fn dispatched(&self, _normalized_url: &str) {}
+}
+
+// Breadth-first: one step further than the page the link was found on.
+pub struct Bfs;
+
+impl PriorityPolicy for Bfs {
+    fn score(&self, link: &LinkCandidate) -> f64 {
+        link.parent_score + 1.0
+    }
+}
+
+// Depth-first: links from the page just crawled go ahead of everything queued before them.
+pub struct Dfs;
+
+impl PriorityPolicy for Dfs {
+    fn score(&self, link: &LinkCandidate) -> f64 {
+        link.parent_score - 1.0
+    }
+}
+
+// Online Page Importance Computation. Every crawled page splits its cash evenly between its
+// outlinks and is then forgotten; a URL's score is the inverse of the cash it has collected,
+// so pages with many well-funded backlinks are fetched first. Seeds and pages never credited
+// hold 1.0. The cash is kept in memory: a restarted crawl starts every page from 1.0 again,
+// and once `MAX_OPIC_PAGES` pages hold cash, new targets are scored from their share alone.
+#[derive(Default)]
+pub struct Opic {
+    cash: Mutex<HashMap<String, f64>>,
+}
+
+impl Opic {
+    pub fn cash(&self, url: &str) -> f64 {
+        let key = normalize_url(url).unwrap_or_else(|_| url.to_string());
+        self.cash.lock().unwrap().get(&key).copied().unwrap_or(1.0)
+    }
+}
+
+impl PriorityPolicy for Opic {
+    fn score(&self, link: &LinkCandidate) -> f64 {
+        let source = normalize_url(link.source_url).unwrap_or_else(|_| link.source_url.to_string());
+        let target = normalize_url(link.target_url).unwrap_or_else(|_| link.target_url.to_string());
+
+        let mut cash = self.cash.lock().unwrap();
+        let share = cash.get(&source).copied().unwrap_or(1.0) / link.outlink_count.max(1) as f64;
+        let tracked = cash.len();
+        let target_cash = match cash.get_mut(&target) {
+            Some(target_cash) => {
+                *target_cash += share;
+                *target_cash
+            }
+            None if tracked < MAX_OPIC_PAGES => *cash.entry(target).or_insert(share),
+            None => share,
+        };
+
+        1.0 / target_cash
+    }
+
+    fn crawled(&self, normalized_url: &str) {
+        self.cash.lock().unwrap().remove(normalized_url);
+    }
+}
+
+// Round-robin across hosts: a URL queued behind n others of its host scores n + 1, so every
+// host gets a turn before any host gets a second one.
+#[derive(Default)]
+pub struct HostDiversity {
+    queued: Mutex<HashMap<String, usize>>,
+}
+
+impl HostDiversity {
+    // URLs of `host` queued through this policy and not dispatched yet.
+    pub fn queued(&self, host: &str) -> usize {
+        self.queued.lock().unwrap().get(host.trim_start_matches("www.")).copied().unwrap_or(0)
+    }
+}
+
+impl PriorityPolicy for HostDiversity {
+    fn score(&self, link: &LinkCandidate) -> f64 {
+        let host = Url::parse(link.target_url)
+            .ok()
+            .and_then(|url| url.host_str().map(|host| host.trim_start_matches("www.").to_string()))
+            .unwrap_or_default();
+
+        let mut queued = self.queued.lock().unwrap();
+        if link.queued {
+            return queued.get(&host).copied().unwrap_or(0).max(1) as f64;
+        }
+
+        let count = queued.entry(host).or_insert(0);
+        *count += 1;
+        *count as f64
+    }
+
+    fn dispatched(&self, normalized_url: &str) {
+        let host = host_of(normalized_url);
+        let mut queued = self.queued.lock().unwrap();
+
+        if let Some(count) = queued.get_mut(host) {
+            *count -= 1;
+            if *count == 0 {
+                queued.remove(host);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UrlBoost {
+    pub pattern: Regex,
+    pub boost: f64,
+}
+
+impl PartialEq for UrlBoost {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern.as_str() == other.pattern.as_str() && self.boost == other.boost
+    }
+}
+
+// Parses `pattern=boost` pairs separated by `;`, e.g. `/news/=5;\.pdf$=-2`. A positive
+// boost moves matching URLs up the queue, a negative one pushes them back.
+pub fn parse_url_boosts(value: &str) -> Result<Vec<UrlBoost>, String> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (pattern, boost) = pair
+                .rsplit_once('=')
+                .ok_or_else(|| format!("Invalid URL boost '{}': expected pattern=boost", pair))?;
+            let boost = boost
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid URL boost '{}': boost must be a number", pair))?;
+            let pattern = Regex::new(pattern.trim()).map_err(|e| format!("Invalid URL boost pattern '{}': {}", pattern, e))?;
+
+            Ok(UrlBoost { pattern, boost })
+        })
+        .collect()
+}
+
+// Applies URL-pattern boosts on top of another policy.
+pub struct Boosted {
+    pub inner: Arc<dyn PriorityPolicy>,
+    pub boosts: Vec<UrlBoost>,
+}
+
+impl PriorityPolicy for Boosted {
+    fn score(&self, link: &LinkCandidate) -> f64 {
+        let boost: f64 = self
+            .boosts
+            .iter()
+            .filter(|boost| boost.pattern.is_match(link.target_url))
+            .map(|boost| boost.boost)
+            .sum();
+
+        self.inner.score(link) - boost
+    }
+
+    fn crawled(&self, normalized_url: &str) {
+        self.inner.crawled(normalized_url);
+    }
+
+    fn dispatched(&self, normalized_url: &str) {
+        self.inner.dispatched(normalized_url);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PriorityPolicyKind {
+    #[default]
+    Bfs,
+    Dfs,
+    Opic,
+    HostDiversity,
+}
+
+impl PriorityPolicyKind {
+    pub fn build(self, boosts: Vec<UrlBoost>) -> Arc<dyn PriorityPolicy> {
+        let policy: Arc<dyn PriorityPolicy> = match self {
+            Self::Bfs => Arc::new(Bfs),
+            Self::Dfs => Arc::new(Dfs),
+            Self::Opic => Arc::new(Opic::default()),
+            Self::HostDiversity => Arc::new(HostDiversity::default()),
+        };
+
+        if boosts.is_empty() {
+            policy
+        } else {
+            Arc::new(Boosted { inner: policy, boosts })
+        }
+    }
+}
+
+impl FromStr for PriorityPolicyKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "bfs" => Ok(Self::Bfs),
+            "dfs" => Ok(Self::Dfs),
+            "opic" => Ok(Self::Opic),
+            "host-diversity" => Ok(Self::HostDiversity),
+            other => Err(format!("Invalid priority policy: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for PriorityPolicyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bfs => write!(f, "bfs"),
+            Self::Dfs => write!(f, "dfs"),
+            Self::Opic => write!(f, "opic"),
+            Self::HostDiversity => write!(f, "host-diversity"),
+        }
+    }
+}
+
+// The queue score for `link`, keeping an existing lower score. `MIN_SCORE` itself is left to
+// new feed items, which go ahead of every crawled link.
+pub fn bounded_score(policy: &dyn PriorityPolicy, link: &LinkCandidate, existing: Option<f64>) -> f64 {
+    let score = policy.score(link);
+    let score = existing.map_or(score, |existing| existing.min(score));
+    score.clamp(MIN_SCORE as f64 + 1.0, MAX_SCORE as f64)
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+use tokio::time::sleep;
+use tracing::{info, error};
+use crate::database::Database;
+use crate::database::frontier::host_of;
+use super::crawler::CrawlerConfig;
+use super::get_image_data::get_image_data_with;
+
+impl CrawlerConfig {
+    // Downloads every image discovered in this batch that has not been analysed yet through
+    // the crawler's fetcher. Each host's images are fetched one at a time, `delay_for(host)`
+    // apart, and all downloads share the crawler's concurrency limit.
+    pub async fn process_images(&self, db: &Arc<Mutex<Database>>) {
+        let mut pending = HashMap::new();
+
+        for images in self.images.lock().await.values() {
+            for image in images {
+                if !image.source_url.is_empty() {
+                    pending.entry(image.normalized_source_url.clone()).or_insert(image.source_url.clone());
+                }
+            }
+        }
+
+        let already_processed = self.image_data.lock().await;
+        pending.retain(|normalized_url, _| !already_processed.contains_key(normalized_url));
+        drop(already_processed);
+
+        let mut by_host: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        for (normalized_url, source_url) in pending {
+            match db.lock().await.has_image_data(&normalized_url).await {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(err) => {
+                    error!("Error checking image data: {}", err);
+                    continue;
+                }
+            }
+
+            by_host.entry(host_of(&normalized_url).to_string()).or_default().push((normalized_url, source_url));
+        }
+
+        let frontier = db.lock().await.frontier().clone();
+        let mut tasks = JoinSet::new();
+
+        for (host, images) in by_host {
+            let delay = frontier.delay_for(&host);
+            let fetcher = self.fetcher.clone();
+            let permits = self.concurrency.semaphore();
+
+            tasks.spawn(async move {
+                let mut results = Vec::with_capacity(images.len());
+                for (i, (normalized_url, source_url)) in images.into_iter().enumerate() {
+                    if i > 0 {
+                        sleep(delay).await;
+                    }
+                    let Ok(permit) = permits.clone().acquire_owned().await else {
+                        break;
+                    };
+                    let result = get_image_data_with(fetcher.as_ref(), &source_url).await.map_err(|e| e.to_string());
+                    drop(permit);
+                    results.push((normalized_url, result));
+                }
+                results
+            });
+        }
+
+        let mut count = 0;
+        while let Some(joined) = tasks.join_next().await {
+            let results = match joined {
+                Ok(results) => results,
+                Err(err) => {
+                    error!("Image task failed: {:?}", err);
+                    continue;
+                }
+            };
+
+            for (normalized_url, result) in results {
+                match result {
+                    Ok(image_data) => {
+                        self.image_data.lock().await.insert(image_data.normalized_source_url.clone(), image_data);
+                        count += 1;
+                    }
+                    Err(err) => error!("Error fetching image {}: {}", normalized_url, err),
+                }
+            }
+        }
+
+        info!("Processed {} images", count);
+    }
+}
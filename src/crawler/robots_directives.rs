@@ -0,0 +1,122 @@
+use scraper::{Html, Selector};
+use std::collections::BTreeSet;
+use crate::utils::ROBOTS_USER_AGENT;
+
+pub const NOFOLLOW_RELS: [&str; 3] = ["nofollow", "ugc", "sponsored"];
+const DIRECTIVES_WITH_VALUE: [&str; 4] = ["unavailable_after", "max-snippet", "max-image-preview", "max-video-preview"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RobotsDirectives {
+    pub noindex: bool,
+    pub nofollow: bool,
+}
+
+impl RobotsDirectives {
+    pub fn parse(content: &str) -> Self {
+        let mut directives = Self::default();
+
+        for token in content.split(',').map(|t| t.trim().to_ascii_lowercase()) {
+            match token.as_str() {
+                "noindex" => directives.noindex = true,
+                "nofollow" => directives.nofollow = true,
+                "none" => {
+                    directives.noindex = true;
+                    directives.nofollow = true;
+                }
+                _ => {}
+            }
+        }
+
+        directives
+    }
+
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            noindex: self.noindex || other.noindex,
+            nofollow: self.nofollow || other.nofollow,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RobotsPolicy {
+    pub respect_noindex: bool,
+    pub respect_nofollow: bool,
+    pub respect_rel_nofollow: bool,
+}
+
+impl Default for RobotsPolicy {
+    fn default() -> Self {
+        Self {
+            respect_noindex: true,
+            respect_nofollow: true,
+            respect_rel_nofollow: true,
+        }
+    }
+}
+
+impl RobotsPolicy {
+    pub fn ignore_all() -> Self {
+        Self {
+            respect_noindex: false,
+            respect_nofollow: false,
+            respect_rel_nofollow: false,
+        }
+    }
+
+    pub fn should_index(&self, directives: &RobotsDirectives) -> bool {
+        !(self.respect_noindex && directives.noindex)
+    }
+
+    pub fn should_follow(&self, directives: &RobotsDirectives) -> bool {
+        !(self.respect_nofollow && directives.nofollow)
+    }
+
+    pub fn should_follow_link(&self, rel: &BTreeSet<String>) -> bool {
+        !(self.respect_rel_nofollow && is_nofollow_rel(rel))
+    }
+}
+
+pub fn is_nofollow_rel(rel: &BTreeSet<String>) -> bool {
+    NOFOLLOW_RELS.iter().any(|r| rel.contains(*r))
+}
+
+pub fn parse_rel(rel: &str) -> BTreeSet<String> {
+    rel.split_ascii_whitespace().map(|r| r.to_ascii_lowercase()).collect()
+}
+
+pub fn parse_x_robots_tag<'a>(values: impl IntoIterator<Item = &'a str>) -> RobotsDirectives {
+    let mut directives = RobotsDirectives::default();
+
+    for value in values {
+        let applies = match value.split_once(':') {
+            Some((prefix, rest)) if !prefix.contains(',') && !DIRECTIVES_WITH_VALUE.contains(&prefix.trim().to_ascii_lowercase().as_str()) => {
+                if prefix.trim().eq_ignore_ascii_case(ROBOTS_USER_AGENT) {
+                    Some(rest)
+                } else {
+                    None
+                }
+            }
+            _ => Some(value),
+        };
+
+        if let Some(content) = applies {
+            directives = directives.merge(RobotsDirectives::parse(content));
+        }
+    }
+
+    directives
+}
+
+pub fn get_robots_directives_from_document(document: &Html) -> RobotsDirectives {
+    let meta_selector = Selector::parse("meta[name][content]").unwrap();
+
+    document
+        .select(&meta_selector)
+        .filter(|meta| {
+            let name = meta.value().attr("name").unwrap_or("").trim();
+            name.eq_ignore_ascii_case("robots") || name.eq_ignore_ascii_case(ROBOTS_USER_AGENT)
+        })
+        .map(|meta| RobotsDirectives::parse(meta.value().attr("content").unwrap_or("")))
+        .fold(RobotsDirectives::default(), RobotsDirectives::merge)
+}
@@ -0,0 +1,103 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use regex::Regex;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScopeAction {
+    Allow,
+    #[default]
+    Deny,
+}
+
+impl FromStr for ScopeAction {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "allow" => Ok(Self::Allow),
+            "deny" => Ok(Self::Deny),
+            other => Err(format!("Unknown scope action '{}', expected allow or deny", other)),
+        }
+    }
+}
+
+impl fmt::Display for ScopeAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Allow => write!(f, "allow"),
+            Self::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScopeRule {
+    pub pattern: String,
+    pub action: ScopeAction,
+    #[serde(skip)]
+    regex: Regex,
+}
+
+impl ScopeRule {
+    pub fn new(pattern: &str, action: ScopeAction) -> Result<Self, String> {
+        let regex = Regex::new(pattern).map_err(|e| format!("Invalid scope pattern '{}': {}", pattern, e))?;
+        Ok(Self { pattern: pattern.to_string(), action, regex })
+    }
+
+    pub fn matches(&self, url: &str) -> bool {
+        self.regex.is_match(url)
+    }
+}
+
+// URL regexes changed at runtime through the admin API. A URL matching a deny rule is out of
+// scope; once any allow rule exists, URLs must also match one of them.
+#[derive(Debug, Clone, Default)]
+pub struct ScopeRules {
+    rules: Arc<RwLock<Vec<ScopeRule>>>,
+}
+
+impl ScopeRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Adds the rule, replacing the action of an existing rule with the same pattern.
+    pub fn add(&self, rule: ScopeRule) {
+        if let Ok(mut rules) = self.rules.write() {
+            match rules.iter_mut().find(|existing| existing.pattern == rule.pattern) {
+                Some(existing) => *existing = rule,
+                None => rules.push(rule),
+            }
+        }
+    }
+
+    // Returns false if no rule had this pattern.
+    pub fn remove(&self, pattern: &str) -> bool {
+        let Ok(mut rules) = self.rules.write() else {
+            return false;
+        };
+        let before = rules.len();
+        rules.retain(|rule| rule.pattern != pattern);
+        rules.len() != before
+    }
+
+    pub fn list(&self) -> Vec<ScopeRule> {
+        self.rules.read().map(|rules| rules.clone()).unwrap_or_default()
+    }
+
+    pub fn allows(&self, url: &str) -> bool {
+        let Ok(rules) = self.rules.read() else {
+            return true;
+        };
+
+        if rules.iter().any(|rule| rule.action == ScopeAction::Deny && rule.matches(url)) {
+            return false;
+        }
+
+        let mut allow_rules = rules.iter().filter(|rule| rule.action == ScopeAction::Allow).peekable();
+        allow_rules.peek().is_none() || allow_rules.any(|rule| rule.matches(url))
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::io::AsyncReadExt;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use serde::Serialize;
+use crate::database::Database;
+use super::scope::ScopeRules;
+use crate::utils::{is_valid_url, normalize_url, strip_url, MAX_SCORE, MIN_SCORE, MAX_SEEDS_PER_DRAIN};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Seed {
+    pub url: String,
+    pub depth: f64,
+    pub priority: f64,
+}
+
+impl Seed {
+    pub fn new(url: String) -> Self {
+        Self { url, depth: 0.0, priority: 0.0 }
+    }
+
+    // Seeds start at their depth; a higher priority moves them further up the queue.
+    pub fn score(&self) -> f64 {
+        (self.depth - self.priority).clamp(MIN_SCORE as f64, MAX_SCORE as f64)
+    }
+}
+
+// Where seeds come from: `https://...` is a single URL, `stdin` (or `-`) reads standard
+// input, `redis:<key>` drains a Redis list and anything else, optionally prefixed with
+// `file:`, is a path. Files ending in .csv and Redis entries may carry `url,depth,priority`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeedSource {
+    Url(String),
+    File(PathBuf),
+    Stdin,
+    RedisList(String),
+}
+
+impl FromStr for SeedSource {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+
+        if value.is_empty() {
+            return Err("Empty seed source".to_string());
+        }
+
+        if value == "stdin" || value == "-" {
+            return Ok(Self::Stdin);
+        }
+
+        if let Some(key) = value.strip_prefix("redis:") {
+            return match key.trim() {
+                "" => Err(format!("Invalid seed source '{}': missing Redis key", value)),
+                key => Ok(Self::RedisList(key.to_string())),
+            };
+        }
+
+        if value.starts_with("http://") || value.starts_with("https://") {
+            return Ok(Self::Url(value.to_string()));
+        }
+
+        Ok(Self::File(PathBuf::from(value.strip_prefix("file:").unwrap_or(value))))
+    }
+}
+
+impl fmt::Display for SeedSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Url(url) => write!(f, "{}", url),
+            Self::File(path) => write!(f, "file:{}", path.display()),
+            Self::Stdin => write!(f, "stdin"),
+            Self::RedisList(key) => write!(f, "redis:{}", key),
+        }
+    }
+}
+
+impl SeedSource {
+    fn is_csv(&self) -> bool {
+        match self {
+            Self::File(path) => path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv")),
+            Self::RedisList(_) => true,
+            Self::Url(_) | Self::Stdin => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RejectedSeed {
+    pub input: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SeedReport {
+    pub accepted: Vec<Seed>,
+    pub rejected: Vec<RejectedSeed>,
+    seen: HashSet<String>,
+    scope: ScopeRules,
+}
+
+impl SeedReport {
+    // Seeds that `scope` does not allow are rejected as out of scope.
+    pub fn with_scope(scope: ScopeRules) -> Self {
+        Self { scope, ..Self::default() }
+    }
+
+    fn reject(&mut self, input: &str, reason: impl Into<String>) {
+        self.rejected.push(RejectedSeed {
+            input: input.to_string(),
+            reason: reason.into(),
+        });
+    }
+
+    // Parses and validates one line of seed input. Blank lines, `#` comments and a
+    // leading CSV header are skipped.
+    pub fn add_line(&mut self, line: &str, csv: bool) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return;
+        }
+
+        let seed = if csv {
+            match parse_csv_seed(line) {
+                Ok(Some(seed)) => seed,
+                Ok(None) => return,
+                Err(reason) => return self.reject(line, reason),
+            }
+        } else {
+            Seed::new(line.to_string())
+        };
+
+        self.add_seed(seed);
+    }
+
+    // Runs a seed through the same checks `push_urls` and the crawler apply to links.
+    pub fn add_seed(&mut self, seed: Seed) {
+        if !is_valid_url(&seed.url) {
+            return self.reject(&seed.url, "invalid URL characters");
+        }
+
+        let stripped = match strip_url(&seed.url) {
+            Ok(stripped) => stripped,
+            Err(e) => return self.reject(&seed.url, e.to_string()),
+        };
+
+        let normalized = match normalize_url(&stripped) {
+            Ok(normalized) => normalized,
+            Err(e) => return self.reject(&seed.url, e.to_string()),
+        };
+
+        if !self.scope.allows(&seed.url) {
+            return self.reject(&seed.url, "out of scope");
+        }
+
+        if !self.seen.insert(normalized) {
+            return self.reject(&seed.url, "duplicate seed");
+        }
+
+        self.accepted.push(seed);
+    }
+
+    pub fn rejection_summary(&self) -> BTreeMap<String, usize> {
+        let mut summary = BTreeMap::new();
+        for rejected in &self.rejected {
+            *summary.entry(rejected.reason.clone()).or_insert(0) += 1;
+        }
+        summary
+    }
+
+    pub fn log_summary(&self) {
+        for rejected in &self.rejected {
+            warn!("Rejected seed {}: {}", rejected.input, rejected.reason);
+        }
+
+        info!("Seeds: {} accepted, {} rejected", self.accepted.len(), self.rejected.len());
+        for (reason, count) in self.rejection_summary() {
+            info!("  {} rejected: {}", count, reason);
+        }
+    }
+}
+
+fn parse_csv_seed(line: &str) -> Result<Option<Seed>, String> {
+    let mut fields = line.split(',').map(str::trim);
+    let url = fields.next().unwrap_or("");
+
+    if url.eq_ignore_ascii_case("url") {
+        return Ok(None);
+    }
+
+    let mut number = |name: &str| -> Result<f64, String> {
+        match fields.next() {
+            None | Some("") => Ok(0.0),
+            Some(value) => value
+                .parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .ok_or_else(|| format!("invalid {}", name)),
+        }
+    };
+
+    Ok(Some(Seed {
+        url: url.to_string(),
+        depth: number("depth")?,
+        priority: number("priority")?,
+    }))
+}
+
+async fn read_seed_source(source: &SeedSource, db: &Arc<Mutex<Database>>) -> Result<Vec<String>, Box<dyn Error>> {
+    match source {
+        SeedSource::Url(url) => Ok(vec![url.clone()]),
+        SeedSource::File(path) => Ok(tokio::fs::read_to_string(path).await?.lines().map(str::to_string).collect()),
+        SeedSource::Stdin => {
+            let mut input = String::new();
+            tokio::io::stdin().read_to_string(&mut input).await?;
+            Ok(input.lines().map(str::to_string).collect())
+        }
+        SeedSource::RedisList(key) => Ok(db.lock().await.drain_list(key, MAX_SEEDS_PER_DRAIN).await?),
+    }
+}
+
+// Reads, validates and enqueues seeds from every source. Duplicates are detected across
+// sources; a source that can't be read is reported as a rejection.
+pub async fn seed_queue(db: &Arc<Mutex<Database>>, sources: &[SeedSource], scope: &ScopeRules) -> SeedReport {
+    let mut report = SeedReport::with_scope(scope.clone());
+
+    for source in sources {
+        match read_seed_source(source, db).await.map_err(|e| e.to_string()) {
+            Ok(lines) => {
+                for line in lines {
+                    report.add_line(&line, source.is_csv());
+                }
+            }
+            Err(err) => report.reject(&source.to_string(), format!("unreadable source: {}", err)),
+        }
+    }
+
+    let mut pushed = Vec::with_capacity(report.accepted.len());
+    for seed in std::mem::take(&mut report.accepted) {
+        let queued = db.lock().await.push_urls(&[(seed.url.clone(), seed.score())], seed.depth).await;
+        match queued {
+            Ok(_) => pushed.push(seed),
+            Err(e) => report.reject(&seed.url, format!("queue error: {}", e)),
+        }
+    }
+    report.accepted = pushed;
+
+    report
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::{info, error};
+use tokio::sync::Notify;
+use crate::metrics::metrics;
+
+// Shared stop flag. Workers stop popping URLs once it is triggered; `wait` lets the main loop
+// react to it without polling.
+#[derive(Debug, Clone, Default)]
+pub struct Shutdown {
+    triggered: Arc<AtomicBool>,
+    notify: Arc<Notify>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn trigger(&self) {
+        if !self.triggered.swap(true, Ordering::SeqCst) {
+            self.notify.notify_waiters();
+        }
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.triggered.load(Ordering::SeqCst)
+    }
+
+    pub async fn wait(&self) {
+        // Registered before the flag check so a trigger in between is not missed.
+        let notified = self.notify.notified();
+        if self.is_triggered() {
+            return;
+        }
+        notified.await;
+    }
+}
+
+// URLs popped from the frontier whose crawl has not finished yet, keyed by normalized URL,
+// with the raw URL and score needed to put them back.
+#[derive(Debug, Clone, Default)]
+pub struct InFlight {
+    urls: Arc<Mutex<HashMap<String, (String, f64)>>>,
+}
+
+impl InFlight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Tracks `normalized_url` until the returned guard is dropped.
+    pub fn track(&self, normalized_url: &str, raw_url: &str, score: f64) -> InFlightGuard {
+        if let Ok(mut urls) = self.urls.lock() {
+            urls.insert(normalized_url.to_string(), (raw_url.to_string(), score));
+        }
+        metrics().in_flight.inc();
+
+        InFlightGuard {
+            urls: self.urls.clone(),
+            normalized_url: normalized_url.to_string(),
+        }
+    }
+
+    // Raw URL and score of everything still in flight.
+    pub fn snapshot(&self) -> Vec<(String, f64)> {
+        match self.urls.lock() {
+            Ok(urls) => urls.values().cloned().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    pub fn contains(&self, normalized_url: &str) -> bool {
+        self.urls.lock().map(|urls| urls.contains_key(normalized_url)).unwrap_or(false)
+    }
+
+    pub fn len(&self) -> usize {
+        self.urls.lock().map(|urls| urls.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub struct InFlightGuard {
+    urls: Arc<Mutex<HashMap<String, (String, f64)>>>,
+    normalized_url: String,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if let Ok(mut urls) = self.urls.lock() {
+            urls.remove(&self.normalized_url);
+        }
+        metrics().in_flight.dec();
+    }
+}
+
+// Resolves on the first SIGINT or SIGTERM.
+pub async fn wait_for_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = match signal(SignalKind::terminate()) {
+            Ok(terminate) => terminate,
+            Err(err) => {
+                error!("Error installing SIGTERM handler: {}", err);
+                let _ = tokio::signal::ctrl_c().await;
+                info!("Received SIGINT");
+                return;
+            }
+        };
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => info!("Received SIGINT"),
+            _ = terminate.recv() => info!("Received SIGTERM"),
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        info!("Received SIGINT");
+    }
+}
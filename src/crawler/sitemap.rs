@@ -0,0 +1,210 @@
+use std::collections::{HashSet, VecDeque};
+use std::error::Error;
+use std::io::Read;
+use chrono::{DateTime, NaiveDate, Utc};
+use tracing::{info, warn};
+use roxmltree::{Document, Node};
+use url::Url;
+use crate::utils::MAX_SITEMAPS;
+use super::fetch::{fetch, fetch_settings};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitemapEntry {
+    pub url: String,
+    pub priority: Option<f64>,
+    pub lastmod: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sitemap {
+    Index(Vec<String>),
+    UrlSet(Vec<SitemapEntry>),
+}
+
+impl SitemapEntry {
+    pub fn new(url: String) -> Self {
+        Self { url, priority: None, lastmod: None }
+    }
+
+    // Queue score for this entry; lower scores are crawled first. High `<priority>` and a
+    // recent `<lastmod>` each pull the entry forward, landing it between the seed (0) and
+    // pages found two links away from it.
+    pub fn score(&self, now: DateTime<Utc>) -> f64 {
+        let priority = self.priority.unwrap_or(0.5).clamp(0.0, 1.0);
+        let age_days = self
+            .lastmod
+            .map(|lastmod| (now - lastmod).num_days().max(0) as f64)
+            .unwrap_or(365.0);
+
+        1.0 + (1.0 - priority) + (age_days / 365.0).min(1.0)
+    }
+
+    // The sitemap protocol only lets a sitemap list URLs on its own host. Like normalized
+    // URLs, `www.example.com` and `example.com` count as the same host.
+    pub fn is_on_host(&self, host: &str) -> bool {
+        let host = host.trim_start_matches("www.");
+        Url::parse(&self.url).is_ok_and(|url| {
+            url.host_str().is_some_and(|h| h.trim_start_matches("www.").eq_ignore_ascii_case(host))
+        })
+    }
+}
+
+// Finds every page listed in the site's sitemaps, following sitemap indexes up to
+// `MAX_SITEMAPS` documents. Sitemaps come from robots.txt, falling back to /sitemap.xml.
+// Entries on other hosts are dropped.
+pub async fn get_sitemap_entries(start_url: &str) -> Result<Vec<SitemapEntry>, Box<dyn Error>> {
+    let start = Url::parse(start_url)?;
+    let host = start.host_str().ok_or("Starting URL has no host")?.to_string();
+
+    let mut pending: VecDeque<String> = discover_sitemaps(&start).await.into();
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+
+    while let Some(sitemap_url) = pending.pop_front() {
+        if seen.len() >= MAX_SITEMAPS {
+            warn!("Sitemap limit of {} reached, skipping the rest", MAX_SITEMAPS);
+            break;
+        }
+        if !seen.insert(sitemap_url.clone()) {
+            continue;
+        }
+
+        let response = match fetch(&sitemap_url, fetch_settings().max_body_size, |_| true).await {
+            Ok(response) => response,
+            Err(err) => {
+                warn!("Error fetching sitemap {}: {}", sitemap_url, err);
+                continue;
+            }
+        };
+
+        match parse_sitemap(&response.bytes) {
+            Ok(Sitemap::Index(sitemaps)) => pending.extend(sitemaps),
+            Ok(Sitemap::UrlSet(urls)) => {
+                let found = urls.len();
+                entries.extend(urls.into_iter().filter(|entry| entry.is_on_host(&host)));
+                info!("Found {} URLs in sitemap {}", found, sitemap_url);
+            }
+            Err(err) => warn!("Error parsing sitemap {}: {}", sitemap_url, err),
+        }
+    }
+
+    Ok(entries)
+}
+
+// The root URL of each host among `urls` that is not in `known` yet, in order, so each seed
+// host has its sitemaps read once. The hosts are added to `known`.
+pub fn new_sitemap_roots<'a>(urls: impl IntoIterator<Item = &'a str>, known: &mut HashSet<String>) -> Vec<String> {
+    urls.into_iter()
+        .filter_map(|url| Url::parse(url).ok())
+        .filter_map(|url| {
+            let host = url.host_str()?.to_ascii_lowercase();
+            let root = format!("{}://{}/", url.scheme(), host);
+            known.insert(host).then_some(root)
+        })
+        .collect()
+}
+
+async fn discover_sitemaps(start: &Url) -> Vec<String> {
+    let mut sitemaps = Vec::new();
+
+    if let Ok(robots_url) = start.join("/robots.txt") {
+        match fetch(robots_url.as_str(), fetch_settings().max_body_size, |_| true).await {
+            Ok(response) => sitemaps = parse_robots_sitemaps(&response.text()),
+            Err(err) => info!("No robots.txt at {}: {}", robots_url, err),
+        }
+    }
+
+    if sitemaps.is_empty()
+        && let Ok(default_sitemap) = start.join("/sitemap.xml")
+    {
+        sitemaps.push(default_sitemap.to_string());
+    }
+
+    sitemaps
+}
+
+pub fn parse_robots_sitemaps(robots_txt: &str) -> Vec<String> {
+    robots_txt
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim().eq_ignore_ascii_case("sitemap").then(|| value.trim())
+        })
+        .filter(|value| Url::parse(value).is_ok())
+        .map(str::to_string)
+        .collect()
+}
+
+// Parses a sitemap index, XML urlset or plain-text sitemap. Gzipped input is detected by
+// its magic bytes, since servers often send .xml.gz files without Content-Encoding.
+pub fn parse_sitemap(bytes: &[u8]) -> Result<Sitemap, String> {
+    let mut decompressed = Vec::new();
+    let bytes = if bytes.starts_with(&[0x1f, 0x8b]) {
+        flate2::read::GzDecoder::new(bytes)
+            .take(fetch_settings().max_body_size as u64 * 5)
+            .read_to_end(&mut decompressed)
+            .map_err(|e| format!("Invalid gzip sitemap: {}", e))?;
+        &decompressed[..]
+    } else {
+        bytes
+    };
+
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.trim_start_matches('\u{feff}').trim();
+
+    if !text.starts_with('<') {
+        let urls = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with("http://") || line.starts_with("https://"))
+            .map(|line| SitemapEntry::new(line.to_string()))
+            .collect();
+        return Ok(Sitemap::UrlSet(urls));
+    }
+
+    let document = Document::parse(text).map_err(|e| format!("Invalid XML: {}", e))?;
+    let root = document.root_element();
+
+    match root.tag_name().name() {
+        "sitemapindex" => Ok(Sitemap::Index(
+            children(root, "sitemap").filter_map(|sitemap| child_text(sitemap, "loc")).collect(),
+        )),
+        "urlset" => Ok(Sitemap::UrlSet(
+            children(root, "url")
+                .filter_map(|url| {
+                    Some(SitemapEntry {
+                        url: child_text(url, "loc")?,
+                        priority: child_text(url, "priority").and_then(|p| p.parse().ok()),
+                        lastmod: child_text(url, "lastmod").and_then(|d| parse_lastmod(&d)),
+                    })
+                })
+                .collect(),
+        )),
+        other => Err(format!("Unknown sitemap root element: {}", other)),
+    }
+}
+
+// W3C datetime: a full RFC 3339 timestamp or just the date.
+pub fn parse_lastmod(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime.with_timezone(&Utc));
+    }
+
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|datetime| datetime.and_utc())
+}
+
+fn children<'a, 'input: 'a>(node: Node<'a, 'input>, name: &'a str) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children().filter(move |n| n.tag_name().name() == name)
+}
+
+fn child_text(node: Node, name: &str) -> Option<String> {
+    children(node, name)
+        .next()
+        .and_then(|n| n.text())
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
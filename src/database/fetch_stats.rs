@@ -0,0 +1,89 @@
+use std::collections::{BTreeMap, HashMap};
+
+// The outcome of one fetch, counted in `fetch_stats` for the crawl report.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FetchStat {
+    pub host: String,
+    pub depth: f64,
+    pub duration_ms: u64,
+    // HTTP status, also set for HTTP errors.
+    pub status: Option<u16>,
+    pub content_type: String,
+    pub redirected: bool,
+    // `error_kind` of a failed fetch.
+    pub error: Option<String>,
+}
+
+impl FetchStat {
+    // `fetch_stats` fields incremented for this fetch.
+    pub fn counters(&self) -> Vec<String> {
+        let mut counters = vec!["fetches".to_string()];
+
+        if let Some(status) = self.status {
+            counters.push(format!("status:{}", status));
+        }
+        match &self.error {
+            Some(kind) => counters.push(format!("error:{}", kind)),
+            None => {
+                counters.push(format!("host:{}", self.host));
+                counters.push(format!("depth:{}", self.depth.max(0.0).round() as i64));
+                if !self.content_type.is_empty() {
+                    counters.push(format!("content_type:{}", self.content_type));
+                }
+            }
+        }
+        if self.redirected {
+            counters.push("redirects".to_string());
+        }
+
+        counters
+    }
+}
+
+// `fetch_stats` read back and split by counter kind.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FetchStats {
+    pub fetches: u64,
+    pub redirects: u64,
+    pub pages_per_host: BTreeMap<String, u64>,
+    pub status_codes: BTreeMap<u16, u64>,
+    pub content_types: BTreeMap<String, u64>,
+    pub depths: BTreeMap<i64, u64>,
+    pub errors: BTreeMap<String, u64>,
+}
+
+impl FetchStats {
+    pub fn from_counters(counters: &HashMap<String, u64>) -> Self {
+        let mut stats = Self::default();
+
+        for (field, &count) in counters {
+            let (kind, value) = field.split_once(':').unwrap_or((field.as_str(), ""));
+            match kind {
+                "fetches" => stats.fetches = count,
+                "redirects" => stats.redirects = count,
+                "host" => {
+                    stats.pages_per_host.insert(value.to_string(), count);
+                }
+                "status" => {
+                    if let Ok(status) = value.parse() {
+                        stats.status_codes.insert(status, count);
+                    }
+                }
+                "content_type" => {
+                    stats.content_types.insert(value.to_string(), count);
+                }
+                "depth" => {
+                    if let Ok(depth) = value.parse() {
+                        stats.depths.insert(depth, count);
+                    }
+                }
+                "error" => {
+                    stats.errors.insert(value.to_string(), count);
+                }
+                _ => {}
+            }
+        }
+
+        stats
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+// How the frontier hands out URLs. Every host has its own queue and a slot in the
+// ready-hosts schedule, keyed by the earliest time it may be fetched again. A host's weight
+// divides its delay, so a weight of 2 is visited twice as often; a host stops being
+// dispatched once it has been given its page budget (0 = unlimited).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrontierPolicy {
+    pub host_delay: Duration,
+    pub host_weights: HashMap<String, f64>,
+    pub host_max_pages: usize,
+    pub host_page_budgets: HashMap<String, usize>,
+}
+
+impl Default for FrontierPolicy {
+    fn default() -> Self {
+        Self {
+            host_delay: Duration::from_millis(1000),
+            host_weights: HashMap::new(),
+            host_max_pages: 0,
+            host_page_budgets: HashMap::new(),
+        }
+    }
+}
+
+impl FrontierPolicy {
+    pub fn delay_for(&self, host: &str) -> Duration {
+        match self.host_weights.get(host) {
+            Some(weight) => self.host_delay.div_f64(*weight),
+            None => self.host_delay,
+        }
+    }
+
+    pub fn page_budget_for(&self, host: &str) -> usize {
+        self.host_page_budgets.get(host).copied().unwrap_or(self.host_max_pages)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PoppedUrl {
+    Ready {
+        raw_url: String,
+        // Queue priority; lower is fetched sooner.
+        score: f64,
+        // Links followed from a seed, or 0 if unknown.
+        depth: f64,
+        normalized_url: String,
+    },
+    // URLs are queued, but no host may be fetched for this long.
+    Wait(Duration),
+    Empty,
+}
+
+// The host part of a normalized URL ("example.com/a/b" -> "example.com").
+pub fn host_of(normalized_url: &str) -> &str {
+    normalized_url.split('/').next().unwrap_or(normalized_url)
+}
+
+// Parses `host=value` pairs separated by `;`. Hosts are normalized like URLs (lowercase,
+// without `www.`); a `*` host sets the default, returned separately.
+pub fn parse_host_values<T: std::str::FromStr>(value: &str) -> Result<(Option<T>, HashMap<String, T>), String> {
+    let mut default = None;
+    let mut values = HashMap::new();
+
+    for pair in value.split(';').map(str::trim).filter(|pair| !pair.is_empty()) {
+        let (host, value) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid host setting '{}': expected host=value", pair))?;
+        let value = value
+            .trim()
+            .parse::<T>()
+            .map_err(|_| format!("Invalid host setting '{}': bad value", pair))?;
+
+        let host = host.trim().to_ascii_lowercase();
+        match host.as_str() {
+            "" => return Err(format!("Invalid host setting '{}': missing host", pair)),
+            "*" => default = Some(value),
+            _ => {
+                values.insert(host.trim_start_matches("www.").to_string(), value);
+            }
+        }
+    }
+
+    Ok((default, values))
+}
+
+// Popping takes two scripts so that each declares every key it touches: the first finds a
+// host whose next fetch time has passed, the second pops that host's best URL and pushes
+// its next fetch time back by its delay. Hosts with an empty queue are dropped once their
+// delay is over; pushing a new URL reschedules them. A host past its budget is reported
+// instead of popped so its queue can be dropped with `DROP_HOST_SCRIPT`.
+//
+// KEYS: ready hosts
+// ARGV: now (ms)
+pub(crate) const PICK_HOST_SCRIPT: &str = r#"
+local now = tonumber(ARGV[1])
+local ready = redis.call('ZRANGEBYSCORE', KEYS[1], '-inf', now, 'LIMIT', 0, 1)
+if #ready == 0 then
+    local next = redis.call('ZRANGE', KEYS[1], 0, 0, 'WITHSCORES')
+    if #next == 0 then
+        return {'empty'}
+    end
+    return {'wait', tostring(tonumber(next[2]) - now)}
+end
+return {'host', ready[1]}
+"#;
+
+// Replies `retry` when another worker took the host's turn or its queue ran out.
+//
+// KEYS: ready hosts, global queue index, host delays, host budgets, host dispatch counts, host queue, URL depths
+// ARGV: now (ms), default delay (ms), default budget, host
+pub(crate) const POP_HOST_SCRIPT: &str = r#"
+local now = tonumber(ARGV[1])
+local host = ARGV[4]
+local ready_at = redis.call('ZSCORE', KEYS[1], host)
+if not ready_at or tonumber(ready_at) > now then
+    return {'retry'}
+end
+
+local budget = tonumber(redis.call('HGET', KEYS[4], host) or ARGV[3])
+local dispatched = tonumber(redis.call('HGET', KEYS[5], host) or '0')
+if budget > 0 and dispatched >= budget then
+    return {'exhausted'}
+end
+
+local popped = redis.call('ZPOPMIN', KEYS[6])
+if #popped == 0 then
+    redis.call('ZREM', KEYS[1], host)
+    return {'retry'}
+end
+
+local delay = tonumber(redis.call('HGET', KEYS[3], host) or ARGV[2])
+redis.call('ZADD', KEYS[1], now + delay, host)
+redis.call('HINCRBY', KEYS[5], host, 1)
+redis.call('ZREM', KEYS[2], popped[1])
+return {'url', popped[1], popped[2], redis.call('HGET', KEYS[7], popped[1]) or ''}
+"#;
+
+// Unschedules a host and drops its queued URLs. Returns how many were dropped.
+//
+// KEYS: ready hosts, global queue index, host queue, URL depths
+// ARGV: host
+pub(crate) const DROP_HOST_SCRIPT: &str = r#"
+redis.call('ZREM', KEYS[1], ARGV[1])
+local urls = redis.call('ZRANGE', KEYS[3], 0, -1)
+for i = 1, #urls, 1000 do
+    redis.call('ZREM', KEYS[2], unpack(urls, i, math.min(i + 999, #urls)))
+    redis.call('HDEL', KEYS[4], unpack(urls, i, math.min(i + 999, #urls)))
+end
+redis.call('DEL', KEYS[3])
+return #urls
+"#;
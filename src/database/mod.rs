@@ -1,2 +1,7 @@
+pub mod fetch_stats;
+pub mod frontier;
 pub mod redis_client;
+pub mod status;
+pub use fetch_stats::{FetchStat, FetchStats};
 pub use redis_client::Database;
+pub use status::CrawlStatus;
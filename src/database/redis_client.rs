@@ -1,10 +1,86 @@
 use anyhow::{anyhow, Result};
 use redis::{AsyncCommands, Client};
 use redis::aio::MultiplexedConnection;
+use tracing::{debug, info};
+use super::fetch_stats::{FetchStat, FetchStats};
+use super::frontier::{host_of, FrontierPolicy, PoppedUrl, DROP_HOST_SCRIPT, PICK_HOST_SCRIPT, POP_HOST_SCRIPT};
+use super::status::CrawlStatus;
+use crate::crawler::budget::{registrable_domain, BudgetUsage, HostUsage};
+use crate::pages::{FailedUrl, ImageReference};
+use crate::utils::{
+    BACKLINKS_PREFIX, BACKLINK_EDGES_PREFIX, CRAWL_REPORT_KEY, CRAWL_STATS_KEY, DOMAIN_BYTES_KEY,
+    DOMAIN_PAGES_KEY, FAILED_URLS_KEY, FEED_ITEMS_PREFIX, FETCH_ATTEMPTS_KEY, FETCH_LATENCIES_KEY,
+    FETCH_STATS_KEY, FEED_PREFIX, FEED_SCHEDULE_KEY,
+    HOST_BUDGETS_KEY, HOST_BYTES_KEY, HOST_DELAYS_KEY, HOST_DISPATCHED_KEY, HOST_QUEUE_PREFIX,
+    IMAGE_LRU_KEY, IMAGE_PAGES_PREFIX, IMAGE_PREFIX, IMAGE_REFS_PREFIX, INDEXER_QUEUE_KEY, MAX_LATENCY_SAMPLES,
+    OUTLINKS_PREFIX, OUTLINK_EDGES_PREFIX, PAGE_COUNT_KEY, PAGE_IMAGES_PREFIX, PAGE_METADATA_PREFIX, PAGE_PREFIX,
+    READY_HOSTS_KEY, SIGNAL_QUEUE_KEY, SITEMAP_ORPHANS_KEY, SITEMAP_URLS_KEY, SPIDER_QUEUE_KEY,
+    STRUCTURED_DATA_PREFIX, URL_DEPTHS_KEY, VISITED_PREFIX, normalize_url, strip_url,
+};
+
+// Keys holding the state of a crawl: the frontier, visited markers, budgets and failures.
+// `true` marks a prefix whose `prefix:*` keys are matched.
+const CRAWL_STATE_KEYS: [(&str, bool); 18] = [
+    (SPIDER_QUEUE_KEY, false),
+    (URL_DEPTHS_KEY, false),
+    (HOST_QUEUE_PREFIX, true),
+    (READY_HOSTS_KEY, false),
+    (HOST_DELAYS_KEY, false),
+    (HOST_BUDGETS_KEY, false),
+    (HOST_DISPATCHED_KEY, false),
+    (CRAWL_STATS_KEY, false),
+    (CRAWL_REPORT_KEY, false),
+    (HOST_BYTES_KEY, false),
+    (DOMAIN_PAGES_KEY, false),
+    (DOMAIN_BYTES_KEY, false),
+    (VISITED_PREFIX, true),
+    (FAILED_URLS_KEY, false),
+    (FETCH_ATTEMPTS_KEY, false),
+    (FETCH_STATS_KEY, false),
+    (FETCH_LATENCIES_KEY, false),
+    (SIGNAL_QUEUE_KEY, false),
+];
+
+// Keys holding what the crawl extracted.
+const CRAWL_DATA_KEYS: [(&str, bool); 19] = [
+    (INDEXER_QUEUE_KEY, false),
+    (PAGE_PREFIX, true),
+    (PAGE_COUNT_KEY, false),
+    (PAGE_METADATA_PREFIX, true),
+    (STRUCTURED_DATA_PREFIX, true),
+    (IMAGE_PREFIX, true),
+    (PAGE_IMAGES_PREFIX, true),
+    (IMAGE_PAGES_PREFIX, true),
+    (IMAGE_REFS_PREFIX, true),
+    (IMAGE_LRU_KEY, false),
+    (BACKLINKS_PREFIX, true),
+    (OUTLINKS_PREFIX, true),
+    (BACKLINK_EDGES_PREFIX, true),
+    (OUTLINK_EDGES_PREFIX, true),
+    (SITEMAP_URLS_KEY, false),
+    (SITEMAP_ORPHANS_KEY, false),
+    (FEED_PREFIX, true),
+    (FEED_ITEMS_PREFIX, true),
+    (FEED_SCHEDULE_KEY, false),
+];
+
+// Sets a visited marker, counting it in `crawl_stats` the first time, and forgets the
+// URL's link depth.
+//
+// KEYS: visited marker, crawl stats, URL depths
+// ARGV: normalized URL
+const VISIT_PAGE_SCRIPT: &str = r#"
+if redis.call('SET', KEYS[1], '1', 'NX') then
+    redis.call('HINCRBY', KEYS[2], 'visited', 1)
+end
+redis.call('HDEL', KEYS[3], ARGV[1])
+return 0
+"#;
 
 pub struct Database {
     conn: MultiplexedConnection,
     pub client: Client,
+    frontier: FrontierPolicy,
 }
 
 impl Database {
@@ -27,9 +103,10 @@ impl Database {
             std::time::Duration::from_secs(5),
             client.get_async_connection()
         ).await.map_err(|_| anyhow!("Redis test connection timeout"))?.map_err(|e| anyhow!("Redis connection test failed: {}", e))?;
-        Ok(Self { conn: mgr, client: client })
+        Ok(Self { conn: mgr, client, frontier: FrontierPolicy::default() })
     }
 
+    // Puts a URL back in the queue at `score`, keeping the link depth recorded by `push_urls`.
     pub async fn push_url(
         &mut self,
         raw_url: &str,
@@ -39,11 +116,61 @@ impl Database {
         
         let raw = strip_url(raw_url).map_err(|e| anyhow!("Strip URL error: {}", e))?;
         let normalized = normalize_url(&raw).map_err(|e| anyhow!("Normalize URL error: {}", e))?;
-        let _: () = self.conn.zadd(SPIDER_QUEUE_KEY, normalized, score).await?;
-        println!("Pushed {} to queue", raw_url);
+
+        // `spider_queue` indexes every queued URL; dispatch goes through the per-host queues.
+        let now = chrono::Utc::now().timestamp_millis();
+        let _: () = redis::pipe()
+            .atomic()
+            .zadd(SPIDER_QUEUE_KEY, &normalized, score)
+            .zadd(format!("{}:{}", HOST_QUEUE_PREFIX, host_of(&normalized)), &normalized, score)
+            .cmd("ZADD").arg(READY_HOSTS_KEY).arg("NX").arg(now).arg(host_of(&normalized))
+            .query_async(&mut self.conn)
+            .await?;
+        debug!("Pushed {} to queue", raw_url);
         Ok(())
     }
 
+    // Pushes a batch of URLs found `depth` links away from a seed, in two round trips. A URL
+    // already queued keeps whichever score and depth are lower. Returns the normalized URLs
+    // that were queued.
+    pub async fn push_urls(&mut self, urls: &[(String, f64)], depth: f64) -> Result<Vec<String>> {
+
+        let urls: Vec<(String, f64)> = urls
+            .iter()
+            .filter_map(|(raw_url, score)| {
+                let raw = strip_url(raw_url).ok()?;
+                Some((normalize_url(&raw).ok()?, *score))
+            })
+            .collect();
+
+        let mut queued = Vec::with_capacity(urls.len());
+        for chunk in urls.chunks(500) {
+            let mut pipe = redis::pipe();
+            for (normalized, _) in chunk {
+                pipe.zscore(SPIDER_QUEUE_KEY, normalized).hget(URL_DEPTHS_KEY, normalized);
+            }
+            let existing: Vec<(Option<f64>, Option<f64>)> = pipe.query_async(&mut self.conn).await?;
+
+            let now = chrono::Utc::now().timestamp_millis();
+            let mut pipe = redis::pipe();
+            pipe.atomic();
+            for ((normalized, score), (existing_score, existing_depth)) in chunk.iter().zip(existing) {
+                let score = existing_score.map_or(*score, |existing| existing.min(*score));
+                let depth = existing_depth.map_or(depth, |existing| existing.min(depth));
+                pipe.hset(URL_DEPTHS_KEY, normalized, depth).ignore()
+                    .zadd(SPIDER_QUEUE_KEY, normalized, score).ignore()
+                    .zadd(format!("{}:{}", HOST_QUEUE_PREFIX, host_of(normalized)), normalized, score).ignore()
+                    .cmd("ZADD").arg(READY_HOSTS_KEY).arg("NX").arg(now).arg(host_of(normalized)).ignore();
+            }
+            let _: () = pipe.query_async(&mut self.conn).await?;
+
+            queued.extend(chunk.iter().map(|(normalized, _)| normalized.clone()));
+        }
+
+        debug!("Pushed {} URLs to queue", queued.len());
+        Ok(queued)
+    }
+
     pub async fn exists_in_queue(&mut self, raw_url: &str) -> Result<Option<f64>> {
         use crate::utils::normalize_url;
         let normalized = match normalize_url(raw_url) {
@@ -54,25 +181,124 @@ impl Database {
         Ok(score)
     }
 
-    pub async fn pop_url(&mut self) -> Result<(String, f64, String)> {
-        use crate::utils::{SPIDER_QUEUE_KEY};
-        let values: Vec<(String, f64)> = self
-            .conn
-            .zpopmin(SPIDER_QUEUE_KEY, 1)
+    // Whether each URL was visited, and its queue score, in one round trip.
+    pub async fn get_link_states(&mut self, raw_urls: &[String]) -> Result<Vec<(bool, Option<f64>)>> {
+
+        let mut pipe = redis::pipe();
+        for raw_url in raw_urls {
+            let normalized = normalize_url(raw_url).unwrap_or_else(|_| raw_url.clone());
+            pipe.exists(format!("{}:{}", VISITED_PREFIX, normalized))
+                .zscore(SPIDER_QUEUE_KEY, normalized);
+        }
+
+        let replies: Vec<(bool, Option<f64>)> = pipe.query_async(&mut self.conn).await?;
+        Ok(replies)
+    }
+
+    // Stores per-host delays and budgets where the pop script can read them.
+    pub async fn configure_frontier(&mut self, frontier: FrontierPolicy) -> Result<()> {
+
+        let mut pipe = redis::pipe();
+        pipe.atomic().del(HOST_DELAYS_KEY).del(HOST_BUDGETS_KEY);
+
+        for host in frontier.host_weights.keys() {
+            pipe.hset(HOST_DELAYS_KEY, host, frontier.delay_for(host).as_millis() as u64);
+        }
+        for host in frontier.host_page_budgets.keys() {
+            pipe.hset(HOST_BUDGETS_KEY, host, frontier.page_budget_for(host));
+        }
+
+        let _: () = pipe.query_async(&mut self.conn).await?;
+        self.frontier = frontier;
+        Ok(())
+    }
+
+    pub fn frontier(&self) -> &FrontierPolicy {
+        &self.frontier
+    }
+
+    pub async fn pop_url(&mut self) -> Result<PoppedUrl> {
+
+        for _ in 0..64 {
+            let now = chrono::Utc::now().timestamp_millis();
+            let reply: Vec<String> = redis::Script::new(PICK_HOST_SCRIPT)
+                .key(READY_HOSTS_KEY)
+                .arg(now)
+                .invoke_async(&mut self.conn)
+                .await
+                .map_err(|e| anyhow!("Pick host script failed: {}", e))?;
+
+            let host = match reply.as_slice() {
+                [kind, host] if kind == "host" => host.clone(),
+                [kind, wait] if kind == "wait" => {
+                    let wait = wait.parse::<i64>().unwrap_or(0).max(0) as u64;
+                    return Ok(PoppedUrl::Wait(std::time::Duration::from_millis(wait)));
+                }
+                [kind] if kind == "empty" => return Ok(PoppedUrl::Empty),
+                other => return Err(anyhow!("Unexpected pick host reply: {:?}", other)),
+            };
+
+            let reply: Vec<String> = redis::Script::new(POP_HOST_SCRIPT)
+                .key(READY_HOSTS_KEY)
+                .key(SPIDER_QUEUE_KEY)
+                .key(HOST_DELAYS_KEY)
+                .key(HOST_BUDGETS_KEY)
+                .key(HOST_DISPATCHED_KEY)
+                .key(format!("{}:{}", HOST_QUEUE_PREFIX, host))
+                .key(URL_DEPTHS_KEY)
+                .arg(now)
+                .arg(self.frontier.host_delay.as_millis() as u64)
+                .arg(self.frontier.host_max_pages)
+                .arg(&host)
+                .invoke_async(&mut self.conn)
+                .await
+                .map_err(|e| anyhow!("Pop script failed: {}", e))?;
+
+            match reply.as_slice() {
+                [kind, member, score, depth] if kind == "url" => {
+                    return Ok(PoppedUrl::Ready {
+                        raw_url: format!("https://{}", member),
+                        score: score.parse().unwrap_or(0.0),
+                        depth: depth.parse().unwrap_or(0.0),
+                        normalized_url: member.clone(),
+                    });
+                }
+                [kind] if kind == "exhausted" => {
+                    let dropped = self.drop_host(&host).await?;
+                    info!("Page budget for host {} reached, dropped {} queued URLs", host, dropped);
+                }
+                [kind] if kind == "retry" => {}
+                other => return Err(anyhow!("Unexpected pop reply: {:?}", other)),
+            }
+        }
+
+        Ok(PoppedUrl::Wait(std::time::Duration::ZERO))
+    }
+
+    // Stops dispatching `host` and drops its queued URLs. Returns how many were dropped.
+    pub async fn drop_host(&mut self, host: &str) -> Result<u64> {
+
+        let dropped: u64 = redis::Script::new(DROP_HOST_SCRIPT)
+            .key(READY_HOSTS_KEY)
+            .key(SPIDER_QUEUE_KEY)
+            .key(format!("{}:{}", HOST_QUEUE_PREFIX, host))
+            .key(URL_DEPTHS_KEY)
+            .arg(host)
+            .invoke_async(&mut self.conn)
             .await
-            .map_err(|e| anyhow!("ZPopMin failed: {}", e))?;
-        
-        let (member, score) = values.into_iter().next()
-            .ok_or_else(|| anyhow!("No URLs in queue"))?;
-        let raw = format!("https://{}", member);
-        Ok((raw, score, member))
+            .map_err(|e| anyhow!("Drop host script failed: {}", e))?;
+        Ok(dropped)
+    }
+
+    // Takes every pending control signal without blocking.
+    pub async fn pop_signals(&mut self) -> Result<Vec<String>> {
+        self.drain_list(crate::utils::SIGNAL_QUEUE_KEY, crate::utils::MAX_SIGNALS_PER_DRAIN).await
     }
 
-    pub async fn pop_signal(&mut self) -> Result<String> {
-        let mut arr: Vec<String> = self.conn.brpop(crate::utils::SIGNAL_QUEUE_KEY, 0).await?;
-        arr
-            .pop()
-            .ok_or_else(|| anyhow!("BRPop returned empty"))
+    // URLs waiting to be crawled, whether or not their host is due.
+    pub async fn get_queue_size(&mut self) -> Result<u64> {
+        let size = self.conn.zcard(crate::utils::SPIDER_QUEUE_KEY).await?;
+        Ok(size)
     }
 
     pub async fn get_indexer_queue_size(&mut self) -> Result<i64> {
@@ -81,12 +307,319 @@ impl Database {
     }
 
     pub async fn visit_page(&mut self, url: &str) -> Result<()> {
-        let _: () = self.conn.set(format!("visited:{}", url), "1").await?;
+        let _: () = redis::Script::new(VISIT_PAGE_SCRIPT)
+            .key(format!("{}:{}", VISITED_PREFIX, url))
+            .key(CRAWL_STATS_KEY)
+            .key(URL_DEPTHS_KEY)
+            .arg(url)
+            .invoke_async(&mut self.conn)
+            .await?;
         Ok(())
     }
 
     pub async fn has_url_been_visited(&mut self, url: &str) -> Result<bool> {
-        let exists: bool = self.conn.exists(format!("visited:{}", url)).await?;
+        let exists: bool = self.conn.exists(format!("{}:{}", crate::utils::VISITED_PREFIX, url)).await?;
         Ok(exists)
     }
+
+    pub async fn has_image_data(&mut self, normalized_source_url: &str) -> Result<bool> {
+        let key = format!("{}:{}", crate::utils::IMAGE_PREFIX, normalized_source_url);
+        let exists: bool = self.conn.hexists(key, "byte_size").await?;
+        Ok(exists)
+    }
+
+    // Atomically takes up to `max` entries from the head of a list.
+    pub async fn drain_list(&mut self, key: &str, max: usize) -> Result<Vec<String>> {
+        let (entries,): (Vec<String>,) = redis::pipe()
+            .atomic()
+            .lrange(key, 0, max as isize - 1)
+            .ltrim(key, max as isize, -1)
+            .ignore()
+            .query_async(&mut self.conn)
+            .await?;
+        Ok(entries)
+    }
+
+    // Starts the wall-clock budget unless a previous run already did.
+    pub async fn start_crawl_clock(&mut self) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        let _: () = self.conn.hset_nx(crate::utils::CRAWL_STATS_KEY, "started_at", now).await?;
+        Ok(())
+    }
+
+    pub async fn record_fetch(&mut self, host: &str, bytes: u64) -> Result<()> {
+
+        let domain = registrable_domain(host);
+        let _: () = redis::pipe()
+            .hincr(CRAWL_STATS_KEY, "pages", 1).ignore()
+            .hincr(CRAWL_STATS_KEY, "bytes", bytes).ignore()
+            .hincr(HOST_BYTES_KEY, host, bytes).ignore()
+            .hincr(DOMAIN_PAGES_KEY, domain, 1).ignore()
+            .hincr(DOMAIN_BYTES_KEY, domain, bytes).ignore()
+            .query_async(&mut self.conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_budget_usage(&mut self) -> Result<BudgetUsage> {
+        let (pages, bytes, started_at): (Option<u64>, Option<u64>, Option<i64>) = self
+            .conn
+            .hget(crate::utils::CRAWL_STATS_KEY, &["pages", "bytes", "started_at"])
+            .await?;
+
+        let elapsed = started_at
+            .map(|started_at| (chrono::Utc::now().timestamp() - started_at).max(0) as u64)
+            .unwrap_or(0);
+
+        Ok(BudgetUsage {
+            pages: pages.unwrap_or(0),
+            bytes: bytes.unwrap_or(0),
+            elapsed: std::time::Duration::from_secs(elapsed),
+        })
+    }
+
+    // The crawl's budget usage and that of `host` and its domain, in one round trip.
+    pub async fn get_usage(&mut self, host: &str) -> Result<(BudgetUsage, HostUsage)> {
+
+        let domain = registrable_domain(host);
+        #[allow(clippy::type_complexity)]
+        let ((pages, bytes, started_at), host_bytes, domain_pages, domain_bytes): (
+            (Option<u64>, Option<u64>, Option<i64>),
+            Option<u64>,
+            Option<u64>,
+            Option<u64>,
+        ) = redis::pipe()
+            .hget(CRAWL_STATS_KEY, &["pages", "bytes", "started_at"])
+            .hget(HOST_BYTES_KEY, host)
+            .hget(DOMAIN_PAGES_KEY, domain)
+            .hget(DOMAIN_BYTES_KEY, domain)
+            .query_async(&mut self.conn)
+            .await?;
+
+        let elapsed = started_at
+            .map(|started_at| (chrono::Utc::now().timestamp() - started_at).max(0) as u64)
+            .unwrap_or(0);
+
+        let usage = BudgetUsage {
+            pages: pages.unwrap_or(0),
+            bytes: bytes.unwrap_or(0),
+            elapsed: std::time::Duration::from_secs(elapsed),
+        };
+        let host_usage = HostUsage {
+            host_bytes: host_bytes.unwrap_or(0),
+            domain_pages: domain_pages.unwrap_or(0),
+            domain_bytes: domain_bytes.unwrap_or(0),
+        };
+        Ok((usage, host_usage))
+    }
+
+    pub async fn save_crawl_report(&mut self, usage: &BudgetUsage, reason: &str) -> Result<()> {
+        let report = [
+            ("reason", reason.to_string()),
+            ("pages", usage.pages.to_string()),
+            ("bytes", usage.bytes.to_string()),
+            ("duration_secs", usage.elapsed.as_secs().to_string()),
+            ("finished_at", chrono::Utc::now().to_rfc2822()),
+        ];
+        let _: () = self.conn.hset_multiple(crate::utils::CRAWL_REPORT_KEY, &report).await?;
+        Ok(())
+    }
+
+    // Counts a fetch of `normalized_url` and returns which attempt this is, starting at 1.
+    pub async fn record_attempt(&mut self, normalized_url: &str) -> Result<u64> {
+        let attempt: u64 = self.conn.hincr(FETCH_ATTEMPTS_KEY, normalized_url, 1).await?;
+        Ok(attempt)
+    }
+
+    // Counts the fetch in `fetch_stats` and keeps its latency among the most recent samples.
+    pub async fn record_fetch_stats(&mut self, stat: &FetchStat) -> Result<()> {
+        let mut pipe = redis::pipe();
+        for counter in stat.counters() {
+            pipe.hincr(FETCH_STATS_KEY, counter, 1).ignore();
+        }
+        pipe.lpush(FETCH_LATENCIES_KEY, stat.duration_ms).ignore()
+            .ltrim(FETCH_LATENCIES_KEY, 0, MAX_LATENCY_SAMPLES as isize - 1).ignore();
+
+        let _: () = pipe.query_async(&mut self.conn).await?;
+        Ok(())
+    }
+
+    pub async fn get_fetch_stats(&mut self) -> Result<FetchStats> {
+        let counters: std::collections::HashMap<String, u64> = self.conn.hgetall(FETCH_STATS_KEY).await?;
+        Ok(FetchStats::from_counters(&counters))
+    }
+
+    pub async fn get_fetch_latencies(&mut self) -> Result<Vec<u64>> {
+        let latencies: Vec<u64> = self.conn.lrange(FETCH_LATENCIES_KEY, 0, -1).await?;
+        Ok(latencies)
+    }
+
+    // Pages with the most backlinks, most linked first.
+    pub async fn get_most_linked(&mut self, limit: usize) -> Result<Vec<(String, u64)>> {
+        let keys = self.scan_keys(&format!("{}:*", BACKLINKS_PREFIX)).await?;
+
+        let mut counts = Vec::with_capacity(keys.len());
+        for chunk in keys.chunks(500) {
+            let mut pipe = redis::pipe();
+            for key in chunk {
+                pipe.scard(key);
+            }
+            let sizes: Vec<u64> = pipe.query_async(&mut self.conn).await?;
+
+            for (key, size) in chunk.iter().zip(sizes) {
+                let url = key.strip_prefix(&format!("{}:", BACKLINKS_PREFIX)).unwrap_or(key);
+                counts.push((url.to_string(), size));
+            }
+        }
+
+        Ok(top_counts(counts, limit))
+    }
+
+    // Pages referencing stored images without alt text, with the number of such images.
+    pub async fn get_missing_alt(&mut self, limit: usize) -> Result<Vec<(String, u64)>> {
+        let keys = self.scan_keys(&format!("{}:*", IMAGE_PREFIX)).await?;
+
+        let prefix = format!("{}:", IMAGE_PREFIX);
+        let mut pages: std::collections::HashMap<String, u64> = std::collections::HashMap::new();
+        for chunk in keys.chunks(500) {
+            let mut pipe = redis::pipe();
+            for key in chunk {
+                let source = key.strip_prefix(&prefix).unwrap_or(key);
+                pipe.hgetall(format!("{}:{}", IMAGE_REFS_PREFIX, source));
+            }
+            let references: Vec<std::collections::HashMap<String, String>> = pipe.query_async(&mut self.conn).await?;
+
+            for (page, reference) in references.into_iter().flatten() {
+                if let Ok(reference) = ImageReference::from_json(&reference)
+                    && reference.alt.trim().is_empty()
+                {
+                    *pages.entry(page).or_default() += 1;
+                }
+            }
+        }
+
+        Ok(top_counts(pages.into_iter().collect(), limit))
+    }
+
+    // Remembers a failed fetch so `requeue_failed` can retry it.
+    pub async fn record_failure(&mut self, failure: &FailedUrl) -> Result<()> {
+        let _: () = self
+            .conn
+            .hset(FAILED_URLS_KEY, &failure.normalized_url, failure.to_json())
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_failures(&mut self) -> Result<Vec<FailedUrl>> {
+        let entries: Vec<String> = self.conn.hvals(FAILED_URLS_KEY).await?;
+        Ok(entries.iter().filter_map(|entry| FailedUrl::from_json(entry).ok()).collect())
+    }
+
+    pub async fn get_failure(&mut self, normalized_url: &str) -> Result<Option<FailedUrl>> {
+        let entry: Option<String> = self.conn.hget(FAILED_URLS_KEY, normalized_url).await?;
+        Ok(entry.and_then(|entry| FailedUrl::from_json(&entry).ok()))
+    }
+
+    // Pushes every recorded failure back onto the queue at its old score and forgets it.
+    // URLs visited since they failed are dropped without being requeued.
+    pub async fn requeue_failed(&mut self) -> Result<usize> {
+        let mut requeued = 0;
+
+        for failure in self.get_failures().await? {
+            if !self.has_url_been_visited(&failure.normalized_url).await? {
+                self.push_url(&failure.raw_url, failure.score).await?;
+                requeued += 1;
+            }
+            let _: () = self.conn.hdel(FAILED_URLS_KEY, &failure.normalized_url).await?;
+        }
+
+        Ok(requeued)
+    }
+
+    // Visited and stored pages come from the counters kept by `visit_page` and `save_pages`,
+    // so this is a single round trip however large the crawl is.
+    pub async fn get_status(&mut self) -> Result<CrawlStatus> {
+        #[allow(clippy::type_complexity)]
+        let (queued_urls, ready_hosts, indexer_queue, pending_signals, failed, stop_reason, (visited, pages, bytes, started_at), pages_stored): (
+            u64,
+            u64,
+            u64,
+            u64,
+            u64,
+            Option<String>,
+            (Option<u64>, Option<u64>, Option<u64>, Option<i64>),
+            Option<u64>,
+        ) = redis::pipe()
+            .zcard(SPIDER_QUEUE_KEY)
+            .zcard(READY_HOSTS_KEY)
+            .llen(INDEXER_QUEUE_KEY)
+            .llen(SIGNAL_QUEUE_KEY)
+            .hlen(FAILED_URLS_KEY)
+            .hget(CRAWL_REPORT_KEY, "reason")
+            .hget(CRAWL_STATS_KEY, &["visited", "pages", "bytes", "started_at"])
+            .get(PAGE_COUNT_KEY)
+            .query_async(&mut self.conn)
+            .await?;
+
+        let elapsed = started_at
+            .map(|started_at| (chrono::Utc::now().timestamp() - started_at).max(0) as u64)
+            .unwrap_or(0);
+
+        Ok(CrawlStatus {
+            queued_urls,
+            ready_hosts,
+            indexer_queue,
+            pending_signals,
+            visited: visited.unwrap_or(0),
+            pages_stored: pages_stored.unwrap_or(0),
+            failed,
+            usage: BudgetUsage {
+                pages: pages.unwrap_or(0),
+                bytes: bytes.unwrap_or(0),
+                elapsed: std::time::Duration::from_secs(elapsed),
+            },
+            stop_reason,
+        })
+    }
+
+    // Deletes the crawl state so the next run starts from scratch; `include_data` also
+    // deletes stored pages, links, images, feeds and sitemaps. Returns the number of keys removed.
+    pub async fn purge(&mut self, include_data: bool) -> Result<u64> {
+        let mut targets: Vec<(&str, bool)> = CRAWL_STATE_KEYS.to_vec();
+        if include_data {
+            targets.extend(CRAWL_DATA_KEYS);
+        }
+
+        let mut deleted = 0;
+        for (key, is_prefix) in targets {
+            let keys = if is_prefix {
+                self.scan_keys(&format!("{}:*", key)).await?
+            } else {
+                vec![key.to_string()]
+            };
+
+            for chunk in keys.chunks(500) {
+                let removed: u64 = self.conn.del(chunk).await?;
+                deleted += removed;
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    async fn scan_keys(&mut self, pattern: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut iter: redis::AsyncIter<String> = self.conn.scan_match(pattern).await?;
+        while let Some(key) = iter.next_item().await {
+            keys.push(key);
+        }
+        Ok(keys)
+    }
+}
+
+// The `limit` largest counts, largest first, ties by name.
+fn top_counts(mut counts: Vec<(String, u64)>, limit: usize) -> Vec<(String, u64)> {
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(limit);
+    counts
 }
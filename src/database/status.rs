@@ -0,0 +1,39 @@
+use std::fmt;
+use crate::crawler::budget::BudgetUsage;
+
+// A snapshot of the crawl as stored in Redis, for `spider status`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CrawlStatus {
+    pub queued_urls: u64,
+    pub ready_hosts: u64,
+    pub indexer_queue: u64,
+    pub pending_signals: u64,
+    pub visited: u64,
+    pub pages_stored: u64,
+    pub failed: u64,
+    pub usage: BudgetUsage,
+    // Why the last run stopped, if it ran out of budget.
+    pub stop_reason: Option<String>,
+}
+
+impl fmt::Display for CrawlStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Queued URLs:      {} ({} hosts)", self.queued_urls, self.ready_hosts)?;
+        writeln!(f, "Indexer queue:    {}", self.indexer_queue)?;
+        writeln!(f, "Pending signals:  {}", self.pending_signals)?;
+        writeln!(f, "Visited URLs:     {}", self.visited)?;
+        writeln!(f, "Pages stored:     {}", self.pages_stored)?;
+        writeln!(f, "Failed URLs:      {}", self.failed)?;
+        writeln!(
+            f,
+            "Fetched:          {} pages, {} bytes in {}s",
+            self.usage.pages,
+            self.usage.bytes,
+            self.usage.elapsed.as_secs()
+        )?;
+        match &self.stop_reason {
+            Some(reason) => write!(f, "Stopped:          {}", reason),
+            None => write!(f, "Stopped:          no"),
+        }
+    }
+}
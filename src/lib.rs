@@ -1,5 +1,14 @@
 pub mod utils;
+pub mod config;
+pub mod logging;
+pub mod metrics;
 pub mod pages;
 pub mod crawler;
 pub mod database;
 pub mod controllers;
+pub mod commands;
+pub mod report;
+pub mod admin;
+pub mod spider;
+
+pub use crate::spider::{CrawlOutcome, Spider, SpiderBuilder, StopReason};
@@ -0,0 +1,96 @@
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Mutex;
+use tracing::Metadata;
+use tracing_subscriber::filter::{filter_fn, EnvFilter, LevelFilter};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt as tracing_fmt, Layer};
+
+// Target of the per-fetch audit events. They go to the audit log file only.
+pub const AUDIT_TARGET: &str = "spider::audit";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    #[default]
+    Full,
+    Pretty,
+    Compact,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "full" => Ok(Self::Full),
+            "pretty" => Ok(Self::Pretty),
+            "compact" => Ok(Self::Compact),
+            "json" => Ok(Self::Json),
+            other => Err(format!("Unknown log format '{}', expected full, pretty, compact or json", other)),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full => write!(f, "full"),
+            Self::Pretty => write!(f, "pretty"),
+            Self::Compact => write!(f, "compact"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+fn is_audit(metadata: &Metadata<'_>) -> bool {
+    metadata.target() == AUDIT_TARGET
+}
+
+// Installs the global subscriber: `format` on stderr filtered by RUST_LOG (default `info`),
+// plus JSON lines of every audit event appended to `audit_log` when given.
+pub fn init_logging(format: LogFormat, audit_log: Option<&Path>) -> Result<(), String> {
+    let env_filter = EnvFilter::builder()
+        .with_default_directive(LevelFilter::INFO.into())
+        .from_env_lossy();
+
+    let console = tracing_fmt::layer().with_writer(std::io::stderr);
+    let console = match format {
+        LogFormat::Full => console.boxed(),
+        LogFormat::Pretty => console.pretty().boxed(),
+        LogFormat::Compact => console.compact().boxed(),
+        LogFormat::Json => console.json().with_current_span(true).with_span_list(true).boxed(),
+    };
+    let console = console.with_filter(env_filter).with_filter(filter_fn(|metadata| !is_audit(metadata)));
+
+    let audit = match audit_log {
+        Some(path) => {
+            let file = open_audit_log(path)?;
+            let layer = tracing_fmt::layer()
+                .json()
+                .with_current_span(false)
+                .with_span_list(false)
+                .with_writer(Mutex::new(file))
+                .with_filter(filter_fn(is_audit));
+            Some(layer)
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(console)
+        .with(audit)
+        .try_init()
+        .map_err(|e| e.to_string())
+}
+
+fn open_audit_log(path: &Path) -> Result<File, String> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Cannot open audit log {}: {}", path.display(), e))
+}
@@ -1,125 +1,185 @@
-use std::env;
-use std::sync::Arc;
-use tokio::sync::{Mutex, Notify};
-use tokio::task;
-use tokio::time::{sleep, Duration};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use clap::{Parser, Subcommand};
 use tracing::{info, error};
 
-mod controllers;
-mod crawler;
-mod database;
-mod pages;
-mod utils;
+use spider::{CrawlOutcome, Spider, StopReason};
+use spider::commands;
+use spider::config::{ConfigArgs, SpiderConfig};
+use spider::logging::{init_logging, LogFormat};
+use spider::report::{self, ReportFormat};
 
-use controllers::page_controller::PageController;
-use controllers::page_node_controller::LinksController;
-use controllers::image_controller::ImageController;
-use crawler::crawler::CrawlerConfig;
+#[derive(Debug, Parser)]
+#[command(name = "spider", about = "Distributed web crawler backed by Redis")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
 
-#[tokio::main]
-async fn main() {
-    tracing_subscriber::fmt::init();
+    #[command(subcommand)]
+    command: Option<Command>,
+}
 
-    fn get_env(key: &str, fallback: &str) -> String {
-        env::var(key).unwrap_or_else(|_| fallback.to_string())
-    }
+#[derive(Debug, Subcommand)]
+enum Command {
+    #[command(about = "Run the crawler (default)")]
+    Crawl,
+    #[command(about = "Queue URLs without crawling them")]
+    Seed {
+        #[arg(required = true, help = "URLs, files, `stdin` or `redis:<list key>`")]
+        sources: Vec<String>,
+    },
+    #[command(about = "Show queue sizes, visited and stored pages, and failures")]
+    Status,
+    #[command(about = "Write stored pages as JSON lines")]
+    Export {
+        #[arg(long, short, help = "File to write to instead of standard output")]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Queue failed URLs again")]
+    RequeueFailed,
+    #[command(about = "Show the stored page, links and images of a URL")]
+    Inspect {
+        url: String,
+        #[arg(long, help = "Include the raw and extracted HTML")]
+        html: bool,
+    },
+    #[command(about = "Delete the crawl state from Redis")]
+    Purge {
+        #[arg(long, help = "Also delete stored pages, links, images, feeds and sitemaps")]
+        all: bool,
+        #[arg(long, help = "Confirm the deletion")]
+        yes: bool,
+    },
+    #[command(about = "Summarize the crawl stored in Redis as JSON or HTML")]
+    Report {
+        #[arg(long, default_value_t = ReportFormat::Json, help = "json or html")]
+        format: ReportFormat,
+        #[arg(long, help = "Write to this file instead of stdout")]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Inspect the configuration")]
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
 
-    let max_concurrency = env::var("MAX_CONCURRENCY")
-        .ok()
-        .and_then(|v| v.parse::<usize>().ok())
-        .unwrap_or(10);
-
-    let max_pages = env::var("MAX_PAGES")
-        .ok()
-        .and_then(|v| v.parse::<usize>().ok())
-        .unwrap_or(100);
-
-    let redis_host = get_env("REDIS_HOST", "localhost");
-    let redis_port = get_env("REDIS_PORT", "6379");
-    let redis_password = get_env("REDIS_PASSWORD", "");
-    let redis_db = get_env("REDIS_DB", "0");
-    let starting_url = get_env("STARTING_URL", "https://starkbak.net");
-
-    let redis_db_num: i64 = redis_db.parse().unwrap_or(0);
-    let db_instance = database::Database::connect(&redis_host, &redis_port, &redis_password, redis_db_num).await;
-    if let Err(e) = db_instance {
-        error!("Error connecting to Redis: {:?}", e);
-        return;
-    }
-    let db = Arc::new(Mutex::new(db_instance.unwrap()));
+#[derive(Debug, Subcommand)]
+enum ConfigCommand {
+    #[command(about = "Print the effective config after merging the file, environment and flags")]
+    Print,
+}
 
-    if let Err(e) = db.lock().await.push_url(&starting_url, 0.0).await {
-        error!("Error pushing starting URL: {:?}", e);
-        return;
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    let config = match SpiderConfig::load(&cli.config) {
+        Ok(config) => config,
+        Err(errors) => {
+            let _ = init_logging(LogFormat::default(), None);
+            for e in errors {
+                error!("Invalid configuration: {}", e);
+            }
+            std::process::exit(2);
+        }
+    };
+
+    // Only a crawl writes to the audit log.
+    let audit_log = match cli.command {
+        None | Some(Command::Crawl) => config.audit_log(),
+        Some(_) => None,
+    };
+    if let Err(e) = init_logging(config.logging.format, audit_log) {
+        eprintln!("Error setting up logging: {}", e);
+        std::process::exit(2);
     }
-    info!("PUSH {}", starting_url);
-
-    let page_controller = PageController::new(db.clone());
-    let links_controller = LinksController::new(db.clone());
-    let image_controller = ImageController::new(db.clone());
-
-    let crawler = Arc::new(Mutex::new(CrawlerConfig::new(max_pages, max_concurrency)));
-
-    loop {
-        info!("Checking number of entries...");
 
-        let queue_size = match db.lock().await.get_indexer_queue_size().await {
-            Ok(size) => size,
-            Err(e) => {
-                error!("Error getting indexer queue: {:?}", e);
-                return;
-            }
-        };
-
-        if queue_size >= utils::MAX_INDEXER_QUEUE_SIZE as i64 {
-            info!("Indexer queue is full. Waiting...");
-
-            loop {
-                match db.lock().await.pop_signal().await {
-                    Ok(sig) if sig == utils::RESUME_CRAWL => {
-                        info!("Resume crawl!");
-                        break;
-                    }
-                    Ok(_) => { }
-                    Err(e) => {
-                        error!("Could not get signal: {:?}", e);
-                        return;
-                    }
-                }
-                sleep(Duration::from_secs(1)).await;
+    let command = match cli.command.unwrap_or(Command::Crawl) {
+        Command::Crawl => return crawl(config).await,
+        Command::Config { command: ConfigCommand::Print } => {
+            match config.to_toml() {
+                Ok(toml) => print!("{}", toml),
+                Err(e) => error!("Error printing config: {}", e),
             }
+            return;
         }
+        command => command,
+    };
 
-        info!("Spawning workers...");
+    if let Err(e) = run_command(&config, command).await {
+        error!("{:#}", e);
+        std::process::exit(1);
+    }
+}
 
-        let mut handles = Vec::with_capacity(max_concurrency);
+async fn run_command(config: &SpiderConfig, command: Command) -> anyhow::Result<()> {
+    if let Command::Purge { yes: false, .. } = command {
+        anyhow::bail!("Refusing to purge without --yes");
+    }
 
-        for _ in 0..max_concurrency {
-            let db_clone = db.clone();
-            let crawler_clone = crawler.clone();
+    let db = commands::connect(config).await?;
 
-            handles.push(task::spawn(async move {
-                let c = crawler_clone.lock().await;
-                c.crawl(&db_clone).await;
-            }));
+    match command {
+        Command::Seed { sources } => {
+            let report = commands::seed(&db, &sources).await?;
+            println!("Queued {} URLs, rejected {}", report.accepted.len(), report.rejected.len());
         }
-
-        for handle in handles {
-            if let Err(e) = handle.await {
-                error!("Worker task failed: {:?}", e);
+        Command::Status => println!("{}", commands::status(&db).await?),
+        Command::Export { output } => {
+            let count = match output {
+                Some(path) => commands::export(&db, &mut BufWriter::new(File::create(path)?)).await?,
+                None => commands::export(&db, &mut io::stdout().lock()).await?,
+            };
+            info!("Exported {} pages", count);
+        }
+        Command::RequeueFailed => {
+            let count = commands::requeue_failed(&db).await?;
+            println!("Requeued {} failed URLs", count);
+        }
+        Command::Inspect { url, html } => {
+            let inspection = commands::inspect(&db, &url, html).await?;
+            let mut out = io::stdout().lock();
+            serde_json::to_writer_pretty(&mut out, &inspection)?;
+            writeln!(out)?;
+        }
+        Command::Purge { all, .. } => {
+            let count = commands::purge(&db, all).await?;
+            println!("Deleted {} keys", count);
+        }
+        Command::Report { format, output } => {
+            let rendered = report::build_report(&db).await?.render(format)?;
+            match output {
+                Some(path) => std::fs::write(path, rendered)?,
+                None => println!("{}", rendered),
             }
         }
+        Command::Crawl | Command::Config { .. } => {}
+    }
 
-        let mut c = crawler.lock().await;
-        page_controller.save_pages(&*c).await;
-        links_controller.save_links(&*c).await;
-        if let Err(e) = image_controller.save_images(&*c).await {
-            error!("Error saving images: {:?}", e);
+    Ok(())
+}
+
+async fn crawl(config: SpiderConfig) {
+    let spider = match Spider::builder().config(config).handle_signals(true).build() {
+        Ok(spider) => spider,
+        Err(e) => {
+            error!("{:#}", e);
+            std::process::exit(2);
         }
+    };
 
-        c.pages.lock().await.clear();
-        c.outlinks.lock().await.clear();
-        c.backlinks.lock().await.clear();
-        c.images.lock().await.clear();
+    match spider.run().await {
+        Ok(CrawlOutcome { reason: StopReason::Stopped, clean }) => {
+            let exit_code = if clean { 0 } else { 1 };
+            info!("Shutdown complete, exiting with status {}", exit_code);
+            std::process::exit(exit_code);
+        }
+        Ok(_) => {}
+        Err(e) => {
+            error!("{:#}", e);
+            std::process::exit(1);
+        }
     }
 }
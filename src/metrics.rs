@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex, OnceLock};
+use std::time::Duration;
+use axum::Router;
+use axum::http::header::CONTENT_TYPE;
+use axum::routing::get;
+use tracing::{info, error};
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+use crate::database::Database;
+
+pub const CONTENT_TYPE_TEXT: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+// Upper bounds in seconds, Prometheus' default buckets stretched to the fetch timeout.
+const FETCH_DURATION_BUCKETS: [f64; 11] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 30.0, 60.0];
+
+#[derive(Debug, Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    pub fn add(&self, value: u64) {
+        self.0.fetch_add(value, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Gauge(AtomicI64);
+
+impl Gauge {
+    pub fn set(&self, value: i64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn dec(&self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// A counter split by the value of one label.
+#[derive(Debug)]
+pub struct LabeledCounter {
+    label: &'static str,
+    values: StdMutex<BTreeMap<String, u64>>,
+}
+
+impl LabeledCounter {
+    pub fn new(label: &'static str) -> Self {
+        Self { label, values: StdMutex::new(BTreeMap::new()) }
+    }
+
+    pub fn inc(&self, value: &str) {
+        if let Ok(mut values) = self.values.lock() {
+            *values.entry(value.to_string()).or_default() += 1;
+        }
+    }
+
+    pub fn get(&self, value: &str) -> u64 {
+        self.values.lock().ok().and_then(|values| values.get(value).copied()).unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Default)]
+struct HistogramState {
+    // Observations per bucket, not cumulative; the last entry is +Inf.
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+#[derive(Debug)]
+pub struct Histogram {
+    bounds: &'static [f64],
+    state: StdMutex<HistogramState>,
+}
+
+impl Histogram {
+    pub fn new(bounds: &'static [f64]) -> Self {
+        let state = HistogramState { counts: vec![0; bounds.len() + 1], ..Default::default() };
+        Self { bounds, state: StdMutex::new(state) }
+    }
+
+    pub fn observe(&self, value: f64) {
+        let bucket = self.bounds.iter().position(|bound| value <= *bound).unwrap_or(self.bounds.len());
+        if let Ok(mut state) = self.state.lock() {
+            state.counts[bucket] += 1;
+            state.sum += value;
+            state.count += 1;
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.state.lock().map(|state| state.count).unwrap_or(0)
+    }
+}
+
+// Process-wide crawler metrics, read by the `/metrics` endpoint.
+#[derive(Debug)]
+pub struct Metrics {
+    pub fetches: LabeledCounter,
+    pub fetch_errors: LabeledCounter,
+    pub fetched_bytes: Counter,
+    pub fetch_duration: Histogram,
+    pub spider_queue: Gauge,
+    pub pages_queue: Gauge,
+    pub visited: Gauge,
+    pub links_discovered: Counter,
+    pub links_admitted: Counter,
+    pub rejections: LabeledCounter,
+    pub in_flight: Gauge,
+    pub backpressure_pauses: Counter,
+    pub backpressure_paused: Gauge,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            fetches: LabeledCounter::new("status_class"),
+            fetch_errors: LabeledCounter::new("kind"),
+            fetched_bytes: Counter::default(),
+            fetch_duration: Histogram::new(&FETCH_DURATION_BUCKETS),
+            spider_queue: Gauge::default(),
+            pages_queue: Gauge::default(),
+            visited: Gauge::default(),
+            links_discovered: Counter::default(),
+            links_admitted: Counter::default(),
+            rejections: LabeledCounter::new("reason"),
+            in_flight: Gauge::default(),
+            backpressure_pauses: Counter::default(),
+            backpressure_paused: Gauge::default(),
+        }
+    }
+}
+
+impl Metrics {
+    // Everything in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        render_labeled(&mut out, "spider_fetches_total", "HTTP responses by status class", &self.fetches);
+        render_labeled(&mut out, "spider_fetch_errors_total", "Failed fetches by kind", &self.fetch_errors);
+        render_counter(&mut out, "spider_fetched_bytes_total", "Response body bytes downloaded", self.fetched_bytes.get());
+        render_histogram(&mut out, "spider_fetch_duration_seconds", "Time to fetch a URL", &self.fetch_duration);
+        render_gauge(&mut out, "spider_queue_depth", "URLs waiting in spider_queue", self.spider_queue.get());
+        render_gauge(&mut out, "spider_pages_queue_depth", "Pages waiting in pages_queue for the indexer", self.pages_queue.get());
+        render_gauge(&mut out, "spider_visited_urls", "URLs marked visited", self.visited.get());
+        render_counter(&mut out, "spider_links_discovered_total", "Links found on crawled pages", self.links_discovered.get());
+        render_counter(&mut out, "spider_links_admitted_total", "Discovered links pushed to the queue", self.links_admitted.get());
+        render_labeled(&mut out, "spider_rejections_total", "Pages and links skipped by robots directives or crawl scope", &self.rejections);
+        render_gauge(&mut out, "spider_in_flight_workers", "Workers currently crawling a URL", self.in_flight.get());
+        render_counter(&mut out, "spider_backpressure_pauses_total", "Times the crawl paused for the indexer", self.backpressure_pauses.get());
+        render_gauge(&mut out, "spider_backpressure_paused", "1 while the crawl is paused for the indexer", self.backpressure_paused.get());
+
+        out
+    }
+}
+
+fn render_header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn render_counter(out: &mut String, name: &str, help: &str, value: u64) {
+    render_header(out, name, help, "counter");
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+fn render_gauge(out: &mut String, name: &str, help: &str, value: i64) {
+    render_header(out, name, help, "gauge");
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+fn render_labeled(out: &mut String, name: &str, help: &str, counter: &LabeledCounter) {
+    render_header(out, name, help, "counter");
+    if let Ok(values) = counter.values.lock() {
+        for (value, count) in values.iter() {
+            let _ = writeln!(out, "{}{{{}=\"{}\"}} {}", name, counter.label, escape_label(value), count);
+        }
+    }
+}
+
+fn render_histogram(out: &mut String, name: &str, help: &str, histogram: &Histogram) {
+    render_header(out, name, help, "histogram");
+    let Ok(state) = histogram.state.lock() else {
+        return;
+    };
+
+    let mut cumulative = 0;
+    for (bound, count) in histogram.bounds.iter().zip(&state.counts) {
+        cumulative += count;
+        let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative);
+    }
+    let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, state.count);
+    let _ = writeln!(out, "{}_sum {}", name, state.sum);
+    let _ = writeln!(out, "{}_count {}", name, state.count);
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+pub fn metrics() -> &'static Metrics {
+    METRICS.get_or_init(Metrics::default)
+}
+
+// "2xx", "3xx", ... for `spider_fetches_total`.
+pub fn status_class(status_code: u16) -> String {
+    format!("{}xx", status_code / 100)
+}
+
+pub fn router() -> Router {
+    Router::new().route(
+        "/metrics",
+        get(|| async { ([(CONTENT_TYPE, CONTENT_TYPE_TEXT)], metrics().render()) }),
+    )
+}
+
+// Serves `/metrics` on `addr` until the task is dropped.
+pub async fn serve(addr: SocketAddr) {
+    let listener = match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Error binding metrics endpoint to {}: {}", addr, e);
+            return;
+        }
+    };
+
+    info!("Serving metrics on http://{}/metrics", addr);
+    if let Err(e) = axum::serve(listener, router()).await {
+        error!("Metrics endpoint failed: {}", e);
+    }
+}
+
+// Copies queue depths and the visited count from Redis into the gauges every `interval`.
+// Runs until the task is dropped.
+pub async fn refresh(db: Arc<Mutex<Database>>, interval: Duration) {
+    loop {
+        let status = db.lock().await.get_status().await;
+        match status {
+            Ok(status) => {
+                let metrics = metrics();
+                metrics.spider_queue.set(status.queued_urls as i64);
+                metrics.pages_queue.set(status.indexer_queue as i64);
+                metrics.visited.set(status.visited as i64);
+            }
+            Err(e) => error!("Error refreshing metrics: {:?}", e),
+        }
+
+        sleep(interval).await;
+    }
+}
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+// A URL whose fetch failed, kept with its queue score so it can be requeued later.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedUrl {
+    pub normalized_url: String,
+    pub raw_url: String,
+    pub score: f64,
+    pub error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+impl FailedUrl {
+    pub fn new(normalized_url: String, raw_url: String, score: f64, error: String) -> Self {
+        Self {
+            normalized_url,
+            raw_url,
+            score,
+            error,
+            failed_at: Utc::now(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn from_json(data: &str) -> Result<Self, String> {
+        serde_json::from_str(data).map_err(|e| format!("Invalid failed URL: {}", e))
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+
+// A discovered RSS/Atom feed and the validators from its last poll, used for conditional GETs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Feed {
+    pub normalized_url: String,
+    pub url: String,
+    pub etag: String,
+    pub last_modified: String,
+    pub last_polled: Option<DateTime<Utc>>,
+}
+
+impl Feed {
+    pub fn new(normalized_url: String, url: String) -> Self {
+        Self {
+            normalized_url,
+            url,
+            ..Default::default()
+        }
+    }
+
+    pub fn to_hash(&self) -> HashMap<String, String> {
+        let mut map = HashMap::new();
+        map.insert("normalized_url".to_string(), self.normalized_url.clone());
+        map.insert("url".to_string(), self.url.clone());
+        map.insert("etag".to_string(), self.etag.clone());
+        map.insert("last_modified".to_string(), self.last_modified.clone());
+        map.insert(
+            "last_polled".to_string(),
+            self.last_polled.map(|t| t.to_rfc2822()).unwrap_or_default(),
+        );
+        map
+    }
+
+    pub fn from_hash(data: &HashMap<String, String>) -> Result<Self, String> {
+        let get = |field: &str| data.get(field).cloned().unwrap_or_default();
+
+        let normalized_url = data.get("normalized_url").ok_or("Missing 'normalized_url'")?.clone();
+        let url = data.get("url").ok_or("Missing 'url'")?.clone();
+
+        let last_polled = match get("last_polled").as_str() {
+            "" => None,
+            value => Some(
+                crate::utils::parse::parse_time(value).map_err(|e| format!("Invalid last_polled: {}", e))?,
+            ),
+        };
+
+        Ok(Self {
+            normalized_url,
+            url,
+            etag: get("etag"),
+            last_modified: get("last_modified"),
+            last_polled,
+        })
+    }
+}
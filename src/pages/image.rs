@@ -1,6 +1,46 @@
+use serde::{Serialize, Deserialize};
+
 #[derive(Debug, Clone)]
 pub struct Image {
     pub normalized_page_url: String,
     pub normalized_source_url: String,
+    pub source_url: String,
     pub alt: String,
+    pub width: String,
+    pub height: String,
+    pub loading: String,
+    pub descriptor: String,
+}
+
+// How one page uses an image. An image can be referenced by many pages, each
+// with its own alt text and attributes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageReference {
+    pub alt: String,
+    pub width: String,
+    pub height: String,
+    pub loading: String,
+    pub descriptor: String,
+}
+
+impl Image {
+    pub fn reference(&self) -> ImageReference {
+        ImageReference {
+            alt: self.alt.clone(),
+            width: self.width.clone(),
+            height: self.height.clone(),
+            loading: self.loading.clone(),
+            descriptor: self.descriptor.clone(),
+        }
+    }
+}
+
+impl ImageReference {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn from_json(data: &str) -> Result<Self, String> {
+        serde_json::from_str(data).map_err(|e| format!("Invalid image reference: {}", e))
+    }
 }
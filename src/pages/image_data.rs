@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImageData {
+    pub normalized_source_url: String,
+    pub mime_type: String,
+    pub byte_size: usize,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub dhash: Option<u64>,
+}
+
+impl ImageData {
+    pub fn dhash_hex(&self) -> String {
+        self.dhash.map(|hash| format!("{:016x}", hash)).unwrap_or_default()
+    }
+
+    pub fn to_hash(&self) -> HashMap<String, String> {
+        let optional = |value: Option<u32>| value.map(|v| v.to_string()).unwrap_or_default();
+
+        let mut map = HashMap::new();
+        map.insert("mime_type".to_string(), self.mime_type.clone());
+        map.insert("byte_size".to_string(), self.byte_size.to_string());
+        map.insert("pixel_width".to_string(), optional(self.width));
+        map.insert("pixel_height".to_string(), optional(self.height));
+        map.insert("dhash".to_string(), self.dhash_hex());
+        map
+    }
+}
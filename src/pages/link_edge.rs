@@ -0,0 +1,89 @@
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use crate::crawler::robots_directives::{is_nofollow_rel, NOFOLLOW_RELS};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkPosition {
+    Nav,
+    Header,
+    Footer,
+    Aside,
+    #[default]
+    Body,
+}
+
+impl fmt::Display for LinkPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LinkPosition::Nav => "nav",
+            LinkPosition::Header => "header",
+            LinkPosition::Footer => "footer",
+            LinkPosition::Aside => "aside",
+            LinkPosition::Body => "body",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkEdge {
+    pub source_url: String,
+    pub target_url: String,
+    pub anchor_texts: Vec<String>,
+    pub title: String,
+    pub rel: BTreeSet<String>,
+    pub position: LinkPosition,
+    pub count: usize,
+}
+
+impl LinkEdge {
+    pub fn new(source_url: String, target_url: String) -> Self {
+        Self {
+            source_url,
+            target_url,
+            count: 1,
+            ..Default::default()
+        }
+    }
+
+    pub fn anchor_text(&self) -> &str {
+        self.anchor_texts.first().map(String::as_str).unwrap_or("")
+    }
+
+    // Folds another occurrence of the same source -> target link into this edge.
+    pub fn merge(&mut self, other: &LinkEdge) {
+        self.count += other.count;
+
+        for anchor in &other.anchor_texts {
+            if !self.anchor_texts.contains(anchor) {
+                self.anchor_texts.push(anchor.clone());
+            }
+        }
+
+        if self.title.is_empty() {
+            self.title = other.title.clone();
+        }
+
+        // The target stays followable if any anchor to it is, so nofollow-type values are only
+        // kept when every occurrence carries one.
+        let followable = !is_nofollow_rel(&self.rel) || !is_nofollow_rel(&other.rel);
+        self.rel.extend(other.rel.iter().cloned());
+        if followable {
+            self.rel.retain(|rel| !NOFOLLOW_RELS.contains(&rel.as_str()));
+        }
+
+        if other.position == LinkPosition::Body {
+            self.position = LinkPosition::Body;
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn from_json(data: &str) -> Result<Self, String> {
+        serde_json::from_str(data).map_err(|e| format!("Invalid link edge: {}", e))
+    }
+}
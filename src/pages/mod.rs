@@ -1,7 +1,19 @@
+pub mod failed_url;
+pub mod feed;
 pub mod image;
+pub mod image_data;
+pub mod link_edge;
 pub mod page;
+pub mod page_metadata;
 pub mod page_node;
+pub mod structured_data;
 
-pub use image::Image;
+pub use failed_url::FailedUrl;
+pub use feed::Feed;
+pub use image::{Image, ImageReference};
+pub use image_data::ImageData;
+pub use link_edge::{LinkEdge, LinkPosition};
 pub use page::{Page, create_page, hash_page, dehash_page};
+pub use page_metadata::{Heading, PageMetadata};
 pub use page_node::PageNode;
+pub use structured_data::StructuredData;
@@ -7,6 +7,8 @@ use std::collections::HashMap;
 pub struct Page {
     pub normalized_url: String,
     pub html: String,
+    pub text: String,
+    pub content_html: String,
     pub content_type: String,
     pub status_code: i32,
     pub last_crawled: DateTime<Utc>,
@@ -43,16 +45,28 @@ impl Page {
         Self {
             normalized_url,
             html,
+            text: String::new(),
+            content_html: String::new(),
             content_type,
             status_code,
             last_crawled: Utc::now(),
         }
     }
 
+    pub fn with_content(mut self, text: String, content_html: String) -> Self {
+        self.text = text;
+        self.content_html = content_html;
+        self
+    }
+
     pub fn to_hash(&self) -> HashMap<String, String> {
         let mut map = HashMap::new();
         map.insert("normalized_url".to_string(), self.normalized_url.clone());
         map.insert("html".to_string(), self.html.clone());
+        map.insert("text".to_string(), self.text.clone());
+        if !self.content_html.is_empty() {
+            map.insert("content_html".to_string(), self.content_html.clone());
+        }
         map.insert("content_type".to_string(), self.content_type.clone());
         map.insert("status_code".to_string(), self.status_code.to_string());
         map.insert("last_crawled".to_string(), self.last_crawled.to_rfc2822());
@@ -62,6 +76,8 @@ impl Page {
     pub fn from_hash(data: &HashMap<String, String>) -> Result<Self, String> {
         let normalized_url = data.get("normalized_url").ok_or("Missing 'normalized_url'")?.clone();
         let html = data.get("html").ok_or("Missing 'html'")?.clone();
+        let text = data.get("text").cloned().unwrap_or_default();
+        let content_html = data.get("content_html").cloned().unwrap_or_default();
         let content_type = data.get("content_type").ok_or("Missing 'content_type'")?.clone();
 
         let status_code = data.get("status_code")
@@ -77,6 +93,8 @@ impl Page {
         Ok(Self {
             normalized_url,
             html,
+            text,
+            content_html,
             content_type,
             status_code,
             last_crawled,
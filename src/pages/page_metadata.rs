@@ -0,0 +1,85 @@
+use serde::{Serialize, Deserialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Heading {
+    pub level: u8,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PageMetadata {
+    pub normalized_url: String,
+    pub title: String,
+    pub description: String,
+    pub keywords: Vec<String>,
+    pub lang: String,
+    pub canonical: String,
+    pub headings: Vec<Heading>,
+    pub robots: String,
+    pub open_graph: BTreeMap<String, String>,
+    pub twitter: BTreeMap<String, String>,
+    pub word_count: usize,
+}
+
+impl PageMetadata {
+    pub fn new(normalized_url: String) -> Self {
+        Self {
+            normalized_url,
+            ..Default::default()
+        }
+    }
+
+    pub fn to_hash(&self) -> HashMap<String, String> {
+        let mut map = HashMap::new();
+        map.insert("normalized_url".to_string(), self.normalized_url.clone());
+        map.insert("title".to_string(), self.title.clone());
+        map.insert("description".to_string(), self.description.clone());
+        map.insert("keywords".to_string(), serde_json::to_string(&self.keywords).unwrap_or_default());
+        map.insert("lang".to_string(), self.lang.clone());
+        map.insert("canonical".to_string(), self.canonical.clone());
+        map.insert("headings".to_string(), serde_json::to_string(&self.headings).unwrap_or_default());
+        map.insert("robots".to_string(), self.robots.clone());
+        map.insert("open_graph".to_string(), serde_json::to_string(&self.open_graph).unwrap_or_default());
+        map.insert("twitter".to_string(), serde_json::to_string(&self.twitter).unwrap_or_default());
+        map.insert("word_count".to_string(), self.word_count.to_string());
+        map
+    }
+
+    pub fn from_hash(data: &HashMap<String, String>) -> Result<Self, String> {
+        let get = |field: &str| data.get(field).cloned().unwrap_or_default();
+
+        let normalized_url = data.get("normalized_url").ok_or("Missing 'normalized_url'")?.clone();
+
+        let keywords = crate::utils::parse::parse_strings_slice(&get("keywords"))
+            .map_err(|e| format!("Invalid keywords: {}", e))?;
+
+        let headings = serde_json::from_str(&get("headings"))
+            .map_err(|e| format!("Invalid headings: {}", e))?;
+
+        let open_graph = serde_json::from_str(&get("open_graph"))
+            .map_err(|e| format!("Invalid open_graph: {}", e))?;
+
+        let twitter = serde_json::from_str(&get("twitter"))
+            .map_err(|e| format!("Invalid twitter: {}", e))?;
+
+        let word_count = data.get("word_count")
+            .ok_or("Missing 'word_count'")?
+            .parse::<usize>()
+            .map_err(|e| format!("Invalid word_count: {}", e))?;
+
+        Ok(Self {
+            normalized_url,
+            title: get("title"),
+            description: get("description"),
+            keywords,
+            lang: get("lang"),
+            canonical: get("canonical"),
+            headings,
+            robots: get("robots"),
+            open_graph,
+            twitter,
+            word_count,
+        })
+    }
+}
@@ -1,10 +1,12 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use super::link_edge::LinkEdge;
 
 #[derive(Debug, Clone)]
 pub struct PageNode {
     pub normalized_url: String,
     normalized_link_urls: HashSet<String>,
+    edges: HashMap<String, LinkEdge>,
 }
 
 impl PageNode {
@@ -12,6 +14,7 @@ impl PageNode {
         Self {
             normalized_url,
             normalized_link_urls: HashSet::new(),
+            edges: HashMap::new(),
         }
     }
 
@@ -19,6 +22,20 @@ impl PageNode {
         self.normalized_link_urls.insert(new_normalized_link);
     }
 
+    pub fn append_edge(&mut self, new_normalized_link: String, edge: LinkEdge) {
+        match self.edges.get_mut(&new_normalized_link) {
+            Some(existing) => existing.merge(&edge),
+            None => {
+                self.edges.insert(new_normalized_link.clone(), edge);
+            }
+        }
+        self.append_link(new_normalized_link);
+    }
+
+    pub fn get_edge(&self, normalized_link: &str) -> Option<&LinkEdge> {
+        self.edges.get(normalized_link)
+    }
+
     pub fn get_links(&self) -> Vec<String> {
         self.normalized_link_urls.iter().cloned().collect()
     }
@@ -0,0 +1,59 @@
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StructuredData {
+    pub normalized_url: String,
+    pub json_ld: Vec<Value>,
+    pub microdata: Vec<Value>,
+    pub rdfa: Vec<Value>,
+    pub warnings: Vec<String>,
+}
+
+impl StructuredData {
+    pub fn new(normalized_url: String) -> Self {
+        Self {
+            normalized_url,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.json_ld.is_empty() && self.microdata.is_empty() && self.rdfa.is_empty() && self.warnings.is_empty()
+    }
+
+    pub fn entities(&self) -> impl Iterator<Item = &Value> {
+        self.json_ld.iter().chain(self.microdata.iter()).chain(self.rdfa.iter())
+    }
+
+    pub fn to_hash(&self) -> HashMap<String, String> {
+        let mut map = HashMap::new();
+        map.insert("normalized_url".to_string(), self.normalized_url.clone());
+        map.insert("json_ld".to_string(), serde_json::to_string(&self.json_ld).unwrap_or_default());
+        map.insert("microdata".to_string(), serde_json::to_string(&self.microdata).unwrap_or_default());
+        map.insert("rdfa".to_string(), serde_json::to_string(&self.rdfa).unwrap_or_default());
+        map.insert("warnings".to_string(), serde_json::to_string(&self.warnings).unwrap_or_default());
+        map
+    }
+
+    pub fn from_hash(data: &HashMap<String, String>) -> Result<Self, String> {
+        let normalized_url = data.get("normalized_url").ok_or("Missing 'normalized_url'")?.clone();
+
+        let parse_values = |field: &str| -> Result<Vec<Value>, String> {
+            let raw = data.get(field).ok_or(format!("Missing '{}'", field))?;
+            serde_json::from_str(raw).map_err(|e| format!("Invalid {}: {}", field, e))
+        };
+
+        let warnings = crate::utils::parse::parse_strings_slice(data.get("warnings").ok_or("Missing 'warnings'")?)
+            .map_err(|e| format!("Invalid warnings: {}", e))?;
+
+        Ok(Self {
+            normalized_url,
+            json_ld: parse_values("json_ld")?,
+            microdata: parse_values("microdata")?,
+            rdfa: parse_values("rdfa")?,
+            warnings,
+        })
+    }
+}
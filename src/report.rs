@@ -0,0 +1,260 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use anyhow::Result;
+use serde::Serialize;
+use tokio::sync::Mutex;
+use crate::database::{CrawlStatus, Database, FetchStats};
+
+// Rows kept in each "top" table of the report.
+pub const REPORT_TOP_N: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Html,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "html" => Ok(Self::Html),
+            other => Err(format!("Unknown report format '{}', expected json or html", other)),
+        }
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::Html => write!(f, "html"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Count {
+    pub name: String,
+    pub count: u64,
+}
+
+impl Count {
+    fn new(name: impl ToString, count: u64) -> Self {
+        Self { name: name.to_string(), count }
+    }
+}
+
+// Fetch durations over the most recent `MAX_LATENCY_SAMPLES` fetches.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub samples: usize,
+    pub average_ms: f64,
+    pub p50_ms: u64,
+    pub p90_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+}
+
+impl LatencySummary {
+    pub fn new(latencies: &[u64]) -> Self {
+        if latencies.is_empty() {
+            return Self::default();
+        }
+
+        let mut sorted = latencies.to_vec();
+        sorted.sort_unstable();
+
+        Self {
+            samples: sorted.len(),
+            average_ms: sorted.iter().sum::<u64>() as f64 / sorted.len() as f64,
+            p50_ms: percentile(&sorted, 50.0),
+            p90_ms: percentile(&sorted, 90.0),
+            p99_ms: percentile(&sorted, 99.0),
+            max_ms: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+// Nearest-rank percentile of an ascending slice; 0 when it is empty.
+pub fn percentile(sorted: &[u64], p: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+// Summary of a crawl built from what is stored in Redis, for `spider report` and the end of
+// a run.
+#[derive(Debug, Clone, Serialize)]
+pub struct CrawlReport {
+    pub generated_at: String,
+    pub pages_fetched: u64,
+    pub bytes_fetched: u64,
+    pub elapsed_secs: u64,
+    pub stop_reason: Option<String>,
+    pub visited: u64,
+    pub failed: u64,
+    pub queued_urls: u64,
+    pub fetches: u64,
+    pub redirects: u64,
+    pub pages_per_host: Vec<Count>,
+    pub status_codes: Vec<Count>,
+    pub content_types: Vec<Count>,
+    pub depths: Vec<Count>,
+    pub top_errors: Vec<Count>,
+    pub latency: LatencySummary,
+    pub most_linked: Vec<Count>,
+    pub missing_alt: Vec<Count>,
+}
+
+impl CrawlReport {
+    pub fn new(
+        status: &CrawlStatus,
+        stats: &FetchStats,
+        latencies: &[u64],
+        most_linked: Vec<(String, u64)>,
+        missing_alt: Vec<(String, u64)>,
+    ) -> Self {
+        Self {
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            pages_fetched: status.usage.pages,
+            bytes_fetched: status.usage.bytes,
+            elapsed_secs: status.usage.elapsed.as_secs(),
+            stop_reason: status.stop_reason.clone(),
+            visited: status.visited,
+            failed: status.failed,
+            queued_urls: status.queued_urls,
+            fetches: stats.fetches,
+            redirects: stats.redirects,
+            pages_per_host: top(&stats.pages_per_host, REPORT_TOP_N),
+            status_codes: stats.status_codes.iter().map(|(status, count)| Count::new(status, *count)).collect(),
+            content_types: top(&stats.content_types, REPORT_TOP_N),
+            depths: stats.depths.iter().map(|(depth, count)| Count::new(depth, *count)).collect(),
+            top_errors: top(&stats.errors, REPORT_TOP_N),
+            latency: LatencySummary::new(latencies),
+            most_linked: most_linked.into_iter().map(|(url, count)| Count::new(url, count)).collect(),
+            missing_alt: missing_alt.into_iter().map(|(url, count)| Count::new(url, count)).collect(),
+        }
+    }
+
+    pub fn render(&self, format: ReportFormat) -> Result<String> {
+        match format {
+            ReportFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+            ReportFormat::Html => Ok(self.to_html()),
+        }
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        out.push_str("<title>Crawl report</title>\n<style>\n");
+        out.push_str("body { font-family: sans-serif; margin: 2em; }\n");
+        out.push_str("table { border-collapse: collapse; margin-bottom: 2em; }\n");
+        out.push_str("th, td { border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: left; }\n");
+        out.push_str("td.count { text-align: right; }\n");
+        out.push_str("</style>\n</head>\n<body>\n");
+        let _ = writeln!(out, "<h1>Crawl report</h1>\n<p>Generated {}</p>", escape_html(&self.generated_at));
+
+        let stop_reason = self.stop_reason.as_deref().unwrap_or("not stopped");
+        let latency = &self.latency;
+        let summary = [
+            ("Pages fetched", self.pages_fetched.to_string()),
+            ("Bytes fetched", self.bytes_fetched.to_string()),
+            ("Elapsed", format!("{}s", self.elapsed_secs)),
+            ("Stop reason", stop_reason.to_string()),
+            ("Visited URLs", self.visited.to_string()),
+            ("Failed URLs", self.failed.to_string()),
+            ("Queued URLs", self.queued_urls.to_string()),
+            ("Fetches", self.fetches.to_string()),
+            ("Redirects", self.redirects.to_string()),
+            ("Latency samples", latency.samples.to_string()),
+            ("Average latency", format!("{:.1} ms", latency.average_ms)),
+            ("p50 / p90 / p99 / max", format!("{} / {} / {} / {} ms", latency.p50_ms, latency.p90_ms, latency.p99_ms, latency.max_ms)),
+        ];
+        out.push_str("<h2>Summary</h2>\n<table>\n");
+        for (label, value) in summary {
+            let _ = writeln!(out, "<tr><th>{}</th><td>{}</td></tr>", label, escape_html(&value));
+        }
+        out.push_str("</table>\n");
+
+        render_table(&mut out, "Pages per host", "Host", &self.pages_per_host);
+        render_table(&mut out, "Status codes", "Status", &self.status_codes);
+        render_table(&mut out, "Content types", "Content type", &self.content_types);
+        render_table(&mut out, "Depth distribution", "Depth", &self.depths);
+        render_table(&mut out, "Top errors", "Error kind", &self.top_errors);
+        render_table(&mut out, "Most linked pages", "Page", &self.most_linked);
+        render_table(&mut out, "Pages with images missing alt text", "Page", &self.missing_alt);
+
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    // Writes `crawl-report-<job>.json` and `.html` into `dir`, creating it if needed.
+    pub fn write_files(&self, dir: &Path, job: &str) -> Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)?;
+
+        let mut written = Vec::new();
+        for format in [ReportFormat::Json, ReportFormat::Html] {
+            let path = dir.join(format!("crawl-report-{}.{}", job, format));
+            fs::write(&path, self.render(format)?)?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+}
+
+fn top(counts: &BTreeMap<String, u64>, limit: usize) -> Vec<Count> {
+    let mut counts: Vec<Count> = counts.iter().map(|(name, count)| Count::new(name, *count)).collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    counts.truncate(limit);
+    counts
+}
+
+fn render_table(out: &mut String, title: &str, column: &str, rows: &[Count]) {
+    let _ = writeln!(out, "<h2>{}</h2>", title);
+    if rows.is_empty() {
+        out.push_str("<p>None</p>\n");
+        return;
+    }
+
+    let _ = writeln!(out, "<table>\n<tr><th>{}</th><th>Count</th></tr>", column);
+    for row in rows {
+        let _ = writeln!(out, "<tr><td>{}</td><td class=\"count\">{}</td></tr>", escape_html(&row.name), row.count);
+    }
+    out.push_str("</table>\n");
+}
+
+pub fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub async fn build_report(db: &Arc<Mutex<Database>>) -> Result<CrawlReport> {
+    let mut db = db.lock().await;
+    let status = db.get_status().await?;
+    let stats = db.get_fetch_stats().await?;
+    let latencies = db.get_fetch_latencies().await?;
+    let most_linked = db.get_most_linked(REPORT_TOP_N).await?;
+    let missing_alt = db.get_missing_alt(REPORT_TOP_N).await?;
+
+    Ok(CrawlReport::new(&status, &stats, &latencies, most_linked, missing_alt))
+}
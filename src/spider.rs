@@ -0,0 +1,500 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use anyhow::{anyhow, bail, Result};
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+use tokio::time::{sleep, timeout, Duration};
+use tracing::{info, info_span, error, Instrument};
+use crate::admin::{self, AdminState};
+use crate::commands;
+use crate::config::SpiderConfig;
+use crate::controllers::feed_controller::FeedController;
+use crate::controllers::image_controller::ImageController;
+use crate::controllers::page_controller::PageController;
+use crate::controllers::page_node_controller::LinksController;
+use crate::controllers::sitemap_controller::SitemapController;
+use crate::crawler::backpressure::Backpressure;
+use crate::crawler::content_handler::{ContentHandler, ContentHandlers};
+use crate::crawler::crawler::CrawlerConfig;
+use crate::crawler::events::{CrawlEvent, CrawlEvents, EventCallback, EventStream};
+use crate::crawler::scope::ScopeRules;
+use crate::crawler::fetch::configure_fetcher;
+use crate::crawler::fetcher::Fetcher;
+use crate::crawler::poll_feeds::poll_feeds;
+use crate::crawler::seeds::{seed_queue, Seed, SeedSource};
+use crate::crawler::shutdown::{wait_for_signal, InFlight, Shutdown};
+use crate::crawler::sitemap::{get_sitemap_entries, new_sitemap_roots};
+use crate::database::Database;
+use crate::metrics;
+use crate::report;
+
+// How long `run()` waits for new seeds once the queue is empty.
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StopReason {
+    // The queue ran empty and no Redis seed list can refill it.
+    Completed,
+    // `stop()`, a signal or the admin API's drain.
+    Stopped,
+    BudgetExhausted(String),
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Completed => write!(f, "completed"),
+            Self::Stopped => write!(f, "stopped"),
+            Self::BudgetExhausted(reason) => write!(f, "budget exhausted: {}", reason),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrawlOutcome {
+    pub reason: StopReason,
+    // False if unfinished URLs could not be requeued or results could not be saved.
+    pub clean: bool,
+}
+
+#[derive(Default)]
+pub struct SpiderBuilder {
+    config: SpiderConfig,
+    seeds: Vec<SeedSource>,
+    storage: Option<Arc<Mutex<Database>>>,
+    fetcher: Option<Arc<dyn Fetcher>>,
+    content_handlers: Vec<(String, Arc<dyn ContentHandler>)>,
+    callbacks: Vec<EventCallback>,
+    job: Option<String>,
+    handle_signals: bool,
+}
+
+impl SpiderBuilder {
+    pub fn config(mut self, config: SpiderConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    // Crawled along with the seeds and starting URL from the config.
+    pub fn seed(mut self, source: SeedSource) -> Self {
+        self.seeds.push(source);
+        self
+    }
+
+    pub fn seeds(mut self, sources: impl IntoIterator<Item = SeedSource>) -> Self {
+        self.seeds.extend(sources);
+        self
+    }
+
+    // An open connection to use instead of connecting with `config.storage`. Redis is the only
+    // backend: the frontier, budgets and stored pages rely on its scripts and data types.
+    pub fn storage(mut self, db: Arc<Mutex<Database>>) -> Self {
+        self.storage = Some(db);
+        self
+    }
+
+    // Replaces the HTTP fetcher for crawled pages and images. Feeds and sitemaps are still fetched
+    // over HTTP.
+    pub fn fetcher(mut self, fetcher: impl Fetcher + 'static) -> Self {
+        self.fetcher = Some(Arc::new(fetcher));
+        self
+    }
+
+    // Handles responses of type `mime` (or every subtype, for `type/*`), replacing a built-in
+    // handler for it. Responses without a handler are not fetched.
+    pub fn content_handler(mut self, mime: &str, handler: Arc<dyn ContentHandler>) -> Self {
+        self.content_handlers.push((mime.to_string(), handler));
+        self
+    }
+
+    // Called with every event, in the task that emits it; keep it quick.
+    pub fn on_event(mut self, callback: impl Fn(&CrawlEvent) + Send + Sync + 'static) -> Self {
+        self.callbacks.push(Arc::new(callback));
+        self
+    }
+
+    // Names the run in logs and report files. Defaults to the start time.
+    pub fn job(mut self, job: impl Into<String>) -> Self {
+        self.job = Some(job.into());
+        self
+    }
+
+    // Stop on SIGINT and SIGTERM, like the `spider` binary.
+    pub fn handle_signals(mut self, enabled: bool) -> Self {
+        self.handle_signals = enabled;
+        self
+    }
+
+    pub fn build(mut self) -> Result<Spider> {
+        self.config.scope.seeds.extend(self.seeds);
+        self.config.validate().map_err(|errors| anyhow!("Invalid configuration: {}", errors.join("; ")))?;
+
+        let config = self.config;
+        let mut crawler = CrawlerConfig::new(config.crawl.batch_size, config.crawl.max_concurrency);
+        crawler.simplified_html = config.extraction.simplified_html;
+        crawler.fetch_images = config.extraction.fetch_images;
+        crawler.robots_policy = config.robots_policy();
+        crawler.canonical_policy = config.scope.canonical_dedupe;
+        crawler.priority_policy = config.frontier.priority_policy.build(config.url_boosts());
+        crawler.budget = config.budget();
+        // Validated above.
+        crawler.backpressure = Backpressure::new(config.watermarks().unwrap_or_default());
+        if let Some(fetcher) = self.fetcher {
+            crawler.fetcher = fetcher;
+        }
+        for (mime, handler) in self.content_handlers {
+            crawler.content_handlers.register(&mime, handler);
+        }
+        crawler.events = CrawlEvents::new(self.callbacks);
+
+        Ok(Spider {
+            job: self.job.unwrap_or_else(|| chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string()),
+            config: Arc::new(config),
+            storage: self.storage,
+            handle_signals: self.handle_signals,
+            shutdown: crawler.shutdown.clone(),
+            backpressure: crawler.backpressure.clone(),
+            in_flight: crawler.in_flight.clone(),
+            scope: crawler.scope.clone(),
+            events: crawler.events.clone(),
+            crawler: Arc::new(crawler),
+            running: Arc::new(AtomicBool::new(false)),
+        })
+    }
+}
+
+// A crawl that can be embedded in another service. Clones share the same crawl, so one can
+// `run()` while another pauses, stops or watches events. A stopped spider stays stopped.
+#[derive(Clone)]
+pub struct Spider {
+    job: String,
+    config: Arc<SpiderConfig>,
+    storage: Option<Arc<Mutex<Database>>>,
+    handle_signals: bool,
+    crawler: Arc<CrawlerConfig>,
+    shutdown: Shutdown,
+    backpressure: Backpressure,
+    in_flight: InFlight,
+    scope: ScopeRules,
+    events: CrawlEvents,
+    running: Arc<AtomicBool>,
+}
+
+impl Spider {
+    pub fn builder() -> SpiderBuilder {
+        SpiderBuilder::default()
+    }
+
+    pub fn job(&self) -> &str {
+        &self.job
+    }
+
+    pub fn config(&self) -> &SpiderConfig {
+        &self.config
+    }
+
+    pub fn content_handlers(&self) -> &ContentHandlers {
+        &self.crawler.content_handlers
+    }
+
+    // Workers stop taking new URLs until `resume()`; in-flight pages finish.
+    pub fn pause(&self) {
+        self.backpressure.request_pause(&self.events);
+    }
+
+    pub fn resume(&self) {
+        self.backpressure.request_resume(&self.events);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.backpressure.is_paused()
+    }
+
+    // Asks `run()` to return: in-flight pages get `shutdown_timeout_secs` to finish, the rest
+    // are requeued, and results are flushed.
+    pub fn stop(&self) {
+        self.shutdown.trigger();
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.shutdown.is_triggered()
+    }
+
+    // Events from now on; subscribe before `run()` to see them all.
+    pub fn events(&self) -> EventStream {
+        self.events.subscribe()
+    }
+
+    // Seeds the queue and crawls until it is empty, the budget runs out or `stop()` is called.
+    // With Redis seed lists configured, an empty queue waits for new seeds instead.
+    pub async fn run(&self) -> Result<CrawlOutcome> {
+        if self.running.swap(true, Ordering::SeqCst) {
+            bail!("Spider is already running");
+        }
+
+        let result = self.crawl().instrument(info_span!("crawl", job = %self.job)).await;
+        self.running.store(false, Ordering::SeqCst);
+
+        let reason = match &result {
+            Ok(outcome) => outcome.reason.to_string(),
+            Err(e) => format!("error: {:#}", e),
+        };
+        self.events.emit(CrawlEvent::Finished { reason });
+        result
+    }
+
+    async fn crawl(&self) -> Result<CrawlOutcome> {
+        let config = &self.config;
+        configure_fetcher(config.fetch_settings());
+
+        let shutdown_timeout = Duration::from_secs(config.crawl.shutdown_timeout_secs);
+        let seed_sources = config.seed_sources();
+
+        let db = match &self.storage {
+            Some(db) => db.clone(),
+            None => commands::connect(config).await.map_err(|e| anyhow!("Error connecting to Redis: {:#}", e))?,
+        };
+
+        db.lock().await.configure_frontier(config.frontier_policy()).await.map_err(|e| anyhow!("Error configuring frontier: {:#}", e))?;
+        db.lock().await.start_crawl_clock().await.map_err(|e| anyhow!("Error starting crawl clock: {:#}", e))?;
+
+        let seed_report = seed_queue(&db, &seed_sources, &self.scope).await;
+        seed_report.log_summary();
+
+        // Other services can keep appending to Redis seed lists while the crawl runs.
+        let redis_seed_sources: Vec<SeedSource> = seed_sources
+            .into_iter()
+            .filter(|source| matches!(source, SeedSource::RedisList(_)))
+            .collect();
+
+        if seed_report.accepted.is_empty() && redis_seed_sources.is_empty() {
+            bail!("No valid seeds to crawl");
+        }
+
+        self.events.emit(CrawlEvent::Started { job: self.job.clone(), seeds: seed_report.accepted.len() });
+
+        let page_controller = PageController::new(db.clone());
+        let links_controller = LinksController::new(db.clone());
+        let image_controller = ImageController::new(db.clone()).with_retention(config.storage.image_retention);
+        let sitemap_controller = SitemapController::new(db.clone());
+        let feed_controller = FeedController::new(db.clone());
+        let use_sitemaps = config.scope.use_sitemaps;
+
+        let mut sitemap_hosts = HashSet::new();
+        if use_sitemaps {
+            self.seed_sitemaps(&sitemap_controller, &seed_report.accepted, &mut sitemap_hosts).await;
+        }
+
+        // Helper tasks, aborted when the crawl returns.
+        let mut background = JoinSet::new();
+
+        let feed_poll_interval = config.scope.feed_poll_interval_secs;
+        if feed_poll_interval > 0 {
+            let feeds = FeedController::new(db.clone());
+            let db_clone = db.clone();
+            let interval = Duration::from_secs(feed_poll_interval);
+
+            background.spawn(
+                async move {
+                    loop {
+                        poll_feeds(&feeds, &db_clone, interval).await;
+                        sleep(Duration::from_secs(feed_poll_interval.min(60))).await;
+                    }
+                }
+                .in_current_span(),
+            );
+        }
+
+        // Validated by SpiderConfig::validate.
+        match config.admin_addr() {
+            Ok(Some(addr)) => {
+                background.spawn(admin::serve(addr, AdminState::new(db.clone(), config, &self.crawler)).in_current_span());
+            }
+            _ => info!("Admin API disabled: no ADMIN_TOKEN set"),
+        }
+
+        if self.handle_signals {
+            let shutdown = self.shutdown.clone();
+            background.spawn(async move {
+                wait_for_signal().await;
+                shutdown.trigger();
+            });
+        }
+
+        let backpressure_interval = Duration::from_millis(config.storage.backpressure_poll_ms);
+        background.spawn(self.backpressure.clone().monitor(db.clone(), backpressure_interval).in_current_span());
+
+        // Validated by SpiderConfig::validate.
+        if let Ok(Some(addr)) = config.metrics_addr() {
+            background.spawn(metrics::serve(addr).in_current_span());
+            background.spawn(metrics::refresh(db.clone(), Duration::from_secs(config.metrics.refresh_secs)).in_current_span());
+        }
+
+        let mut clean = true;
+        let mut reason = StopReason::Stopped;
+
+        loop {
+            if self.shutdown.is_triggered() {
+                break;
+            }
+
+            if !redis_seed_sources.is_empty() {
+                let report = seed_queue(&db, &redis_seed_sources, &self.scope).await;
+                if !report.accepted.is_empty() || !report.rejected.is_empty() {
+                    report.log_summary();
+                }
+                if use_sitemaps {
+                    self.seed_sitemaps(&sitemap_controller, &report.accepted, &mut sitemap_hosts).await;
+                }
+            }
+
+            info!("Spawning workers...");
+
+            let mut workers = JoinSet::new();
+
+            for id in 0..self.crawler.concurrency.limit() {
+                let db_clone = db.clone();
+                let crawler = self.crawler.clone();
+
+                workers.spawn(async move { crawler.crawl(&db_clone).await }.instrument(info_span!("worker", id)));
+            }
+
+            let interrupted = tokio::select! {
+                _ = join_workers(&mut workers) => false,
+                _ = self.shutdown.wait() => true,
+            };
+
+            if interrupted {
+                info!("Shutting down: waiting up to {}s for in-flight pages...", shutdown_timeout.as_secs());
+
+                if timeout(shutdown_timeout, join_workers(&mut workers)).await.is_err() {
+                    // Snapshot before aborting: the in-flight guards are dropped with the tasks.
+                    let unfinished = self.in_flight.snapshot();
+                    workers.abort_all();
+                    join_workers(&mut workers).await;
+
+                    let mut db = db.lock().await;
+                    for (raw_url, score) in &unfinished {
+                        if let Err(e) = db.push_url(raw_url, *score).await {
+                            error!("Error requeueing {}: {:?}", raw_url, e);
+                            clean = false;
+                        }
+                    }
+                    info!("Requeued {} unfinished URLs", unfinished.len());
+                }
+            }
+
+            let c = self.crawler.as_ref();
+            // Image downloads are left for the next run rather than holding up the shutdown.
+            if c.fetch_images && !self.shutdown.is_triggered() {
+                c.process_images(&db).await;
+            }
+
+            let pages = c.len_pages().await;
+            page_controller.save_pages(c).await;
+            links_controller.save_links(c).await;
+            if use_sitemaps && let Err(e) = sitemap_controller.update_orphans().await {
+                error!("Error updating sitemap orphans: {:?}", e);
+                clean = false;
+            }
+            feed_controller.save_feeds(c).await;
+            if let Err(e) = image_controller.save_images(c).await {
+                error!("Error saving images: {:?}", e);
+                clean = false;
+            }
+            c.pages.lock().await.clear();
+            c.outlinks.lock().await.clear();
+            c.backlinks.lock().await.clear();
+            c.images.lock().await.clear();
+            c.image_data.lock().await.clear();
+            c.metadata.lock().await.clear();
+            c.structured_data.lock().await.clear();
+            c.feeds.lock().await.clear();
+            self.events.emit(CrawlEvent::Flushed { pages });
+
+            if let Some(budget) = c.budget_exhausted(&db).await {
+                info!("Crawl budget exhausted: {}. Stopping.", budget);
+
+                let mut db = db.lock().await;
+                match db.get_budget_usage().await {
+                    Ok(usage) => {
+                        info!(
+                            "Final report: {} pages, {} bytes in {}s",
+                            usage.pages,
+                            usage.bytes,
+                            usage.elapsed.as_secs()
+                        );
+                        if let Err(e) = db.save_crawl_report(&usage, &budget).await {
+                            error!("Error saving crawl report: {:?}", e);
+                        }
+                    }
+                    Err(e) => error!("Error reading crawl usage: {:?}", e),
+                }
+                reason = StopReason::BudgetExhausted(budget);
+                break;
+            }
+
+            if self.shutdown.is_triggered() {
+                break;
+            }
+
+            let queued = db.lock().await.get_queue_size().await;
+            match queued {
+                Ok(0) if redis_seed_sources.is_empty() => {
+                    info!("Queue is empty. Stopping.");
+                    reason = StopReason::Completed;
+                    break;
+                }
+                Ok(0) => {
+                    tokio::select! {
+                        _ = sleep(IDLE_POLL_INTERVAL) => {}
+                        _ = self.shutdown.wait() => {}
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Error reading queue size: {:?}", e);
+                    sleep(IDLE_POLL_INTERVAL).await;
+                }
+            }
+        }
+
+        if let Some(dir) = config.report_dir() {
+            match report::build_report(&db).await.and_then(|report| report.write_files(dir, &self.job)) {
+                Ok(paths) => {
+                    for path in paths {
+                        info!("Crawl report written to {}", path.display());
+                    }
+                }
+                Err(e) => error!("Error writing crawl report: {:#}", e),
+            }
+        }
+
+        Ok(CrawlOutcome { reason, clean })
+    }
+
+    // Queues the in-scope sitemap URLs of each seed host whose sitemaps were not read yet.
+    async fn seed_sitemaps(&self, controller: &SitemapController, seeds: &[Seed], known: &mut HashSet<String>) {
+        for root in new_sitemap_roots(seeds.iter().map(|seed| seed.url.as_str()), known) {
+            match get_sitemap_entries(&root).await {
+                Ok(mut entries) => {
+                    entries.retain(|entry| self.scope.allows(&entry.url));
+                    controller.save_sitemap_urls(&entries).await;
+                }
+                Err(e) => error!("Error reading sitemaps for {}: {:?}", root, e),
+            }
+        }
+    }
+}
+
+async fn join_workers(workers: &mut JoinSet<()>) {
+    while let Some(result) = workers.join_next().await {
+        if let Err(e) = result
+            && !e.is_cancelled()
+        {
+            error!("Worker task failed: {:?}", e);
+        }
+    }
+}
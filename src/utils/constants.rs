@@ -3,21 +3,58 @@ pub mod utils {
     
     // Crawler settings
     pub const TIMEOUT: Duration = Duration::from_secs(5);
+    pub const MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
     pub const MAX_SCORE: i32 = 10_000;
     pub const MIN_SCORE: i32 = -1_000;
+    pub const ROBOTS_USER_AGENT: &str = "spider";
+    pub const MAX_SITEMAPS: usize = 50;
+    pub const MAX_SEEDS_PER_DRAIN: usize = 10_000;
+    pub const VISITED_PREFIX: &str = "visited";
+    pub const FEED_MIME_TYPES: [&str; 3] = ["application/rss+xml", "application/atom+xml", "application/rdf+xml"];
 
     // Redis message queues
     pub const SPIDER_QUEUE_KEY: &str = "spider_queue";
+    pub const URL_DEPTHS_KEY: &str = "url_depths";
+    pub const HOST_QUEUE_PREFIX: &str = "host_queue";
+    pub const READY_HOSTS_KEY: &str = "ready_hosts";
+    pub const HOST_DELAYS_KEY: &str = "host_delays";
+    pub const HOST_BUDGETS_KEY: &str = "host_budgets";
+    pub const HOST_DISPATCHED_KEY: &str = "host_dispatched";
+    pub const CRAWL_STATS_KEY: &str = "crawl_stats";
+    pub const CRAWL_REPORT_KEY: &str = "crawl_report";
+    pub const FAILED_URLS_KEY: &str = "failed_urls";
+    pub const FETCH_ATTEMPTS_KEY: &str = "fetch_attempts";
+    pub const FETCH_STATS_KEY: &str = "fetch_stats";
+    pub const FETCH_LATENCIES_KEY: &str = "fetch_latencies";
+    pub const MAX_LATENCY_SAMPLES: usize = 10_000;
+    pub const HOST_BYTES_KEY: &str = "host_bytes";
+    pub const DOMAIN_PAGES_KEY: &str = "domain_pages";
+    pub const DOMAIN_BYTES_KEY: &str = "domain_bytes";
     pub const INDEXER_QUEUE_KEY: &str = "pages_queue";
     pub const SIGNAL_QUEUE_KEY: &str = "signal_queue";
     pub const RESUME_CRAWL: &str = "RESUME_CRAWL";
     pub const MAX_INDEXER_QUEUE_SIZE: usize = 5_000;
+    pub const MIN_INDEXER_QUEUE_SIZE: usize = 2_500;
+    pub const MAX_SIGNALS_PER_DRAIN: usize = 100;
 
     // Redis data keys
     pub const NORMALIZED_URL_PREFIX: &str = "normalized_url";
     pub const PAGE_PREFIX: &str = "page_data";                
+    pub const PAGE_COUNT_KEY: &str = "page_count";
+    pub const PAGE_METADATA_PREFIX: &str = "page_metadata";
+    pub const STRUCTURED_DATA_PREFIX: &str = "structured_data";
     pub const IMAGE_PREFIX: &str = "image_data";              
     pub const PAGE_IMAGES_PREFIX: &str = "page_images";       
+    pub const IMAGE_PAGES_PREFIX: &str = "image_pages";
+    pub const IMAGE_REFS_PREFIX: &str = "image_refs";
+    pub const IMAGE_LRU_KEY: &str = "image_lru";
     pub const BACKLINKS_PREFIX: &str = "backlinks";           
     pub const OUTLINKS_PREFIX: &str = "outlinks";             
+    pub const BACKLINK_EDGES_PREFIX: &str = "backlink_edges";
+    pub const OUTLINK_EDGES_PREFIX: &str = "outlink_edges";
+    pub const SITEMAP_URLS_KEY: &str = "sitemap_urls";
+    pub const SITEMAP_ORPHANS_KEY: &str = "sitemap_orphans";
+    pub const FEED_PREFIX: &str = "feed";
+    pub const FEED_ITEMS_PREFIX: &str = "feed_items";
+    pub const FEED_SCHEDULE_KEY: &str = "feed_schedule";
 }
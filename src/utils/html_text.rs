@@ -0,0 +1,29 @@
+use scraper::ElementRef;
+use scraper::node::Node;
+
+pub const SKIPPED_TEXT_TAGS: [&str; 5] = ["script", "style", "noscript", "template", "svg"];
+
+pub fn visible_text(element: ElementRef) -> String {
+    let mut text = String::new();
+
+    for child in element.children() {
+        match child.value() {
+            Node::Text(t) => {
+                text.push_str(t);
+                text.push(' ');
+            }
+            Node::Element(e) if !SKIPPED_TEXT_TAGS.contains(&e.name()) => {
+                if let Some(child_element) = ElementRef::wrap(child) {
+                    text.push_str(&visible_text(child_element));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    text
+}
+
+pub fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
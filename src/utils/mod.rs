@@ -1,5 +1,6 @@
 pub mod constants;
 pub mod parse;
+pub mod html_text;
 pub mod is_valid_url;
 pub mod normalize_url;
 pub mod strip_url;
@@ -30,11 +30,7 @@ pub fn normalize_url(raw_url: &str) -> Result<String, NormalizeUrlError> {
 
     let host = u.host_str().ok_or(NormalizeUrlError::MissingHost)?;
 
-    let host = if host.starts_with("www.") {
-        &host[4..]
-    } else {
-        host
-    };
+    let host = host.strip_prefix("www.").unwrap_or(host);
 
     let mut normalized_url = host.to_string();
 
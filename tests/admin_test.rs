@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use axum::http::HeaderMap;
+    use tokio::time::timeout;
+    use spider::admin::authorized;
+    use spider::config::SpiderConfig;
+    use spider::crawler::backpressure::{Backpressure, BackpressureState, Watermarks};
+    use spider::crawler::events::{CrawlEvent, CrawlEvents};
+    use spider::crawler::scope::{ScopeAction, ScopeRule, ScopeRules};
+
+    struct TestCase {
+        header: Option<&'static str>,
+        expected: bool,
+    }
+
+    #[test]
+    fn test_authorized() {
+        let cases = vec![
+            TestCase { header: Some("Bearer s3cret"), expected: true },
+            TestCase { header: Some("Bearer s3cre"), expected: false },
+            TestCase { header: Some("Bearer s3cret "), expected: false },
+            TestCase { header: Some("s3cret"), expected: false },
+            TestCase { header: Some("Basic s3cret"), expected: false },
+            TestCase { header: None, expected: false },
+        ];
+
+        for case in cases {
+            let mut headers = HeaderMap::new();
+            if let Some(header) = case.header {
+                headers.insert("authorization", header.parse().unwrap());
+            }
+            assert_eq!(authorized(&headers, "s3cret"), case.expected, "{:?}", case.header);
+        }
+
+        // An empty token never authorizes.
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", "Bearer ".parse().unwrap());
+        assert!(!authorized(&headers, ""));
+    }
+
+    #[test]
+    fn test_scope_rules() {
+        let scope = ScopeRules::new();
+        assert!(scope.allows("https://example.com/a"));
+
+        scope.add(ScopeRule::new(r"\.pdf$", ScopeAction::Deny).unwrap());
+        assert!(!scope.allows("https://example.com/report.pdf"));
+        assert!(scope.allows("https://other.org/"));
+
+        scope.add(ScopeRule::new(r"^https://example\.com/", ScopeAction::Allow).unwrap());
+        assert!(scope.allows("https://example.com/a"));
+        assert!(!scope.allows("https://other.org/"));
+        // Deny wins over allow.
+        assert!(!scope.allows("https://example.com/report.pdf"));
+
+        // Re-adding a pattern replaces its action.
+        scope.add(ScopeRule::new(r"\.pdf$", ScopeAction::Allow).unwrap());
+        assert_eq!(scope.list().len(), 2);
+        assert!(scope.allows("https://other.org/report.pdf"));
+
+        assert!(scope.remove(r"\.pdf$"));
+        assert!(!scope.remove(r"\.pdf$"));
+        assert_eq!(scope.list().len(), 1);
+
+        assert!(ScopeRule::new("(", ScopeAction::Deny).is_err());
+        assert_eq!("Allow".parse::<ScopeAction>(), Ok(ScopeAction::Allow));
+        assert!("maybe".parse::<ScopeAction>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_manual_pause() {
+        let backpressure = Backpressure::new(Watermarks::new(10, 5).unwrap());
+
+        backpressure.pause();
+        assert!(backpressure.is_paused());
+        // The indexer draining does not lift a manual pause.
+        backpressure.update(0, false);
+        assert!(backpressure.is_paused());
+
+        let waiter = {
+            let backpressure = backpressure.clone();
+            tokio::spawn(async move { backpressure.wait_resumed().await })
+        };
+        tokio::task::yield_now().await;
+
+        backpressure.resume();
+        assert!(!backpressure.is_paused());
+        timeout(Duration::from_secs(1), waiter).await.expect("waiter was not woken").unwrap();
+
+        // Resuming also overrides a watermark pause.
+        backpressure.update(10, false);
+        assert!(backpressure.is_paused());
+        backpressure.resume();
+        assert_eq!(backpressure.state(), BackpressureState::Overridden);
+    }
+
+    #[tokio::test]
+    async fn test_requested_pause_emits_events() {
+        let backpressure = Backpressure::default();
+        let events = CrawlEvents::default();
+        let mut stream = events.subscribe();
+
+        backpressure.request_pause(&events);
+        assert!(backpressure.is_held());
+        backpressure.request_resume(&events);
+        assert!(!backpressure.is_held());
+
+        assert_eq!(stream.next().await, Some(CrawlEvent::Paused));
+        assert_eq!(stream.next().await, Some(CrawlEvent::Resumed));
+    }
+
+    #[test]
+    fn test_masked_config() {
+        let mut config = SpiderConfig::default();
+        config.admin.token = "s3cret".to_string();
+
+        assert_eq!(config.admin_addr().unwrap(), Some("127.0.0.1:9101".parse().unwrap()));
+        assert_eq!(config.masked().admin.token, "********");
+        assert!(!config.to_toml().unwrap().contains("s3cret"));
+
+        config.admin.token.clear();
+        assert_eq!(config.admin_addr().unwrap(), None);
+        config.admin.listen_addr = "localhost".to_string();
+        assert!(config.validate().is_err());
+    }
+}
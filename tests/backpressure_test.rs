@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use spider::crawler::backpressure::{Backpressure, BackpressureState, Watermarks};
+
+    struct TestCase {
+        state: BackpressureState,
+        queue_len: usize,
+        resume_signal: bool,
+        expected: BackpressureState,
+    }
+
+    #[test]
+    fn test_watermark_transitions() {
+        use BackpressureState::*;
+
+        let watermarks = Watermarks::new(100, 40).expect("valid watermarks");
+
+        let test_cases = vec![
+            TestCase { state: Running, queue_len: 99, resume_signal: false, expected: Running },
+            TestCase { state: Running, queue_len: 100, resume_signal: false, expected: Paused },
+            // Between the watermarks the current state is kept.
+            TestCase { state: Paused, queue_len: 60, resume_signal: false, expected: Paused },
+            TestCase { state: Paused, queue_len: 40, resume_signal: false, expected: Running },
+            TestCase { state: Paused, queue_len: 150, resume_signal: true, expected: Overridden },
+            TestCase { state: Overridden, queue_len: 200, resume_signal: false, expected: Overridden },
+            TestCase { state: Overridden, queue_len: 10, resume_signal: false, expected: Running },
+            TestCase { state: Running, queue_len: 10, resume_signal: true, expected: Running },
+        ];
+
+        for case in test_cases {
+            assert_eq!(
+                watermarks.next_state(case.state, case.queue_len, case.resume_signal),
+                case.expected,
+                "from {:?} with {} queued",
+                case.state,
+                case.queue_len
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_watermarks() {
+        assert!(Watermarks::new(0, 0).is_err());
+        assert!(Watermarks::new(100, 100).is_err());
+        assert!(Watermarks::new(100, 150).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume() {
+        let backpressure = Backpressure::new(Watermarks::new(10, 5).unwrap());
+
+        assert_eq!(backpressure.update(12, false), BackpressureState::Paused);
+        assert!(backpressure.is_paused());
+
+        let waiter = {
+            let backpressure = backpressure.clone();
+            tokio::spawn(async move { backpressure.wait_resumed().await })
+        };
+        tokio::task::yield_now().await;
+
+        assert_eq!(backpressure.update(3, false), BackpressureState::Running);
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter was not woken")
+            .unwrap();
+    }
+}
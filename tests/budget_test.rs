@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use spider::crawler::budget::{registrable_domain, BudgetUsage, CrawlBudget, HostUsage};
+
+    #[test]
+    fn test_registrable_domain() {
+        let tests = [
+            ("example.com", "example.com"),
+            ("www.example.com", "example.com"),
+            ("blog.news.example.co.uk", "example.co.uk"),
+            ("example.co.uk", "example.co.uk"),
+            ("shop.example.com:8080", "example.com"),
+            ("localhost", "localhost"),
+            ("192.168.1.20", "192.168.1.20"),
+        ];
+
+        for (host, expected) in tests {
+            assert_eq!(registrable_domain(host), expected, "Test '{}' FAILED", host);
+        }
+    }
+
+    #[test]
+    fn test_global_budget() {
+        let budget = CrawlBudget {
+            max_pages: 100,
+            max_bytes: 1_000,
+            max_duration: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+
+        let usage = |pages, bytes, secs| BudgetUsage { pages, bytes, elapsed: Duration::from_secs(secs) };
+
+        assert_eq!(budget.exceeded(&usage(99, 999, 59)), None);
+        assert_eq!(budget.exceeded(&usage(100, 0, 0)), Some("page budget of 100 reached".to_string()));
+        assert_eq!(budget.exceeded(&usage(0, 1_500, 0)), Some("byte budget of 1000 reached".to_string()));
+        assert_eq!(budget.exceeded(&usage(0, 0, 60)), Some("time budget of 60s reached".to_string()));
+
+        assert_eq!(CrawlBudget::default().exceeded(&usage(u64::MAX, u64::MAX, u64::MAX)), None, "zero means unlimited");
+    }
+
+    #[test]
+    fn test_host_budget() {
+        let budget = CrawlBudget {
+            host_max_bytes: 500,
+            host_byte_budgets: HashMap::from([("media.example.com".to_string(), 5_000)]),
+            domain_max_pages: 10,
+            domain_page_budgets: HashMap::from([("big.org".to_string(), 1_000)]),
+            ..Default::default()
+        };
+
+        let usage = |host_bytes, domain_pages| HostUsage { host_bytes, domain_pages, domain_bytes: 0 };
+
+        assert_eq!(budget.host_exceeded("example.com", &usage(499, 9)), None);
+        assert!(budget.host_exceeded("example.com", &usage(500, 0)).is_some_and(|r| r.contains("host example.com")));
+        assert_eq!(budget.host_exceeded("media.example.com", &usage(4_000, 0)), None);
+        assert!(budget.host_exceeded("blog.example.com", &usage(0, 10)).is_some_and(|r| r.contains("domain example.com")));
+        assert_eq!(budget.host_exceeded("www.big.org", &usage(0, 999)), None);
+    }
+}
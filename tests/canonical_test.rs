@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use spider::crawler::canonical::CanonicalPolicy;
+
+    #[test]
+    fn test_dedupe_key() {
+        struct TestCase<'a> {
+            name: &'a str,
+            policy: CanonicalPolicy,
+            normalized_url: &'a str,
+            canonical: &'a str,
+            expected: &'a str,
+        }
+
+        let tests = [
+            TestCase {
+                name: "no canonical",
+                policy: CanonicalPolicy::SameHost,
+                normalized_url: "example.com/a",
+                canonical: "",
+                expected: "example.com/a",
+            },
+            TestCase {
+                name: "same host canonical",
+                policy: CanonicalPolicy::SameHost,
+                normalized_url: "example.com/a",
+                canonical: "https://www.example.com/b/",
+                expected: "example.com/b",
+            },
+            TestCase {
+                name: "cross host canonical ignored by default",
+                policy: CanonicalPolicy::SameHost,
+                normalized_url: "example.com/a",
+                canonical: "https://other.com/a",
+                expected: "example.com/a",
+            },
+            TestCase {
+                name: "cross host canonical allowed",
+                policy: CanonicalPolicy::AnyHost,
+                normalized_url: "example.com/a",
+                canonical: "https://other.com/a",
+                expected: "other.com/a",
+            },
+            TestCase {
+                name: "policy off",
+                policy: CanonicalPolicy::Ignore,
+                normalized_url: "example.com/a",
+                canonical: "https://example.com/b",
+                expected: "example.com/a",
+            },
+            TestCase {
+                name: "invalid canonical",
+                policy: CanonicalPolicy::AnyHost,
+                normalized_url: "example.com/a",
+                canonical: "ftp://example.com/b",
+                expected: "example.com/a",
+            },
+        ];
+
+        for test in tests {
+            let result = test.policy.dedupe_key(test.normalized_url, test.canonical);
+            assert_eq!(result, test.expected, "Test '{}' FAILED: expected '{}', got '{}'", test.name, test.expected, result);
+        }
+    }
+
+    #[test]
+    fn test_parse_canonical_policy() {
+        assert_eq!("off".parse::<CanonicalPolicy>(), Ok(CanonicalPolicy::Ignore));
+        assert_eq!("Same-Host".parse::<CanonicalPolicy>(), Ok(CanonicalPolicy::SameHost));
+        assert_eq!("any-host".parse::<CanonicalPolicy>(), Ok(CanonicalPolicy::AnyHost));
+        assert!("sometimes".parse::<CanonicalPolicy>().is_err());
+    }
+}
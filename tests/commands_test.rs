@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use spider::crawler::budget::BudgetUsage;
+    use spider::database::CrawlStatus;
+    use spider::pages::FailedUrl;
+
+    #[test]
+    fn test_failed_url_round_trip() {
+        let failure = FailedUrl::new(
+            "example.com/a".to_string(),
+            "https://example.com/a".to_string(),
+            3.0,
+            "connection refused".to_string(),
+        );
+
+        let parsed = FailedUrl::from_json(&failure.to_json()).expect("failed to parse failure");
+        assert_eq!(parsed, failure);
+
+        assert!(FailedUrl::from_json("{}").is_err());
+        assert!(FailedUrl::from_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_status_display() {
+        let status = CrawlStatus {
+            queued_urls: 12,
+            ready_hosts: 3,
+            visited: 40,
+            pages_stored: 38,
+            failed: 2,
+            usage: BudgetUsage { pages: 40, bytes: 1024, elapsed: Duration::from_secs(90) },
+            stop_reason: Some("page budget of 40 reached".to_string()),
+            ..Default::default()
+        };
+
+        let printed = status.to_string();
+        assert!(printed.contains("Queued URLs:      12 (3 hosts)"));
+        assert!(printed.contains("Failed URLs:      2"));
+        assert!(printed.contains("40 pages, 1024 bytes in 90s"));
+        assert!(printed.ends_with("Stopped:          page budget of 40 reached"));
+
+        let running = CrawlStatus::default().to_string();
+        assert!(running.ends_with("Stopped:          no"));
+    }
+}
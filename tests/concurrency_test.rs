@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use spider::crawler::concurrency::Concurrency;
+
+    #[tokio::test]
+    async fn test_set_limit() {
+        let concurrency = Concurrency::new(2);
+        assert_eq!(concurrency.available(), 2);
+
+        assert_eq!(concurrency.set_limit(4), 2);
+        assert_eq!(concurrency.limit(), 4);
+        assert_eq!(concurrency.available(), 4);
+
+        assert_eq!(concurrency.set_limit(1), 4);
+        assert_eq!(concurrency.available(), 1);
+
+        // Permits held by workers are taken back once they are released.
+        concurrency.set_limit(3);
+        let first = concurrency.acquire().await.expect("permit");
+        let second = concurrency.acquire().await.expect("permit");
+        concurrency.set_limit(1);
+        assert_eq!(concurrency.available(), 0);
+
+        drop(first);
+        drop(second);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(concurrency.available(), 1);
+    }
+}
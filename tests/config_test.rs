@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use spider::config::{ConfigArgs, SpiderConfig};
+    use spider::crawler::canonical::CanonicalPolicy;
+    use spider::crawler::priority::PriorityPolicyKind;
+    use spider::crawler::seeds::SeedSource;
+
+    const TOML: &str = r#"
+        [scope]
+        starting_url = "https://example.com"
+        seeds = ["redis:seeds", "urls.csv"]
+        canonical_dedupe = "any-host"
+
+        [frontier]
+        priority_policy = "opic"
+        priority_boosts = { "/news/" = 5.0 }
+
+        [politeness]
+        host_delay_ms = 250
+        host_weights = { "WWW.Example.com" = 2.0 }
+
+        [budgets]
+        max_pages = 10
+        host_page_budgets = { "*" = 100, "big.com" = 5000 }
+
+        [storage]
+        redis_password = "secret"
+    "#;
+
+    #[test]
+    fn test_from_toml() {
+        let config = SpiderConfig::from_toml(TOML).expect("failed to parse config");
+
+        assert_eq!(config.scope.canonical_dedupe, CanonicalPolicy::AnyHost);
+        assert_eq!(config.frontier.priority_policy, PriorityPolicyKind::Opic);
+        assert_eq!(config.budgets.max_pages, 10);
+        // Unset values keep their defaults.
+        assert_eq!(config.crawl.max_concurrency, 10);
+        assert!(config.politeness.respect_noindex);
+
+        assert_eq!(
+            config.seed_sources(),
+            vec![
+                SeedSource::Url("https://example.com".to_string()),
+                SeedSource::RedisList("seeds".to_string()),
+                SeedSource::File("urls.csv".into()),
+            ]
+        );
+
+        let frontier = config.frontier_policy();
+        assert_eq!(frontier.host_delay, Duration::from_millis(250));
+        assert_eq!(frontier.host_weights, HashMap::from([("example.com".to_string(), 2.0)]));
+        assert_eq!(frontier.host_max_pages, 100);
+        assert_eq!(frontier.host_page_budgets["big.com"], 5000);
+
+        assert_eq!(config.url_boosts().len(), 1);
+        assert!(config.validate().is_ok());
+
+        assert!(SpiderConfig::from_toml("[budget]\nmax_pages = 1").is_err());
+        assert!(SpiderConfig::from_toml("[budgets]\nmax_pages = \"abc\"").is_err());
+        assert!(SpiderConfig::from_toml("[scope]\ncanonical_dedupe = \"sometimes\"").is_err());
+    }
+
+    #[test]
+    fn test_overrides() {
+        let mut config = SpiderConfig::from_toml(TOML).unwrap();
+
+        let args = ConfigArgs {
+            max_pages: Some(50),
+            seeds: Some("https://a.com/,stdin".to_string()),
+            host_weights: Some(String::new()),
+            domain_byte_budgets: Some("*=1000;news.org=50".to_string()),
+            redis_password: Some(String::new()),
+            ..Default::default()
+        };
+        config.apply(&args).expect("failed to apply overrides");
+
+        assert_eq!(config.budgets.max_pages, 50);
+        assert_eq!(
+            config.scope.seeds,
+            vec![SeedSource::Url("https://a.com/".to_string()), SeedSource::Stdin]
+        );
+        // Empty values leave the file's settings alone.
+        assert_eq!(config.politeness.host_weights.len(), 1);
+        assert_eq!(config.storage.redis_password, "secret");
+
+        let budget = config.budget();
+        assert_eq!(budget.max_pages, 50);
+        assert_eq!(budget.domain_max_bytes, 1000);
+        assert_eq!(budget.domain_byte_budgets["news.org"], 50);
+
+        let bad = ConfigArgs {
+            host_weights: Some("example.com".to_string()),
+            ..Default::default()
+        };
+        assert!(config.apply(&bad).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut config = SpiderConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.crawl.max_concurrency = 0;
+        config.storage.indexer_low_watermark = config.storage.indexer_high_watermark;
+        config.scope.starting_url = Some("not a url".to_string());
+        config.frontier.priority_boosts.insert("(".to_string(), 1.0);
+
+        let errors = config.validate().unwrap_err();
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+    }
+
+    #[test]
+    fn test_to_toml_round_trip() {
+        let config = SpiderConfig::from_toml(TOML).unwrap();
+        let printed = config.to_toml().expect("failed to print config");
+
+        assert!(printed.contains("redis_password = \"********\""));
+        assert!(!printed.contains("secret"));
+
+        let reparsed = SpiderConfig::from_toml(&printed).expect("printed config does not parse");
+        assert_eq!(reparsed.scope.seeds, config.scope.seeds);
+        assert_eq!(reparsed.budgets.host_page_budgets, config.budgets.host_page_budgets);
+    }
+}
@@ -0,0 +1,272 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use lopdf::content::{Content, Operation};
+    use lopdf::{dictionary, Document, Object, Stream};
+    use spider::crawler::content_handler::{ContentHandler, ContentHandlers, HandledContent};
+    use spider::crawler::get_feed_content::get_feed_content;
+    use spider::crawler::get_page_data::PageData;
+    use spider::crawler::get_pdf_content::get_pdf_content;
+    use spider::crawler::robots_directives::RobotsDirectives;
+    use spider::pages::create_page;
+
+    fn page_data(content_type: &str, body: &str, bytes: Vec<u8>) -> PageData {
+        PageData {
+            body: body.to_string(),
+            bytes,
+            status_code: 200,
+            redirected: false,
+            content_type: content_type.to_string(),
+            robots: RobotsDirectives::default(),
+        }
+    }
+
+    fn build_pdf(text: &str) -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Courier",
+        });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+        let content = Content {
+            operations: vec![
+                Operation::new("BT", vec![]),
+                Operation::new("Tf", vec!["F1".into(), 24.into()]),
+                Operation::new("Td", vec![100.into(), 600.into()]),
+                Operation::new("Tj", vec![Object::string_literal(text)]),
+                Operation::new("ET", vec![]),
+            ],
+        };
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+        });
+        doc.objects.insert(pages_id, Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => vec![page_id.into()],
+            "Count" => 1,
+            "Resources" => resources_id,
+            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+        }));
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        let info_id = doc.add_object(dictionary! {
+            "Title" => Object::string_literal("Annual Report"),
+            "Author" => Object::string_literal("Jane Doe"),
+            "Subject" => Object::string_literal("Results for the year"),
+            "Keywords" => Object::string_literal("finance, report"),
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc.trailer.set("Info", info_id);
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_resolve_content_handlers() {
+        let handlers = ContentHandlers::default();
+
+        assert!(handlers.accepts("text/html; charset=UTF-8"));
+        assert!(handlers.accepts("application/xhtml+xml"));
+        assert!(handlers.accepts("text/plain"));
+        assert!(handlers.accepts("application/pdf"));
+        assert!(handlers.accepts("Application/RSS+XML"));
+        assert!(handlers.accepts("text/xml"));
+        assert!(!handlers.accepts("image/png"));
+        assert!(!handlers.accepts(""));
+    }
+
+    #[test]
+    fn test_register_custom_handler() {
+        struct JsonHandler;
+
+        impl ContentHandler for JsonHandler {
+            fn handle(&self, _raw_url: &str, normalized_url: &str, data: &PageData) -> Result<HandledContent, String> {
+                let page = create_page(normalized_url.to_string(), data.body.clone(), data.content_type.clone(), 200);
+                Ok(HandledContent::new(page))
+            }
+        }
+
+        let mut handlers = ContentHandlers::default();
+        assert!(!handlers.accepts("application/json"));
+
+        handlers.register("application/json", Arc::new(JsonHandler));
+        handlers.register("image/*", Arc::new(JsonHandler));
+
+        let data = page_data("application/json", "{}", Vec::new());
+        let content = handlers
+            .resolve("application/json")
+            .expect("handler not registered")
+            .handle("https://example.com/data.json", "example.com/data.json", &data)
+            .expect("handler failed");
+
+        assert_eq!(content.page.normalized_url, "example.com/data.json");
+        assert_eq!(content.page.content_type, "application/json");
+        assert!(handlers.accepts("image/webp"));
+    }
+
+    #[test]
+    fn test_html_handler() {
+        let html = r#"<html><head>
+                <title>Example</title>
+                <meta name="robots" content="noindex">
+                <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+            </head><body>
+                <main><p>Some main content here.</p></main>
+                <a href="/next" rel="nofollow">Next</a>
+                <img src="/logo.png" alt="Logo">
+            </body></html>"#;
+        let handlers = ContentHandlers::default();
+
+        for content_type in ["text/html; charset=UTF-8", "application/xhtml+xml"] {
+            let data = page_data(content_type, html, Vec::new());
+            let content = handlers
+                .resolve(content_type)
+                .unwrap()
+                .handle("https://example.com/", "example.com", &data)
+                .expect("handler failed");
+
+            assert!(content.links.is_empty());
+            assert!(content.link_edges.get("https://example.com/next").is_some_and(|e| e.rel.contains("nofollow")));
+            assert_eq!(content.images.len(), 1);
+            assert_eq!(content.feeds, vec!["https://example.com/feed.xml"]);
+            assert!(content.robots.noindex);
+            assert_eq!(content.metadata.map(|m| m.title), Some("Example".to_string()));
+            assert!(content.page.text.contains("Some main content here."));
+        }
+
+        // A handler registered for text/html replaces the built-in pipeline.
+        struct RawHtmlHandler;
+
+        impl ContentHandler for RawHtmlHandler {
+            fn handle(&self, _raw_url: &str, normalized_url: &str, data: &PageData) -> Result<HandledContent, String> {
+                let page = create_page(normalized_url.to_string(), data.body.clone(), data.content_type.clone(), 200);
+                Ok(HandledContent::new(page))
+            }
+        }
+
+        let mut handlers = ContentHandlers::default();
+        handlers.register("text/html", Arc::new(RawHtmlHandler));
+        let data = page_data("text/html", html, Vec::new());
+        let content = handlers.resolve("text/html").unwrap().handle("https://example.com/", "example.com", &data).unwrap();
+        assert!(content.link_edges.is_empty());
+        assert!(content.metadata.is_none());
+    }
+
+    #[test]
+    fn test_plain_text_handler() {
+        let handlers = ContentHandlers::default();
+        let data = page_data("text/plain", "Hello,\n\n   plain   world.\n", Vec::new());
+
+        let content = handlers
+            .resolve("text/plain")
+            .unwrap()
+            .handle("https://example.com/notes.txt", "example.com/notes.txt", &data)
+            .expect("handler failed");
+
+        assert_eq!(content.page.text, "Hello, plain world.");
+        assert_eq!(content.page.content_type, "text/plain");
+        assert!(content.links.is_empty());
+    }
+
+    #[test]
+    fn test_get_pdf_content() {
+        let bytes = build_pdf("Quarterly revenue grew");
+
+        let pdf = get_pdf_content(&bytes).expect("failed to parse PDF");
+        assert_eq!(pdf.page_count, 1);
+        assert!(pdf.text.contains("Quarterly revenue grew"), "unexpected text: {:?}", pdf.text);
+        assert_eq!(pdf.title, "Annual Report");
+        assert_eq!(pdf.author, "Jane Doe");
+        assert_eq!(pdf.subject, "Results for the year");
+        assert_eq!(pdf.keywords, vec!["finance", "report"]);
+
+        let data = page_data("application/pdf", "", bytes);
+        let content = ContentHandlers::default()
+            .resolve("application/pdf")
+            .unwrap()
+            .handle("https://example.com/report.pdf", "example.com/report.pdf", &data)
+            .expect("handler failed");
+
+        assert_eq!(content.page.content_type, "application/pdf");
+        assert_eq!(content.metadata.map(|m| m.title), Some("Annual Report".to_string()));
+
+        assert!(get_pdf_content(b"not a pdf").is_err());
+    }
+
+    #[test]
+    fn test_get_feed_content() {
+        struct TestCase<'a> {
+            name: &'a str,
+            xml: &'a str,
+            title: &'a str,
+            links: Vec<&'a str>,
+        }
+
+        let tests = [
+            TestCase {
+                name: "rss 2.0",
+                xml: r#"<?xml version="1.0"?>
+                    <rss version="2.0"><channel>
+                        <title>Example News</title>
+                        <link>https://example.com/</link>
+                        <item><title>First</title><link>https://example.com/first</link></item>
+                        <item><title>Second</title><link>/second</link></item>
+                        <item><title>Third</title><guid>https://example.com/third</guid></item>
+                        <item><title>Opaque</title><guid isPermaLink="false">abc-123</guid></item>
+                    </channel></rss>"#,
+                title: "Example News",
+                links: vec!["https://example.com/first", "https://example.com/second", "https://example.com/third"],
+            },
+            TestCase {
+                name: "rss 1.0",
+                xml: r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns="http://purl.org/rss/1.0/">
+                        <channel><title>RDF Feed</title></channel>
+                        <item><title>One</title><link>https://example.com/one</link></item>
+                    </rdf:RDF>"#,
+                title: "RDF Feed",
+                links: vec!["https://example.com/one"],
+            },
+            TestCase {
+                name: "atom",
+                xml: r#"<feed xmlns="http://www.w3.org/2005/Atom">
+                        <title>Example Blog</title>
+                        <link href="https://example.com/"/>
+                        <entry>
+                            <title>Post</title>
+                            <link rel="edit" href="/edit/1"/>
+                            <link rel="alternate" href="/posts/1"/>
+                        </entry>
+                        <entry><title>Other</title><link href="https://example.com/posts/2"/></entry>
+                    </feed>"#,
+                title: "Example Blog",
+                links: vec!["https://example.com/posts/1", "https://example.com/posts/2"],
+            },
+            TestCase {
+                name: "plain xml",
+                xml: r#"<catalog><book><title>Dune</title></book></catalog>"#,
+                title: "",
+                links: vec![],
+            },
+        ];
+
+        for test in tests {
+            let feed = get_feed_content(test.xml, "https://example.com/feed.xml")
+                .unwrap_or_else(|e| panic!("Test '{}' FAILED: {}", test.name, e));
+            assert_eq!(feed.title, test.title, "Test '{}' FAILED", test.name);
+            assert_eq!(feed.links, test.links, "Test '{}' FAILED", test.name);
+        }
+
+        assert!(get_feed_content("<rss><channel>", "https://example.com/feed.xml").is_err());
+    }
+}
@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use reqwest::header::{IF_MODIFIED_SINCE, IF_NONE_MATCH};
+    use scraper::Html;
+    use spider::crawler::get_urls_from_html::get_feed_links_from_document;
+    use spider::crawler::poll_feeds::conditional_headers;
+    use spider::pages::Feed;
+
+    #[test]
+    fn test_get_feed_links_from_document() {
+        let document = Html::parse_document(r#"
+            <html>
+                <head>
+                    <base href="https://example.com/blog/">
+                    <link rel="alternate" type="application/rss+xml" title="RSS" href="feed.xml">
+                    <link rel="alternate" type="application/atom+xml" href="https://example.com/atom.xml">
+                    <link rel="Alternate Home" type="Application/RSS+XML" href="/comments.rss">
+                    <link rel="alternate" type="application/rss+xml" href="feed.xml">
+                    <link rel="alternate" hreflang="de" type="text/html" href="/de/">
+                    <link rel="stylesheet" type="application/rss+xml" href="/not-a-feed.xml">
+                    <link rel="alternate" type="application/rss+xml" href="javascript:void(0)">
+                </head>
+                <body></body>
+            </html>
+        "#);
+
+        assert_eq!(
+            get_feed_links_from_document(&document, "https://example.com/blog/post"),
+            vec![
+                "https://example.com/blog/feed.xml",
+                "https://example.com/atom.xml",
+                "https://example.com/comments.rss",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_feed_hash_roundtrip() {
+        let mut feed = Feed::new("example.com/feed.xml".to_string(), "https://example.com/feed.xml".to_string());
+        assert_eq!(Feed::from_hash(&feed.to_hash()), Ok(feed.clone()));
+
+        feed.etag = "\"abc123\"".to_string();
+        feed.last_modified = "Wed, 01 May 2024 10:00:00 GMT".to_string();
+        feed.last_polled = Some(Utc.with_ymd_and_hms(2024, 5, 1, 10, 5, 0).unwrap());
+        assert_eq!(Feed::from_hash(&feed.to_hash()), Ok(feed));
+
+        assert!(Feed::from_hash(&Default::default()).is_err());
+    }
+
+    #[test]
+    fn test_conditional_headers() {
+        let mut feed = Feed::new("example.com/feed.xml".to_string(), "https://example.com/feed.xml".to_string());
+        assert!(conditional_headers(&feed).is_empty());
+
+        feed.etag = "W/\"abc123\"".to_string();
+        feed.last_modified = "Wed, 01 May 2024 10:00:00 GMT".to_string();
+
+        let headers = conditional_headers(&feed);
+        assert_eq!(headers[IF_NONE_MATCH], "W/\"abc123\"");
+        assert_eq!(headers[IF_MODIFIED_SINCE], "Wed, 01 May 2024 10:00:00 GMT");
+    }
+}
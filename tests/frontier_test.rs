@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use spider::database::frontier::{host_of, parse_host_values, FrontierPolicy};
+
+    #[test]
+    fn test_host_of() {
+        assert_eq!(host_of("example.com/a/b"), "example.com");
+        assert_eq!(host_of("example.com"), "example.com");
+        assert_eq!(host_of("sub.example.com:8080/x"), "sub.example.com:8080");
+    }
+
+    #[test]
+    fn test_parse_host_values() {
+        let (default, weights) = parse_host_values::<f64>("Example.com=2; www.news.org=0.5 ;").expect("failed to parse weights");
+        assert_eq!(default, None);
+        assert_eq!(weights, HashMap::from([("example.com".to_string(), 2.0), ("news.org".to_string(), 0.5)]));
+
+        let (default, budgets) = parse_host_values::<usize>("*=100;big.com=5000").expect("failed to parse budgets");
+        assert_eq!(default, Some(100));
+        assert_eq!(budgets["big.com"], 5000);
+
+        assert!(parse_host_values::<usize>("").unwrap().1.is_empty());
+        assert!(parse_host_values::<usize>("big.com").is_err());
+        assert!(parse_host_values::<usize>("big.com=-1").is_err());
+        assert!(parse_host_values::<f64>("=2").is_err());
+    }
+
+    #[test]
+    fn test_frontier_policy() {
+        let policy = FrontierPolicy {
+            host_delay: Duration::from_millis(1000),
+            host_weights: HashMap::from([("fast.com".to_string(), 4.0), ("slow.com".to_string(), 0.5)]),
+            host_max_pages: 100,
+            host_page_budgets: HashMap::from([("big.com".to_string(), 5000)]),
+        };
+
+        assert_eq!(policy.delay_for("fast.com"), Duration::from_millis(250));
+        assert_eq!(policy.delay_for("slow.com"), Duration::from_millis(2000));
+        assert_eq!(policy.delay_for("other.com"), Duration::from_millis(1000));
+
+        assert_eq!(policy.page_budget_for("big.com"), 5000);
+        assert_eq!(policy.page_budget_for("other.com"), 100);
+        assert_eq!(FrontierPolicy::default().page_budget_for("other.com"), 0);
+    }
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+    use spider::crawler::get_image_data::{analyze_image, hamming_distance, MAX_DECODE_DIMENSION};
+    use std::io::Cursor;
+
+    fn gradient(width: u32, height: u32, reversed: bool) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            let value = ((x * 255) / width.max(1)) as u8;
+            let value = if reversed { 255 - value } else { value };
+            Rgb([value, value / 2, ((y * 255) / height.max(1)) as u8])
+        }))
+    }
+
+    fn encode(image: &DynamicImage, format: ImageFormat) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        image.write_to(&mut Cursor::new(&mut bytes), format).expect("failed to encode image");
+        bytes
+    }
+
+    #[test]
+    fn test_analyze_image() {
+        let bytes = encode(&gradient(64, 32, false), ImageFormat::Png);
+
+        // The header claims JPEG, but sniffing the bytes wins.
+        let data = analyze_image(&bytes, "image/jpeg; charset=binary");
+
+        assert_eq!(data.mime_type, "image/png");
+        assert_eq!(data.byte_size, bytes.len());
+        assert_eq!(data.width, Some(64));
+        assert_eq!(data.height, Some(32));
+        assert!(data.dhash.is_some());
+        assert_eq!(data.dhash_hex().len(), 16);
+    }
+
+    #[test]
+    fn test_analyze_undecodable_image() {
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10"></svg>"#;
+        let data = analyze_image(svg, "image/svg+xml");
+
+        assert_eq!(data.mime_type, "image/svg+xml");
+        assert_eq!(data.byte_size, svg.len());
+        assert_eq!(data.width, None);
+        assert_eq!(data.dhash, None);
+        assert_eq!(data.to_hash()["dhash"], "");
+    }
+
+    #[test]
+    fn test_analyze_oversized_image() {
+        let width = MAX_DECODE_DIMENSION + 1;
+        let bytes = encode(&gradient(width, 2, false), ImageFormat::Png);
+        let data = analyze_image(&bytes, "image/png");
+
+        assert_eq!(data.width, Some(width));
+        assert_eq!(data.height, Some(2));
+        assert_eq!(data.dhash, None);
+    }
+
+    #[test]
+    fn test_dhash_near_duplicates() {
+        let original = analyze_image(&encode(&gradient(200, 100, false), ImageFormat::Png), "");
+        let resized = analyze_image(&encode(&gradient(100, 50, false), ImageFormat::Jpeg), "");
+        let different = analyze_image(&encode(&gradient(200, 100, true), ImageFormat::Png), "");
+
+        let original_hash = original.dhash.expect("missing hash");
+        let resized_hash = resized.dhash.expect("missing hash");
+        let different_hash = different.dhash.expect("missing hash");
+
+        assert_eq!(resized.mime_type, "image/jpeg");
+        assert!(hamming_distance(original_hash, resized_hash) <= 5);
+        assert!(hamming_distance(original_hash, different_hash) > 20);
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use spider::crawler::get_images_from_html::{parse_srcset, SrcsetCandidate};
+    use spider::crawler::get_urls_from_html::get_urls_from_html;
+
+    #[test]
+    fn test_parse_srcset() {
+        struct TestCase<'a> {
+            name: &'a str,
+            input: &'a str,
+            expected: Vec<(&'a str, &'a str)>,
+        }
+
+        let tests = [
+            TestCase {
+                name: "width descriptors",
+                input: "small.jpg 480w, medium.jpg 800w,large.jpg 1200w",
+                expected: vec![("small.jpg", "480w"), ("medium.jpg", "800w"), ("large.jpg", "1200w")],
+            },
+            TestCase {
+                name: "density descriptors and bare url",
+                input: "  a.png,  b.png 2x ",
+                expected: vec![("a.png", ""), ("b.png", "2x")],
+            },
+            TestCase {
+                name: "commas inside url",
+                input: "https://cdn.example.com/img,w_400/a.jpg 400w, https://cdn.example.com/img,w_800/a.jpg 800w",
+                expected: vec![
+                    ("https://cdn.example.com/img,w_400/a.jpg", "400w"),
+                    ("https://cdn.example.com/img,w_800/a.jpg", "800w"),
+                ],
+            },
+            TestCase { name: "empty", input: " , ", expected: vec![] },
+        ];
+
+        for test in tests {
+            let expected: Vec<SrcsetCandidate> = test
+                .expected
+                .iter()
+                .map(|(url, descriptor)| SrcsetCandidate { url: url.to_string(), descriptor: descriptor.to_string() })
+                .collect();
+
+            assert_eq!(parse_srcset(test.input), expected, "Test '{}' FAILED", test.name);
+        }
+    }
+
+    #[test]
+    fn test_get_images_from_html() {
+        let input_body = r#"
+            <html>
+                <body>
+                    <img src="/plain.png" alt="Plain" width="640" height="480" loading="lazy">
+                    <img src="/placeholder.gif" data-src="/real.jpg" alt="Lazy">
+                    <img src="/hero.jpg" srcset="/hero-480.jpg 480w, /hero-960.jpg 960w" alt="Hero">
+                    <picture>
+                        <source srcset="/photo.webp 1x, /photo@2x.webp 2x" type="image/webp">
+                        <img src="/photo.jpg" alt="Photo" width="300">
+                    </picture>
+                    <div style="color: red; background-image: url('/bg.png')"></div>
+                    <section style="background: #fff url(&quot;/section.svg&quot;) no-repeat"></section>
+                    <img src="data:image/gif;base64,R0lGODlhAQABAAAAACw=">
+                </body>
+            </html>
+        "#;
+
+        let (_links, images) = get_urls_from_html(input_body, "https://example.com/page").expect("failed to parse HTML");
+
+        let mut keys: Vec<_> = images.keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "example.com/bg.png",
+                "example.com/hero-480.jpg",
+                "example.com/hero-960.jpg",
+                "example.com/hero.jpg",
+                "example.com/photo.jpg",
+                "example.com/photo.webp",
+                "example.com/photo@2x.webp",
+                "example.com/plain.png",
+                "example.com/real.jpg",
+                "example.com/section.svg",
+            ]
+        );
+
+        let plain = &images["example.com/plain.png"];
+        assert_eq!(plain["alt"], "Plain");
+        assert_eq!(plain["width"], "640");
+        assert_eq!(plain["height"], "480");
+        assert_eq!(plain["loading"], "lazy");
+        assert_eq!(plain["url"], "https://example.com/plain.png");
+
+        assert_eq!(images["example.com/hero-960.jpg"]["descriptor"], "960w");
+        assert_eq!(images["example.com/photo@2x.webp"]["descriptor"], "2x");
+        assert_eq!(images["example.com/photo@2x.webp"]["alt"], "Photo");
+        assert_eq!(images["example.com/photo.webp"]["width"], "300");
+    }
+}
@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tests {
+    use spider::crawler::get_main_content::get_main_content;
+    use std::fs;
+    use std::path::Path;
+
+    const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/main_content");
+
+    // Set UPDATE_GOLDEN=1 to rewrite the expected .txt/.out.html files after an intentional change.
+    fn check_golden(path: &Path, actual: &str) {
+        if std::env::var("UPDATE_GOLDEN").is_ok() {
+            fs::write(path, actual).expect("failed to write golden file");
+            return;
+        }
+
+        let expected = fs::read_to_string(path)
+            .unwrap_or_else(|_| panic!("missing golden file {}", path.display()));
+
+        assert_eq!(
+            expected.trim_end(),
+            actual.trim_end(),
+            "Golden file '{}' FAILED",
+            path.display()
+        );
+    }
+
+    #[test]
+    fn test_get_main_content_golden() {
+        let fixtures = ["news_article", "blog_post", "link_heavy_page"];
+
+        for name in fixtures {
+            let input_path = Path::new(FIXTURES_DIR).join(format!("{}.html", name));
+            let input = fs::read_to_string(&input_path)
+                .unwrap_or_else(|_| panic!("missing fixture {}", input_path.display()));
+
+            let content = get_main_content(&input);
+
+            check_golden(&Path::new(FIXTURES_DIR).join(format!("{}.txt", name)), &content.text);
+            check_golden(&Path::new(FIXTURES_DIR).join(format!("{}.out.html", name)), &content.html);
+        }
+    }
+
+    #[test]
+    fn test_get_main_content_empty() {
+        let content = get_main_content("<html><head><title>Nothing</title></head></html>");
+        assert!(content.text.is_empty());
+        assert!(content.html.is_empty());
+    }
+}
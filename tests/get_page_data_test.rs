@@ -1,6 +1,9 @@
+// The cases are built with `vec!`.
+#![allow(clippy::useless_vec)]
+
 #[cfg(test)]
 mod tests {
-    use spider::crawler::get_page_data::get_page_data;
+    use spider::crawler::get_page_data::{get_page_data, PageData};
 
     #[tokio::test]
     async fn test_get_page_data() {
@@ -12,7 +15,7 @@ mod tests {
         for (i, (name, url)) in test_cases.iter().enumerate() {
             println!("Running test {i} - {name}");
             match get_page_data(url).await {
-                Ok((body, status_code, content_type)) => {
+                Ok(PageData { body, status_code, content_type, .. }) => {
                     assert!(
                         status_code < 400,
                         "Test {} - '{}' FAIL: expected status < 400, got {}",
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use spider::crawler::get_page_metadata::get_page_metadata;
+    use spider::pages::{Heading, PageMetadata};
+
+    #[test]
+    fn test_get_page_metadata() {
+        let input_url = "https://www.example.com/articles/rust/";
+        let input_body = r#"
+            <html lang="en-US">
+                <head>
+                    <title>  Learning   Rust </title>
+                    <meta name="description" content="A short guide to Rust.">
+                    <meta name="keywords" content="rust, programming, ,systems">
+                    <meta name="robots" content="NOINDEX, follow">
+                    <meta property="og:title" content="Learning Rust">
+                    <meta property="og:image" content="https://example.com/rust.png">
+                    <meta name="twitter:card" content="summary">
+                    <link rel="canonical" href="/articles/rust">
+                    <style>body { color: red; }</style>
+                </head>
+                <body>
+                    <h1>Learning Rust</h1>
+                    <p>Rust is a systems language.</p>
+                    <h2>Ownership</h2>
+                    <h3>   </h3>
+                    <script>var ignored = "not counted";</script>
+                </body>
+            </html>
+        "#;
+
+        let metadata = get_page_metadata(input_body, input_url).expect("failed to extract metadata");
+
+        assert_eq!(metadata.normalized_url, "example.com/articles/rust");
+        assert_eq!(metadata.title, "Learning Rust");
+        assert_eq!(metadata.description, "A short guide to Rust.");
+        assert_eq!(metadata.keywords, vec!["rust", "programming", "systems"]);
+        assert_eq!(metadata.lang, "en-US");
+        assert_eq!(metadata.canonical, "https://www.example.com/articles/rust");
+        assert_eq!(metadata.robots, "noindex, follow");
+        assert_eq!(metadata.open_graph.get("og:title").map(String::as_str), Some("Learning Rust"));
+        assert_eq!(metadata.open_graph.len(), 2);
+        assert_eq!(metadata.twitter.get("twitter:card").map(String::as_str), Some("summary"));
+        assert_eq!(
+            metadata.headings,
+            vec![
+                Heading { level: 1, text: "Learning Rust".to_string() },
+                Heading { level: 2, text: "Ownership".to_string() },
+            ]
+        );
+        assert_eq!(metadata.word_count, 8);
+    }
+
+    #[test]
+    fn test_page_metadata_hash_roundtrip() {
+        let metadata = get_page_metadata(
+            r#"<html><head><title>Hi</title><meta name="keywords" content="a,b"></head><body><h2>Sub</h2></body></html>"#,
+            "https://example.com",
+        )
+        .expect("failed to extract metadata");
+
+        let restored = PageMetadata::from_hash(&metadata.to_hash()).expect("failed to dehash metadata");
+        assert_eq!(metadata, restored);
+    }
+}
@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use spider::crawler::get_structured_data::get_structured_data;
+    use spider::pages::StructuredData;
+
+    #[test]
+    fn test_json_ld() {
+        let input_body = r#"
+            <html>
+                <head>
+                    <script type="application/ld+json">
+                        {"@context": "https://schema.org", "@type": "Organization", "name": "Example"}
+                    </script>
+                    <script type="application/ld+json">
+                        {
+                            "@context": "https://schema.org",
+                            "@graph": [
+                                {"@type": "WebSite", "name": "Example Site"},
+                                {"@type": "Article", "@context": "https://example.org/ctx", "headline": "Hi"}
+                            ]
+                        }
+                    </script>
+                    <script type="application/ld+json">[{"@type": "Person", "name": "Ada"}]</script>
+                    <script type="application/ld+json">{"@type": "Broken", </script>
+                    <script type="text/javascript">var x = {"@type": "Ignored"};</script>
+                </head>
+            </html>
+        "#;
+
+        let data = get_structured_data(input_body, "https://example.com/").expect("failed to extract structured data");
+
+        assert_eq!(
+            data.json_ld,
+            vec![
+                json!({"@context": "https://schema.org", "@type": "Organization", "name": "Example"}),
+                json!({"@context": "https://schema.org", "@type": "WebSite", "name": "Example Site"}),
+                json!({"@context": "https://example.org/ctx", "@type": "Article", "headline": "Hi"}),
+                json!({"@type": "Person", "name": "Ada"}),
+            ]
+        );
+        assert_eq!(data.warnings.len(), 1, "warnings: {:?}", data.warnings);
+        assert!(data.warnings[0].starts_with("Malformed JSON-LD block 4"));
+    }
+
+    #[test]
+    fn test_microdata() {
+        let input_body = r#"
+            <html>
+                <body>
+                    <div itemscope itemtype="https://schema.org/Product" itemid="/products/42">
+                        <h1 itemprop="name">  Rusty   Widget </h1>
+                        <img itemprop="image" src="/img/widget.png">
+                        <a itemprop="url" href="/products/42">Link</a>
+                        <span itemprop="color">red</span>
+                        <span itemprop="color">blue</span>
+                        <div itemprop="offers" itemscope itemtype="https://schema.org/Offer">
+                            <meta itemprop="priceCurrency" content="EUR">
+                            <data itemprop="price" value="9.99">€9.99</data>
+                        </div>
+                    </div>
+                </body>
+            </html>
+        "#;
+
+        let data = get_structured_data(input_body, "https://shop.example.com/catalog").expect("failed to extract structured data");
+
+        assert_eq!(
+            data.microdata,
+            vec![json!({
+                "@type": "https://schema.org/Product",
+                "@id": "https://shop.example.com/products/42",
+                "name": "Rusty Widget",
+                "image": "https://shop.example.com/img/widget.png",
+                "url": "https://shop.example.com/products/42",
+                "color": ["red", "blue"],
+                "offers": {
+                    "@type": "https://schema.org/Offer",
+                    "priceCurrency": "EUR",
+                    "price": "9.99"
+                }
+            })]
+        );
+        assert!(data.json_ld.is_empty());
+        assert!(data.rdfa.is_empty());
+    }
+
+    #[test]
+    fn test_rdfa() {
+        let input_body = r#"
+            <html>
+                <head><meta property="og:title" content="Not an entity"></head>
+                <body>
+                    <div vocab="https://schema.org/" typeof="Person">
+                        <span property="name">Grace Hopper</span>
+                        <div property="worksFor" typeof="Organization">
+                            <span property="name">US Navy</span>
+                        </div>
+                        <time property="birthDate" datetime="1906-12-09">December 9, 1906</time>
+                    </div>
+                </body>
+            </html>
+        "#;
+
+        let data = get_structured_data(input_body, "https://example.com/people").expect("failed to extract structured data");
+
+        assert_eq!(
+            data.rdfa,
+            vec![json!({
+                "@context": "https://schema.org/",
+                "@type": "Person",
+                "name": "Grace Hopper",
+                "worksFor": {"@type": "Organization", "name": "US Navy"},
+                "birthDate": "1906-12-09"
+            })]
+        );
+    }
+
+    #[test]
+    fn test_structured_data_hash_roundtrip() {
+        let data = get_structured_data(
+            r#"<script type="application/ld+json">{"@type": "Thing"}</script><script type="application/ld+json">{</script>"#,
+            "https://example.com",
+        )
+        .expect("failed to extract structured data");
+
+        assert!(!data.is_empty());
+        let restored = StructuredData::from_hash(&data.to_hash()).expect("failed to dehash structured data");
+        assert_eq!(data, restored);
+    }
+}
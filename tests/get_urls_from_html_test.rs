@@ -1,6 +1,10 @@
+// The cases are built with `vec!` and checked with `expect(&format!(..))`.
+#![allow(clippy::useless_vec, clippy::expect_fun_call)]
+
 #[cfg(test)]
 mod tests {
-    use spider::crawler::get_urls_from_html::get_urls_from_html;
+    use scraper::Html;
+    use spider::crawler::get_urls_from_html::{get_meta_refresh_from_document, get_urls_from_html, MetaRefresh};
     use std::collections::HashSet;
 
     #[test]
@@ -120,4 +124,81 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_get_urls_from_html_base_href() {
+        let input_body = r#"
+            <html>
+                <head><base href="https://cdn.example.com/docs/"></head>
+                <body>
+                    <a href="guide">Guide</a>
+                    <a href="/root">Root</a>
+                    <img src="img/logo.png" alt="Logo">
+                </body>
+            </html>
+        "#;
+
+        let (links, images) = get_urls_from_html(input_body, "https://example.com/index.html")
+            .expect("failed to parse HTML");
+
+        let actual: HashSet<_> = links.iter().map(|s| s.as_str()).collect();
+        let expected: HashSet<_> = ["https://cdn.example.com/docs/guide", "https://cdn.example.com/root"].into_iter().collect();
+
+        assert_eq!(actual, expected);
+        assert!(images.contains_key("cdn.example.com/docs/img/logo.png"));
+    }
+
+    #[test]
+    fn test_get_meta_refresh() {
+        struct TestCase<'a> {
+            name: &'a str,
+            content: &'a str,
+            expected: Option<&'a str>,
+        }
+
+        let tests = [
+            TestCase { name: "delay and url", content: "5; url=/moved", expected: Some("https://example.com/moved") },
+            TestCase { name: "quoted url", content: "0;URL='https://other.com/page'", expected: Some("https://other.com/page") },
+            TestCase { name: "spaces around equals", content: "0; url = next.html", expected: Some("https://example.com/dir/next.html") },
+            TestCase { name: "reload only", content: "30", expected: None },
+            TestCase { name: "invalid delay", content: "soon; url=/moved", expected: None },
+            TestCase { name: "javascript target", content: "0; url=javascript:alert(1)", expected: None },
+        ];
+
+        for test in tests {
+            let body = format!(r#"<html><head><meta http-equiv="Refresh" content="{}"></head></html>"#, test.content);
+            let document = Html::parse_document(&body);
+            let result = get_meta_refresh_from_document(&document, "https://example.com/dir/page");
+
+            assert_eq!(result.map(|refresh| refresh.url).as_deref(), test.expected, "Test '{}' FAILED", test.name);
+        }
+    }
+
+    #[test]
+    fn test_meta_refresh_redirects_from() {
+        struct TestCase<'a> {
+            name: &'a str,
+            delay_secs: f64,
+            url: &'a str,
+            expected: bool,
+        }
+
+        let tests = [
+            TestCase { name: "immediate", delay_secs: 0.0, url: "https://example.com/moved", expected: true },
+            TestCase { name: "short delay", delay_secs: 3.0, url: "https://other.com/", expected: true },
+            TestCase { name: "long delay", delay_secs: 300.0, url: "https://example.com/moved", expected: false },
+            TestCase { name: "self target", delay_secs: 0.0, url: "https://example.com/page", expected: false },
+            TestCase { name: "self target with fragment", delay_secs: 0.0, url: "https://example.com/page#top", expected: false },
+        ];
+
+        for test in tests {
+            let refresh = MetaRefresh { delay_secs: test.delay_secs, url: test.url.to_string() };
+            assert_eq!(refresh.redirects_from("example.com/page"), test.expected, "Test '{}' FAILED", test.name);
+        }
+
+        let document = Html::parse_document(r#"<html><head><meta http-equiv="refresh" content="300; url=/other"></head></html>"#);
+        let refresh = get_meta_refresh_from_document(&document, "https://example.com/page").unwrap();
+        assert_eq!(refresh.delay_secs, 300.0);
+        assert!(!refresh.redirects_from("example.com/page"));
+    }
 }
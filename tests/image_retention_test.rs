@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use spider::controllers::image_controller::{Eviction, ImageRetention};
+    use spider::pages::{Image, ImageReference};
+
+    #[test]
+    fn test_parse_image_retention() {
+        struct TestCase<'a> {
+            input: &'a str,
+            expected: Result<ImageRetention, ()>,
+        }
+
+        let tests = [
+            TestCase { input: "none", expected: Ok(ImageRetention::None) },
+            TestCase { input: "", expected: Ok(ImageRetention::None) },
+            TestCase { input: "TTL:3600", expected: Ok(ImageRetention::Ttl(3600)) },
+            TestCase { input: "lru:100000", expected: Ok(ImageRetention::Lru(100000)) },
+            TestCase { input: "ttl", expected: Err(()) },
+            TestCase { input: "ttl:0", expected: Err(()) },
+            TestCase { input: "lru:many", expected: Err(()) },
+            TestCase { input: "forever", expected: Err(()) },
+        ];
+
+        for test in tests {
+            let result = test.input.parse::<ImageRetention>().map_err(|_| ());
+            assert_eq!(result, test.expected, "Test '{}' FAILED", test.input);
+        }
+
+        assert_eq!(ImageRetention::Ttl(60).to_string(), "ttl:60");
+        assert_eq!(ImageRetention::Lru(5).to_string().parse::<ImageRetention>(), Ok(ImageRetention::Lru(5)));
+    }
+
+    #[test]
+    fn test_image_eviction() {
+        struct TestCase {
+            retention: ImageRetention,
+            tracked: usize,
+            expected: Eviction,
+        }
+
+        let now = 1_000_000;
+        let tests = [
+            TestCase { retention: ImageRetention::None, tracked: 50, expected: Eviction::Nothing },
+            TestCase { retention: ImageRetention::Ttl(3600), tracked: 50, expected: Eviction::SeenBefore(now - 3600) },
+            TestCase { retention: ImageRetention::Ttl(3600), tracked: 0, expected: Eviction::SeenBefore(now - 3600) },
+            TestCase { retention: ImageRetention::Lru(10), tracked: 10, expected: Eviction::Nothing },
+            TestCase { retention: ImageRetention::Lru(10), tracked: 13, expected: Eviction::Oldest(3) },
+        ];
+
+        for test in tests {
+            assert_eq!(test.retention.eviction(test.tracked, now), test.expected, "Test '{}' FAILED", test.retention);
+        }
+    }
+
+    #[test]
+    fn test_image_reference_round_trip() {
+        let image = Image {
+            normalized_page_url: "example.com/post".to_string(),
+            normalized_source_url: "example.com/logo.png".to_string(),
+            source_url: "https://example.com/logo.png".to_string(),
+            alt: "Logo".to_string(),
+            width: "120".to_string(),
+            height: "40".to_string(),
+            loading: "lazy".to_string(),
+            descriptor: "2x".to_string(),
+        };
+
+        let reference = image.reference();
+        assert_eq!(reference.alt, "Logo");
+        assert_eq!(ImageReference::from_json(&reference.to_json()), Ok(reference));
+        assert!(ImageReference::from_json("not json").is_err());
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    use scraper::Html;
+    use spider::crawler::crawler::CrawlerConfig;
+    use spider::crawler::get_urls_from_html::get_links_from_document;
+    use spider::crawler::robots_directives::RobotsPolicy;
+    use spider::pages::{LinkEdge, LinkPosition};
+
+    const INPUT_BODY: &str = r#"
+        <html>
+            <body>
+                <nav><a href="/about">About us</a></nav>
+                <header><a href="/">Home</a></header>
+                <div role="navigation"><a href="/menu">Menu</a></div>
+                <main>
+                    <p>Read <a href="/about" title=" Company   info ">more about the team</a>.</p>
+                    <p><a href="/gallery"><img src="/g.png" alt="Photo gallery"></a></p>
+                    <p><a href="/about#history">Our history</a></p>
+                </main>
+                <footer><a href="/legal" rel="nofollow">Legal</a></footer>
+                <aside><a href="https://other.com/ad" rel="sponsored">Sponsor</a></aside>
+            </body>
+        </html>
+    "#;
+
+    #[test]
+    fn test_link_edges() {
+        let document = Html::parse_document(INPUT_BODY);
+        let (links, _images) = get_links_from_document(&document, "https://example.com/")
+            .expect("failed to extract links");
+
+        let about = &links["https://example.com/about"];
+        assert_eq!(about.source_url, "example.com");
+        assert_eq!(about.count, 2);
+        assert_eq!(about.anchor_texts, vec!["About us", "more about the team"]);
+        assert_eq!(about.title, "Company info");
+        assert_eq!(about.position, LinkPosition::Body);
+
+        assert_eq!(links["https://example.com/"].position, LinkPosition::Header);
+        assert_eq!(links["https://example.com/menu"].position, LinkPosition::Nav);
+        assert_eq!(links["https://example.com/legal"].position, LinkPosition::Footer);
+        assert_eq!(links["https://other.com/ad"].position, LinkPosition::Aside);
+        assert_eq!(links["https://example.com/gallery"].anchor_text(), "Photo gallery");
+    }
+
+    #[tokio::test]
+    async fn test_update_links_merges_edges() {
+        let document = Html::parse_document(INPUT_BODY);
+        let (links, _images) = get_links_from_document(&document, "https://example.com/")
+            .expect("failed to extract links");
+
+        let crawler = CrawlerConfig::new(10, 1);
+        crawler.update_links("example.com", &links).await;
+
+        let outlinks = crawler.outlinks.lock().await;
+        let node = &outlinks["example.com"];
+        let about = node.get_edge("example.com/about").expect("missing edge to /about");
+
+        assert_eq!(about.source_url, "example.com");
+        assert_eq!(about.target_url, "example.com/about");
+        assert_eq!(about.count, 3);
+        let mut anchors = about.anchor_texts.clone();
+        anchors.sort();
+        assert_eq!(anchors, vec!["About us", "Our history", "more about the team"]);
+        assert!(node.get_edge("example.com").is_none(), "self links should be dropped");
+
+        let backlinks = crawler.backlinks.lock().await;
+        let back = backlinks["other.com/ad"].get_edge("example.com").expect("missing backlink edge");
+        assert!(back.rel.contains("sponsored"));
+
+        let restored = LinkEdge::from_json(&about.to_json()).expect("failed to decode edge");
+        assert_eq!(&restored, about);
+    }
+
+    #[test]
+    fn test_merged_rel_follows_any_followable_anchor() {
+        let body = r#"
+            <html><body>
+                <a href="/mixed" rel="nofollow noopener">Ad</a>
+                <a href="/mixed">Mixed</a>
+                <a href="/blocked" rel="nofollow">One</a>
+                <a href="/blocked" rel="sponsored">Two</a>
+            </body></html>
+        "#;
+        let document = Html::parse_document(body);
+        let (links, _images) = get_links_from_document(&document, "https://example.com/")
+            .expect("failed to extract links");
+
+        let mixed = &links["https://example.com/mixed"];
+        assert_eq!(mixed.rel.iter().collect::<Vec<_>>(), vec!["noopener"]);
+        assert!(RobotsPolicy::default().should_follow_link(&mixed.rel));
+
+        let blocked = &links["https://example.com/blocked"];
+        assert_eq!(blocked.rel.iter().collect::<Vec<_>>(), vec!["nofollow", "sponsored"]);
+        assert!(!RobotsPolicy::default().should_follow_link(&blocked.rel));
+    }
+}
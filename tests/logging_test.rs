@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use tracing::{info, info_span};
+    use spider::logging::{init_logging, LogFormat, AUDIT_TARGET};
+
+    struct TestCase {
+        input: &'static str,
+        expected: Option<LogFormat>,
+    }
+
+    #[test]
+    fn test_parse_log_format() {
+        let cases = vec![
+            TestCase { input: "full", expected: Some(LogFormat::Full) },
+            TestCase { input: "Pretty", expected: Some(LogFormat::Pretty) },
+            TestCase { input: " compact ", expected: Some(LogFormat::Compact) },
+            TestCase { input: "JSON", expected: Some(LogFormat::Json) },
+            TestCase { input: "xml", expected: None },
+        ];
+
+        for case in cases {
+            assert_eq!(case.input.parse::<LogFormat>().ok(), case.expected, "{}", case.input);
+            if let Some(format) = case.expected {
+                assert_eq!(format.to_string().parse::<LogFormat>(), Ok(format));
+            }
+        }
+    }
+
+    #[test]
+    fn test_audit_log() {
+        let path = std::env::temp_dir().join(format!("spider-audit-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        init_logging(LogFormat::Json, Some(&path)).expect("failed to set up logging");
+
+        let span = info_span!("url", url = "https://example.com/", depth = 1.0);
+        span.in_scope(|| {
+            info!("Fetching");
+            info!(target: AUDIT_TARGET, url = "https://example.com/", outcome = "fetched", status = 200u16, "fetch");
+        });
+
+        let content = std::fs::read_to_string(&path).expect("audit log was not written");
+        let _ = std::fs::remove_file(&path);
+
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 1, "{}", content);
+
+        let entry: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(entry["target"], AUDIT_TARGET);
+        assert_eq!(entry["fields"]["outcome"], "fetched");
+        assert_eq!(entry["fields"]["status"], 200);
+
+        // Only one subscriber can be installed.
+        assert!(init_logging(LogFormat::Full, None).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use spider::crawler::fetch::{error_kind, FetchError};
+    use spider::metrics::{metrics, router, status_class, Metrics, CONTENT_TYPE_TEXT};
+
+    struct TestCase {
+        status_code: u16,
+        expected: &'static str,
+    }
+
+    #[test]
+    fn test_status_class() {
+        let cases = vec![
+            TestCase { status_code: 200, expected: "2xx" },
+            TestCase { status_code: 304, expected: "3xx" },
+            TestCase { status_code: 404, expected: "4xx" },
+            TestCase { status_code: 503, expected: "5xx" },
+        ];
+
+        for case in cases {
+            assert_eq!(status_class(case.status_code), case.expected);
+        }
+    }
+
+    #[test]
+    fn test_fetch_error_kind() {
+        let errors: Vec<(Box<dyn Error>, &str)> = vec![
+            (FetchError::Status(404).into(), "http_status"),
+            (FetchError::TooLarge { length: None, limit: 10 }.into(), "too_large"),
+            (FetchError::ContentType("image/png".to_string()).into(), "content_type"),
+            ("something else".into(), "other"),
+        ];
+
+        for (err, expected) in errors {
+            assert_eq!(error_kind(err.as_ref()), expected, "{}", err);
+        }
+
+        assert_eq!(FetchError::Status(404).to_string(), "HTTP error: 404 Not Found");
+    }
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics::default();
+        metrics.fetches.inc("2xx");
+        metrics.fetches.inc("2xx");
+        metrics.fetch_errors.inc("timeout");
+        metrics.fetched_bytes.add(2048);
+        metrics.fetch_duration.observe(0.2);
+        metrics.fetch_duration.observe(3.0);
+        metrics.fetch_duration.observe(120.0);
+        metrics.spider_queue.set(42);
+        metrics.rejections.inc("say \"no\"");
+
+        let text = metrics.render();
+
+        for line in [
+            "# TYPE spider_fetches_total counter",
+            "spider_fetches_total{status_class=\"2xx\"} 2",
+            "spider_fetch_errors_total{kind=\"timeout\"} 1",
+            "spider_fetched_bytes_total 2048",
+            "# TYPE spider_fetch_duration_seconds histogram",
+            "spider_fetch_duration_seconds_bucket{le=\"0.1\"} 0",
+            "spider_fetch_duration_seconds_bucket{le=\"0.25\"} 1",
+            "spider_fetch_duration_seconds_bucket{le=\"5\"} 2",
+            "spider_fetch_duration_seconds_bucket{le=\"60\"} 2",
+            "spider_fetch_duration_seconds_bucket{le=\"+Inf\"} 3",
+            "spider_fetch_duration_seconds_sum 123.2",
+            "spider_fetch_duration_seconds_count 3",
+            "# TYPE spider_queue_depth gauge",
+            "spider_queue_depth 42",
+            "spider_rejections_total{reason=\"say \\\"no\\\"\"} 1",
+            "spider_in_flight_workers 0",
+        ] {
+            assert!(text.lines().any(|l| l == line), "missing {:?} in\n{}", line, text);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router()).await });
+
+        metrics().links_discovered.add(3);
+
+        let response = reqwest::get(format!("http://{}/metrics", addr)).await.expect("request failed");
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["content-type"], CONTENT_TYPE_TEXT);
+
+        let body = response.text().await.unwrap();
+        assert!(body.contains("# TYPE spider_links_discovered_total counter"));
+
+        let missing = reqwest::get(format!("http://{}/other", addr)).await.unwrap();
+        assert_eq!(missing.status(), 404);
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use spider::crawler::priority::{
+        bounded_score, parse_url_boosts, Bfs, Dfs, HostDiversity, LinkCandidate, Opic, PriorityPolicy, PriorityPolicyKind,
+    };
+
+    fn link<'a>(source_url: &'a str, target_url: &'a str, parent_score: f64, outlink_count: usize) -> LinkCandidate<'a> {
+        LinkCandidate { source_url, target_url, parent_score, outlink_count, queued: false }
+    }
+
+    #[test]
+    fn test_bfs_and_dfs() {
+        let candidate = link("example.com", "https://example.com/a", 3.0, 10);
+
+        assert_eq!(Bfs.score(&candidate), 4.0);
+        assert_eq!(Dfs.score(&candidate), 2.0);
+
+        assert_eq!(bounded_score(&Bfs, &candidate, Some(2.0)), 2.0, "existing lower score is kept");
+        assert_eq!(bounded_score(&Bfs, &candidate, Some(9.0)), 4.0, "shallower backlink improves the score");
+        assert_eq!(bounded_score(&Dfs, &link("a.com", "https://a.com/b", -1000.0, 1), None), -999.0, "below feed items");
+    }
+
+    #[test]
+    fn test_opic_rewards_backlinks() {
+        let opic = Opic::default();
+
+        // The seed splits its cash between two links.
+        let first = opic.score(&link("example.com", "https://example.com/popular", 0.0, 2));
+        let lonely = opic.score(&link("example.com", "https://example.com/lonely", 0.0, 2));
+        assert_eq!(first, 2.0);
+        assert_eq!(lonely, 2.0);
+
+        // Another backlink to the same page adds cash and lowers its score.
+        let second = opic.score(&link("example.com/lonely", "https://www.example.com/popular/", 2.0, 1));
+        assert!(second < first, "expected {} < {}", second, first);
+        assert_eq!(opic.cash("https://example.com/popular"), 1.0);
+        assert_eq!(opic.cash("https://example.com/unseen"), 1.0);
+
+        // Crawling a page spends its cash.
+        opic.crawled("example.com/popular");
+        assert_eq!(opic.cash("https://example.com/popular"), 1.0);
+        let after = opic.score(&link("example.com/other", "https://example.com/popular", 2.0, 4));
+        assert_eq!(after, 4.0);
+    }
+
+    #[test]
+    fn test_host_diversity_round_robin() {
+        let policy = HostDiversity::default();
+        let scores: Vec<f64> = [
+            "https://a.com/1",
+            "https://a.com/2",
+            "https://b.com/1",
+            "https://www.a.com/3",
+            "https://b.com/2",
+        ]
+        .iter()
+        .map(|url| policy.score(&link("a.com", url, 0.0, 5)))
+        .collect();
+
+        assert_eq!(scores, vec![1.0, 2.0, 1.0, 3.0, 2.0]);
+
+        // Rescoring a queued URL does not count it twice.
+        let queued = LinkCandidate { queued: true, ..link("b.com", "https://b.com/1", 0.0, 5) };
+        assert_eq!(policy.score(&queued), 2.0);
+        assert_eq!(policy.queued("b.com"), 2);
+
+        // Dispatched URLs leave the count.
+        policy.dispatched("a.com/1");
+        policy.dispatched("a.com/2");
+        assert_eq!(policy.queued("www.a.com"), 1);
+        assert_eq!(policy.score(&link("b.com", "https://a.com/4", 0.0, 5)), 2.0);
+
+        policy.dispatched("b.com/1");
+        policy.dispatched("b.com/2");
+        policy.dispatched("b.com/3");
+        assert_eq!(policy.queued("b.com"), 0);
+    }
+
+    #[test]
+    fn test_url_boosts() {
+        let boosts = parse_url_boosts(r"/news/=5; \.pdf$=-2").expect("failed to parse boosts");
+        assert_eq!(boosts.len(), 2);
+
+        let policy = PriorityPolicyKind::Bfs.build(boosts);
+        assert_eq!(policy.score(&link("a.com", "https://a.com/news/today", 1.0, 1)), -3.0);
+        assert_eq!(policy.score(&link("a.com", "https://a.com/report.pdf", 1.0, 1)), 4.0);
+        assert_eq!(policy.score(&link("a.com", "https://a.com/about", 1.0, 1)), 2.0);
+
+        assert!(parse_url_boosts("").unwrap().is_empty());
+        assert!(parse_url_boosts("/news/").is_err());
+        assert!(parse_url_boosts("/news/=lots").is_err());
+        assert!(parse_url_boosts("(=1").is_err());
+    }
+
+    #[test]
+    fn test_parse_priority_policy() {
+        for kind in [PriorityPolicyKind::Bfs, PriorityPolicyKind::Dfs, PriorityPolicyKind::Opic, PriorityPolicyKind::HostDiversity] {
+            assert_eq!(kind.to_string().parse::<PriorityPolicyKind>(), Ok(kind));
+        }
+        assert_eq!("OPIC".parse::<PriorityPolicyKind>(), Ok(PriorityPolicyKind::Opic));
+        assert!("random".parse::<PriorityPolicyKind>().is_err());
+    }
+}
@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use spider::database::{CrawlStatus, FetchStat, FetchStats};
+    use spider::report::{escape_html, percentile, CrawlReport, LatencySummary, ReportFormat};
+
+    struct TestCase {
+        name: &'static str,
+        stat: FetchStat,
+        expected: Vec<&'static str>,
+    }
+
+    #[test]
+    fn test_fetch_stat_counters() {
+        let cases = vec![
+            TestCase {
+                name: "fetched",
+                stat: FetchStat {
+                    host: "example.com".to_string(),
+                    depth: 2.0,
+                    duration_ms: 120,
+                    status: Some(200),
+                    content_type: "text/html".to_string(),
+                    redirected: false,
+                    error: None,
+                },
+                expected: vec!["fetches", "status:200", "host:example.com", "depth:2", "content_type:text/html"],
+            },
+            TestCase {
+                name: "redirected",
+                stat: FetchStat {
+                    host: "example.com".to_string(),
+                    depth: 0.0,
+                    status: Some(200),
+                    redirected: true,
+                    ..Default::default()
+                },
+                expected: vec!["fetches", "status:200", "host:example.com", "depth:0", "redirects"],
+            },
+            TestCase {
+                name: "http error",
+                stat: FetchStat {
+                    host: "example.com".to_string(),
+                    status: Some(404),
+                    error: Some("status".to_string()),
+                    ..Default::default()
+                },
+                expected: vec!["fetches", "status:404", "error:status"],
+            },
+            TestCase {
+                name: "timeout",
+                stat: FetchStat {
+                    host: "example.com".to_string(),
+                    error: Some("timeout".to_string()),
+                    ..Default::default()
+                },
+                expected: vec!["fetches", "error:timeout"],
+            },
+        ];
+
+        for case in cases {
+            assert_eq!(case.stat.counters(), case.expected, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_fetch_stats_from_counters() {
+        let counters: HashMap<String, u64> = [
+            ("fetches", 5),
+            ("redirects", 1),
+            ("status:200", 3),
+            ("status:404", 1),
+            ("status:bogus", 9),
+            ("host:a.com", 2),
+            ("host:b.com", 1),
+            ("depth:0", 1),
+            ("depth:1", 2),
+            ("content_type:text/html", 3),
+            ("error:status", 1),
+            ("error:timeout", 1),
+            ("unknown", 7),
+        ]
+        .into_iter()
+        .map(|(field, count)| (field.to_string(), count))
+        .collect();
+
+        let stats = FetchStats::from_counters(&counters);
+
+        assert_eq!(stats.fetches, 5);
+        assert_eq!(stats.redirects, 1);
+        assert_eq!(stats.status_codes.into_iter().collect::<Vec<_>>(), vec![(200, 3), (404, 1)]);
+        assert_eq!(stats.pages_per_host["a.com"], 2);
+        assert_eq!(stats.depths.into_iter().collect::<Vec<_>>(), vec![(0, 1), (1, 2)]);
+        assert_eq!(stats.content_types["text/html"], 3);
+        assert_eq!(stats.errors.len(), 2);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let sorted: Vec<u64> = (1..=100).collect();
+        assert_eq!(percentile(&sorted, 50.0), 50);
+        assert_eq!(percentile(&sorted, 90.0), 90);
+        assert_eq!(percentile(&sorted, 99.0), 99);
+        assert_eq!(percentile(&sorted, 0.0), 1);
+        assert_eq!(percentile(&[7], 99.0), 7);
+        assert_eq!(percentile(&[], 50.0), 0);
+
+        let summary = LatencySummary::new(&[300, 100, 200]);
+        assert_eq!(summary.samples, 3);
+        assert_eq!(summary.average_ms, 200.0);
+        assert_eq!(summary.p50_ms, 200);
+        assert_eq!(summary.max_ms, 300);
+        assert_eq!(LatencySummary::new(&[]), LatencySummary::default());
+    }
+
+    #[test]
+    fn test_report_rendering() {
+        let mut counters = HashMap::new();
+        counters.insert("fetches".to_string(), 3);
+        counters.insert("host:<b>.com".to_string(), 1);
+        counters.insert("host:a.com".to_string(), 2);
+        let stats = FetchStats::from_counters(&counters);
+
+        let report = CrawlReport::new(
+            &CrawlStatus::default(),
+            &stats,
+            &[10, 20],
+            vec![("https://a.com/".to_string(), 4)],
+            vec![("https://a.com/?q=\"x\"&y".to_string(), 1)],
+        );
+
+        assert_eq!(report.pages_per_host[0].name, "a.com");
+        assert_eq!(report.latency.p50_ms, 10);
+
+        let json: serde_json::Value = serde_json::from_str(&report.render(ReportFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["fetches"], 3);
+        assert_eq!(json["most_linked"][0]["count"], 4);
+
+        let html = report.render(ReportFormat::Html).unwrap();
+        assert!(html.contains("&lt;b&gt;.com"), "{}", html);
+        assert!(html.contains("https://a.com/?q=&quot;x&quot;&amp;y"), "{}", html);
+        assert!(!html.contains("<b>"), "{}", html);
+
+        assert_eq!(escape_html("<a href='x'>&</a>"), "&lt;a href=&#39;x&#39;&gt;&amp;&lt;/a&gt;");
+        assert_eq!("HTML".parse::<ReportFormat>(), Ok(ReportFormat::Html));
+        assert!("csv".parse::<ReportFormat>().is_err());
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod tests {
+    use scraper::Html;
+    use spider::crawler::get_urls_from_html::get_links_from_document;
+    use spider::crawler::robots_directives::{
+        get_robots_directives_from_document, parse_rel, parse_x_robots_tag, RobotsDirectives, RobotsPolicy,
+    };
+
+    #[test]
+    fn test_parse_robots_directives() {
+        struct TestCase<'a> {
+            name: &'a str,
+            input: &'a str,
+            expected: RobotsDirectives,
+        }
+
+        let tests = [
+            TestCase { name: "empty", input: "", expected: RobotsDirectives { noindex: false, nofollow: false } },
+            TestCase { name: "noindex", input: "NoIndex", expected: RobotsDirectives { noindex: true, nofollow: false } },
+            TestCase { name: "both", input: "noindex, nofollow", expected: RobotsDirectives { noindex: true, nofollow: true } },
+            TestCase { name: "none", input: "none", expected: RobotsDirectives { noindex: true, nofollow: true } },
+            TestCase { name: "all", input: "index, follow, all", expected: RobotsDirectives { noindex: false, nofollow: false } },
+        ];
+
+        for test in tests {
+            let result = RobotsDirectives::parse(test.input);
+            assert_eq!(result, test.expected, "Test '{}' FAILED", test.name);
+        }
+    }
+
+    #[test]
+    fn test_parse_x_robots_tag() {
+        struct TestCase<'a> {
+            name: &'a str,
+            input: Vec<&'a str>,
+            expected: RobotsDirectives,
+        }
+
+        let tests = [
+            TestCase { name: "no header", input: vec![], expected: RobotsDirectives { noindex: false, nofollow: false } },
+            TestCase { name: "plain", input: vec!["noindex"], expected: RobotsDirectives { noindex: true, nofollow: false } },
+            TestCase { name: "multiple headers", input: vec!["noindex", "nofollow"], expected: RobotsDirectives { noindex: true, nofollow: true } },
+            TestCase { name: "other user agent", input: vec!["googlebot: noindex"], expected: RobotsDirectives { noindex: false, nofollow: false } },
+            TestCase { name: "our user agent", input: vec!["Spider: nofollow"], expected: RobotsDirectives { noindex: false, nofollow: true } },
+            TestCase {
+                name: "directive with value",
+                input: vec!["unavailable_after: 25 Jun 2010 15:00:00 PST, noindex"],
+                expected: RobotsDirectives { noindex: true, nofollow: false },
+            },
+        ];
+
+        for test in tests {
+            let result = parse_x_robots_tag(test.input.iter().copied());
+            assert_eq!(result, test.expected, "Test '{}' FAILED", test.name);
+        }
+    }
+
+    #[test]
+    fn test_meta_robots_and_rel() {
+        let document = Html::parse_document(r#"
+            <html>
+                <head><meta name="robots" content="noindex"><meta name="spider" content="nofollow"></head>
+                <body>
+                    <a href="/plain">Plain</a>
+                    <a href="/ad" rel="Sponsored noopener">Ad</a>
+                    <a href="/comment" rel="ugc">Comment</a>
+                </body>
+            </html>
+        "#);
+
+        let directives = get_robots_directives_from_document(&document);
+        assert_eq!(directives, RobotsDirectives { noindex: true, nofollow: true });
+
+        let (links, _images) = get_links_from_document(&document, "https://example.com").expect("failed to extract links");
+        assert_eq!(links["https://example.com/plain"].rel, parse_rel(""));
+        assert_eq!(links["https://example.com/ad"].rel, parse_rel("sponsored noopener"));
+
+        let policy = RobotsPolicy::default();
+        assert!(!policy.should_index(&directives));
+        assert!(!policy.should_follow(&directives));
+        assert!(policy.should_follow_link(&links["https://example.com/plain"].rel));
+        assert!(!policy.should_follow_link(&links["https://example.com/ad"].rel));
+        assert!(!policy.should_follow_link(&links["https://example.com/comment"].rel));
+
+        let audit = RobotsPolicy::ignore_all();
+        assert!(audit.should_index(&directives));
+        assert!(audit.should_follow(&directives));
+        assert!(audit.should_follow_link(&links["https://example.com/ad"].rel));
+    }
+}
@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use spider::crawler::scope::{ScopeAction, ScopeRule, ScopeRules};
+    use spider::crawler::seeds::{Seed, SeedReport, SeedSource};
+
+    #[test]
+    fn test_parse_seed_source() {
+        struct TestCase<'a> {
+            input: &'a str,
+            expected: Result<SeedSource, ()>,
+        }
+
+        let tests = [
+            TestCase { input: "https://example.com", expected: Ok(SeedSource::Url("https://example.com".to_string())) },
+            TestCase { input: "stdin", expected: Ok(SeedSource::Stdin) },
+            TestCase { input: "-", expected: Ok(SeedSource::Stdin) },
+            TestCase { input: "redis:seed_list", expected: Ok(SeedSource::RedisList("seed_list".to_string())) },
+            TestCase { input: "file:/data/seeds.csv", expected: Ok(SeedSource::File(PathBuf::from("/data/seeds.csv"))) },
+            TestCase { input: " seeds.txt ", expected: Ok(SeedSource::File(PathBuf::from("seeds.txt"))) },
+            TestCase { input: "redis:", expected: Err(()) },
+            TestCase { input: "", expected: Err(()) },
+        ];
+
+        for test in tests {
+            let result = test.input.parse::<SeedSource>().map_err(|_| ());
+            assert_eq!(result, test.expected, "Test '{}' FAILED", test.input);
+        }
+    }
+
+    #[test]
+    fn test_text_seeds() {
+        let mut report = SeedReport::default();
+        let input = "# news sites\nhttps://example.com/\n\n  https://www.example.com  \nhttps://example.org/a b\nftp://example.net/\nnot a url\nhttps://example.org/news\n";
+
+        for line in input.lines() {
+            report.add_line(line, false);
+        }
+
+        assert_eq!(
+            report.accepted,
+            vec![Seed::new("https://example.com/".to_string()), Seed::new("https://example.org/news".to_string())]
+        );
+
+        let rejected: Vec<(&str, &str)> = report.rejected.iter().map(|r| (r.input.as_str(), r.reason.as_str())).collect();
+        assert_eq!(rejected[0], ("https://www.example.com", "duplicate seed"));
+        assert_eq!(rejected[1], ("https://example.org/a b", "invalid URL characters"));
+        assert_eq!(rejected[2], ("ftp://example.net/", "URL has invalid field 'Scheme'"));
+        assert_eq!(rejected.len(), 4);
+
+        let summary = report.rejection_summary();
+        assert_eq!(summary["duplicate seed"], 1);
+        assert_eq!(summary.values().sum::<usize>(), 4);
+    }
+
+    #[test]
+    fn test_csv_seeds() {
+        let mut report = SeedReport::default();
+        let input = "url,depth,priority\nhttps://example.com/,2,5\nhttps://example.org/\nhttps://example.net/,deep\nhttps://example.io/,1,\n";
+
+        for line in input.lines() {
+            report.add_line(line, true);
+        }
+
+        assert_eq!(
+            report.accepted,
+            vec![
+                Seed { url: "https://example.com/".to_string(), depth: 2.0, priority: 5.0 },
+                Seed::new("https://example.org/".to_string()),
+                Seed { url: "https://example.io/".to_string(), depth: 1.0, priority: 0.0 },
+            ]
+        );
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].reason, "invalid depth");
+
+        assert_eq!(report.accepted[0].score(), -3.0);
+        assert_eq!(report.accepted[1].score(), 0.0);
+        assert_eq!(Seed { url: String::new(), depth: 0.0, priority: 1e9 }.score(), -1000.0);
+    }
+
+    #[test]
+    fn test_out_of_scope_seeds() {
+        let scope = ScopeRules::new();
+        scope.add(ScopeRule::new(r"^https://example\.com/private", ScopeAction::Deny).unwrap());
+
+        let mut report = SeedReport::with_scope(scope);
+        report.add_line("https://example.com/", false);
+        report.add_line("https://example.com/private/a", false);
+
+        assert_eq!(report.accepted, vec![Seed::new("https://example.com/".to_string())]);
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].input, "https://example.com/private/a");
+        assert_eq!(report.rejected[0].reason, "out of scope");
+    }
+}
@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use tokio::time::timeout;
+    use spider::crawler::shutdown::{InFlight, Shutdown};
+
+    #[tokio::test]
+    async fn test_shutdown_wakes_waiters() {
+        let shutdown = Shutdown::new();
+        assert!(!shutdown.is_triggered());
+
+        let waiter = {
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move { shutdown.wait().await })
+        };
+
+        shutdown.trigger();
+        assert!(shutdown.is_triggered());
+        timeout(Duration::from_secs(1), waiter).await.expect("waiter was not woken").unwrap();
+
+        // Waiting after the trigger returns immediately.
+        timeout(Duration::from_secs(1), shutdown.wait()).await.expect("wait did not return");
+    }
+
+    #[test]
+    fn test_in_flight_tracking() {
+        let in_flight = InFlight::new();
+
+        let first = in_flight.track("example.com/a", "https://example.com/a", 2.0);
+        let second = in_flight.track("example.com/b", "https://example.com/b", 3.0);
+        assert_eq!(in_flight.len(), 2);
+
+        drop(first);
+        assert_eq!(in_flight.snapshot(), vec![("https://example.com/b".to_string(), 3.0)]);
+
+        drop(second);
+        assert!(in_flight.is_empty());
+    }
+}
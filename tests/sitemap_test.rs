@@ -0,0 +1,143 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::io::Write;
+    use chrono::{Duration, TimeZone, Utc};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use spider::crawler::sitemap::{new_sitemap_roots, parse_lastmod, parse_robots_sitemaps, parse_sitemap, Sitemap, SitemapEntry};
+
+    const URLSET: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+            <url>
+                <loc>https://example.com/</loc>
+                <lastmod>2024-05-01</lastmod>
+                <priority>1.0</priority>
+            </url>
+            <url>
+                <loc> https://example.com/about </loc>
+                <lastmod>2024-05-01T10:30:00+02:00</lastmod>
+            </url>
+            <url><priority>0.3</priority></url>
+        </urlset>"#;
+
+    #[test]
+    fn test_parse_robots_sitemaps() {
+        let robots = "User-agent: *\nDisallow: /private\nSitemap: https://example.com/sitemap_index.xml\nsitemap:https://example.com/news.xml\nSitemap: /relative.xml\n";
+
+        assert_eq!(
+            parse_robots_sitemaps(robots),
+            vec!["https://example.com/sitemap_index.xml", "https://example.com/news.xml"]
+        );
+        assert!(parse_robots_sitemaps("User-agent: *\nDisallow:").is_empty());
+    }
+
+    #[test]
+    fn test_parse_sitemap_index() {
+        let xml = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+                <sitemap><loc>https://example.com/sitemap-posts.xml</loc></sitemap>
+                <sitemap><loc>https://example.com/sitemap-pages.xml.gz</loc><lastmod>2024-01-01</lastmod></sitemap>
+            </sitemapindex>"#;
+
+        assert_eq!(
+            parse_sitemap(xml.as_bytes()),
+            Ok(Sitemap::Index(vec![
+                "https://example.com/sitemap-posts.xml".to_string(),
+                "https://example.com/sitemap-pages.xml.gz".to_string(),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_parse_urlset() {
+        let expected = Sitemap::UrlSet(vec![
+            SitemapEntry {
+                url: "https://example.com/".to_string(),
+                priority: Some(1.0),
+                lastmod: Some(Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap()),
+            },
+            SitemapEntry {
+                url: "https://example.com/about".to_string(),
+                priority: None,
+                lastmod: Some(Utc.with_ymd_and_hms(2024, 5, 1, 8, 30, 0).unwrap()),
+            },
+        ]);
+
+        assert_eq!(parse_sitemap(URLSET.as_bytes()), Ok(expected.clone()));
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(URLSET.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+        assert_eq!(parse_sitemap(&gzipped), Ok(expected));
+    }
+
+    #[test]
+    fn test_parse_text_sitemap() {
+        let text = "https://example.com/a\n\n  https://example.com/b  \nnot a url\nftp://example.com/c\n";
+
+        assert_eq!(
+            parse_sitemap(text.as_bytes()),
+            Ok(Sitemap::UrlSet(vec![
+                SitemapEntry::new("https://example.com/a".to_string()),
+                SitemapEntry::new("https://example.com/b".to_string()),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_sitemap() {
+        assert!(parse_sitemap(b"<html><body>Not found</body></html>").is_err());
+        assert!(parse_sitemap(b"<urlset><url>").is_err());
+        assert!(parse_sitemap(&[0x1f, 0x8b, 0x00]).is_err());
+    }
+
+    #[test]
+    fn test_parse_lastmod() {
+        assert_eq!(parse_lastmod("2024-02-29"), Some(Utc.with_ymd_and_hms(2024, 2, 29, 0, 0, 0).unwrap()));
+        assert_eq!(parse_lastmod("2024-02-29T12:00:00Z"), Some(Utc.with_ymd_and_hms(2024, 2, 29, 12, 0, 0).unwrap()));
+        assert_eq!(parse_lastmod("yesterday"), None);
+    }
+
+    #[test]
+    fn test_sitemap_entry_score() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        let entry = |priority: Option<f64>, age_days: Option<i64>| SitemapEntry {
+            url: "https://example.com/".to_string(),
+            priority,
+            lastmod: age_days.map(|days| now - Duration::days(days)),
+        };
+
+        let important_fresh = entry(Some(1.0), Some(0)).score(now);
+        let important_stale = entry(Some(1.0), Some(400)).score(now);
+        let unimportant_fresh = entry(Some(0.1), Some(0)).score(now);
+        let unknown = entry(None, None).score(now);
+
+        assert_eq!(important_fresh, 1.0);
+        assert!(important_fresh < important_stale);
+        assert!(important_fresh < unimportant_fresh);
+        assert!(unknown > important_fresh && unknown <= 3.0);
+        assert_eq!(entry(Some(7.0), Some(0)).score(now), 1.0, "priority should be clamped");
+    }
+
+    #[test]
+    fn test_new_sitemap_roots() {
+        let mut known = HashSet::new();
+        let seeds = ["https://example.com/a", "http://Example.com/b", "https://blog.example.com/post?id=1", "not a url"];
+
+        assert_eq!(new_sitemap_roots(seeds, &mut known), vec!["https://example.com/", "https://blog.example.com/"]);
+        assert_eq!(new_sitemap_roots(["https://blog.example.com/", "https://other.org/x"], &mut known), vec!["https://other.org/"]);
+    }
+
+    #[test]
+    fn test_sitemap_entry_is_on_host() {
+        assert!(SitemapEntry::new("https://example.com/a".to_string()).is_on_host("example.com"));
+        assert!(SitemapEntry::new("https://EXAMPLE.com/a".to_string()).is_on_host("example.com"));
+        assert!(!SitemapEntry::new("https://cdn.example.com/a".to_string()).is_on_host("example.com"));
+        assert!(!SitemapEntry::new("https://evil.com/a".to_string()).is_on_host("example.com"));
+        assert!(!SitemapEntry::new("/relative".to_string()).is_on_host("example.com"));
+
+        assert!(SitemapEntry::new("https://www.example.com/a".to_string()).is_on_host("example.com"));
+        assert!(SitemapEntry::new("https://example.com/a".to_string()).is_on_host("www.example.com"));
+        assert!(!SitemapEntry::new("https://www.evil.com/a".to_string()).is_on_host("www.example.com"));
+    }
+}
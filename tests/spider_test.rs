@@ -0,0 +1,158 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use spider::{Spider, StopReason};
+    use spider::commands;
+    use spider::config::SpiderConfig;
+    use spider::crawler::content_handler::{ContentHandler, HandledContent};
+    use spider::crawler::events::{CrawlEvent, CrawlEvents};
+    use spider::crawler::fetch::FetchError;
+    use spider::crawler::fetcher::{FetchFailure, FetchFuture, Fetcher};
+    use spider::crawler::get_page_data::PageData;
+    use spider::crawler::robots_directives::RobotsDirectives;
+    use spider::pages::create_page;
+    use spider::crawler::seeds::SeedSource;
+
+    struct OfflineFetcher;
+
+    impl Fetcher for OfflineFetcher {
+        fn fetch<'a>(&'a self, url: &'a str, _accept: &'a (dyn Fn(&str) -> bool + Sync)) -> FetchFuture<'a> {
+            Box::pin(async move { Err(FetchFailure::new("offline", format!("Not fetching {}", url))) })
+        }
+    }
+
+    struct GeoJsonHandler;
+
+    impl ContentHandler for GeoJsonHandler {
+        fn handle(&self, _raw_url: &str, normalized_url: &str, data: &PageData) -> Result<HandledContent, String> {
+            let page = create_page(normalized_url.to_string(), data.body.clone(), data.content_type.clone(), 200);
+            Ok(HandledContent::new(page))
+        }
+    }
+
+    #[test]
+    fn test_build_validates_config() {
+        let mut config = SpiderConfig::default();
+        config.fetcher.timeout_secs = 0;
+
+        let err = Spider::builder().config(config).build().err().expect("invalid config was accepted");
+        assert!(err.to_string().contains("fetcher.timeout_secs"), "{}", err);
+
+        let spider = Spider::builder()
+            .seed(SeedSource::Url("https://example.com/".to_string()))
+            .fetcher(OfflineFetcher)
+            .job("test")
+            .build()
+            .unwrap();
+        assert_eq!(spider.job(), "test");
+        assert_eq!(spider.config().scope.seeds, vec![SeedSource::Url("https://example.com/".to_string())]);
+    }
+
+    #[test]
+    fn test_custom_content_handler() {
+        let spider = Spider::builder()
+            .content_handler("application/geo+json", Arc::new(GeoJsonHandler))
+            .build()
+            .unwrap();
+        let handlers = spider.content_handlers();
+
+        assert!(handlers.accepts("application/geo+json; charset=utf-8"));
+        assert!(handlers.accepts("text/html"));
+        assert!(!handlers.accepts("application/x-unknown"));
+
+        let data = PageData {
+            body: "{}".to_string(),
+            bytes: b"{}".to_vec(),
+            status_code: 200,
+            redirected: false,
+            content_type: "application/geo+json".to_string(),
+            robots: RobotsDirectives::default(),
+        };
+        let handler = handlers.resolve("application/geo+json").expect("handler not registered");
+        let content = handler.handle("https://example.com/map", "example.com/map", &data).unwrap();
+        assert_eq!(content.page.normalized_url, "example.com/map");
+    }
+
+    #[tokio::test]
+    async fn test_controls_emit_events() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = seen.clone();
+        let spider = Spider::builder()
+            .on_event(move |event| recorder.lock().unwrap().push(event.clone()))
+            .build()
+            .unwrap();
+        let mut events = spider.events();
+
+        spider.pause();
+        assert!(spider.is_paused());
+        spider.resume();
+        assert!(!spider.is_paused());
+        spider.clone().stop();
+        assert!(spider.is_stopped());
+
+        assert_eq!(events.next().await, Some(CrawlEvent::Paused));
+        assert_eq!(events.next().await, Some(CrawlEvent::Resumed));
+        assert_eq!(*seen.lock().unwrap(), vec![CrawlEvent::Paused, CrawlEvent::Resumed]);
+    }
+
+    // Needs Redis on localhost; uses database 15 and skips when it is unreachable.
+    #[tokio::test]
+    async fn test_run_completes_on_empty_queue() {
+        let mut config = SpiderConfig::default();
+        config.storage.redis_db = 15;
+        config.scope.feed_poll_interval_secs = 0;
+        config.report.output_dir = String::new();
+
+        let db = match commands::connect(&config).await {
+            Ok(db) => db,
+            Err(e) => {
+                eprintln!("Skipping: Redis unavailable ({:#})", e);
+                return;
+            }
+        };
+        db.lock().await.purge(true).await.unwrap();
+
+        let spider = Spider::builder()
+            .config(config)
+            .storage(db)
+            .seed(SeedSource::Url("https://example.com/".to_string()))
+            .fetcher(OfflineFetcher)
+            .build()
+            .unwrap();
+
+        let outcome = tokio::time::timeout(Duration::from_secs(30), spider.run())
+            .await
+            .expect("run() did not return on an empty queue")
+            .unwrap();
+        assert_eq!(outcome.reason, StopReason::Completed);
+        assert!(outcome.clean);
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_ends_with_emitter() {
+        let events = CrawlEvents::default();
+        let mut stream = events.subscribe();
+
+        events.emit(CrawlEvent::Flushed { pages: 3 });
+        drop(events);
+
+        assert_eq!(stream.next().await, Some(CrawlEvent::Flushed { pages: 3 }));
+        assert_eq!(stream.next().await, None);
+
+        let json = serde_json::to_value(CrawlEvent::Skipped { url: "https://a.com/".to_string(), reason: "scope".to_string() }).unwrap();
+        assert_eq!(json["event"], "skipped");
+        assert_eq!(json["reason"], "scope");
+    }
+
+    #[test]
+    fn test_fetch_failure_from_error() {
+        let failure = FetchFailure::from_error(&FetchError::Status(503));
+        assert_eq!(failure.kind, "http_status");
+        assert_eq!(failure.status, Some(503));
+
+        let failure = FetchFailure::from_error(&FetchError::ContentType("image/png".to_string()));
+        assert_eq!(failure.kind, "content_type");
+        assert_eq!(failure.status, None);
+    }
+}